@@ -0,0 +1,71 @@
+-- Climate biomes: adds 'tundra', 'jungle', 'swamp' and 'ice_sheet' to planet_tiles.tile_type.
+--
+-- SQLite cannot alter a CHECK constraint, so the table is rebuilt under the same name.
+-- Migrations run inside a transaction where PRAGMA foreign_keys cannot be toggled, so foreign
+-- keys are deferred instead: buildings, battles and battle_reports are briefly orphaned until the
+-- rows are copied back, and the tile_influence rows cascaded away by the drop are restored.
+
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TEMP TABLE planet_tiles_backup AS SELECT * FROM planet_tiles;
+CREATE TEMP TABLE tile_influence_backup AS SELECT * FROM tile_influence;
+
+DROP TABLE planet_tiles;
+
+CREATE TABLE planet_tiles (
+  id                       INTEGER  PRIMARY KEY AUTOINCREMENT,
+  planet_id                INTEGER  NOT NULL REFERENCES planets(id),
+  face                     INTEGER  NOT NULL, -- Goldberg face index
+  u                        INTEGER  NOT NULL,
+  v                        INTEGER  NOT NULL,
+
+  -- Terrain
+  tile_type                TEXT     NOT NULL
+                           CHECK(tile_type IN (
+                             'plains','forest','mountain','desert',
+                             'snow','lava','water','ocean',
+                             'tundra','jungle','swamp','ice_sheet'
+                           )),
+  -- Seeded yield quality: 0.0 (poor) to 1.0 (rich).
+  -- Multiplied against building base output to get actual production.
+  yield_quality            REAL     NOT NULL DEFAULT 0.5,
+
+  -- Rare deposit present on this tile (NULL = none).
+  -- Unlocked by tech era — a coal deposit does nothing until industrial era.
+  rare_deposit             TEXT
+                           CHECK(rare_deposit IN (
+                             'coal','iron','gold','gems','petrol','uranium',
+                             'rare_earths','silicon','deuterium','dark_matter',
+                             NULL
+                           )),
+
+  -- Ownership driven by influence recalc — NULL = unclaimed wilderness
+  owner_player_id          INTEGER  REFERENCES players(id),
+
+  -- Dirty flag: set to 1 when a nearby building is created/destroyed/repaired.
+  -- Background job recalculates influence for all flagged tiles.
+  influence_recalc_needed  INTEGER  NOT NULL DEFAULT 0,
+
+  created_at               TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  updated_at               TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+
+  UNIQUE(planet_id, face, u, v)
+);
+
+INSERT INTO planet_tiles (
+  id, planet_id, face, u, v, tile_type, yield_quality, rare_deposit,
+  owner_player_id, influence_recalc_needed, created_at, updated_at
+)
+SELECT
+  id, planet_id, face, u, v, tile_type, yield_quality, rare_deposit,
+  owner_player_id, influence_recalc_needed, created_at, updated_at
+FROM planet_tiles_backup;
+
+-- Restore the influence rows removed by the ON DELETE CASCADE of the drop
+INSERT INTO tile_influence SELECT * FROM tile_influence_backup;
+
+DROP TABLE planet_tiles_backup;
+DROP TABLE tile_influence_backup;
+
+CREATE INDEX idx_tiles_owner        ON planet_tiles(owner_player_id) WHERE owner_player_id IS NOT NULL;
+CREATE INDEX idx_tiles_recalc       ON planet_tiles(influence_recalc_needed) WHERE influence_recalc_needed = 1;
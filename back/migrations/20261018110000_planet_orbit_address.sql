@@ -0,0 +1,8 @@
+-- Moons: planets are addressed inside their system by orbital slot, plus a moon index for
+-- satellites (NULL = the planet itself). Both are regenerated from the system seed, so the
+-- pair identifies a body independently of its seed.
+
+ALTER TABLE planets ADD COLUMN orbit_index INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE planets ADD COLUMN moon_index  INTEGER;
+
+CREATE UNIQUE INDEX idx_planets_orbit ON planets(star_system_id, orbit_index, IFNULL(moon_index, -1));
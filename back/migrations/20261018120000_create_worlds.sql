@@ -0,0 +1,10 @@
+-- One row per universe. The seed and generation version are all procedural generation needs:
+-- a world keeps regenerating the same galaxies, systems and tiles for as long as its stored
+-- generation_version is honoured, even after newer versions change the generators.
+CREATE TABLE worlds (
+  id                  INTEGER  PRIMARY KEY AUTOINCREMENT,
+  seed                INTEGER  NOT NULL UNIQUE,
+  generation_version  INTEGER  NOT NULL,
+  created_at          TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  updated_at          TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+);
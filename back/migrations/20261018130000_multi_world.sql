@@ -0,0 +1,67 @@
+-- Multi-world hosting: worlds are selected by name at server start and carry their ruleset and
+-- start time. Players and galaxies belong to one world, so several universes can share a
+-- database file. Rows created before this migration move to the first world, which becomes
+-- 'main'. A database holding players or galaxies but no world row gets 'main' with the
+-- historical seed 42; a fresh one keeps `worlds` empty and the server creates its world from
+-- the configuration at start.
+
+PRAGMA defer_foreign_keys = ON;
+
+-- 1. Worlds: add name, ruleset and start time; seeds no longer need to be unique
+CREATE TEMP TABLE worlds_backup AS SELECT * FROM worlds;
+DROP TABLE worlds;
+
+CREATE TABLE worlds (
+  id                  INTEGER  PRIMARY KEY AUTOINCREMENT,
+  name                TEXT     NOT NULL UNIQUE,
+  seed                INTEGER  NOT NULL,
+  generation_version  INTEGER  NOT NULL,
+  ruleset             TEXT     NOT NULL DEFAULT 'standard'
+                      CHECK(ruleset IN ('standard','speed','test')),
+  started_at          TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  created_at          TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  updated_at          TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+);
+
+INSERT INTO worlds (id, name, seed, generation_version, started_at, created_at, updated_at)
+SELECT
+  id,
+  CASE WHEN id = (SELECT MIN(id) FROM worlds_backup) THEN 'main' ELSE 'world_' || id END,
+  seed, generation_version, created_at, created_at, updated_at
+FROM worlds_backup;
+
+DROP TABLE worlds_backup;
+
+INSERT INTO worlds (name, seed, generation_version)
+SELECT 'main', 42, 1
+WHERE NOT EXISTS (SELECT 1 FROM worlds)
+  AND (EXISTS (SELECT 1 FROM players) OR EXISTS (SELECT 1 FROM galaxies));
+
+-- 2. Players: a session key plays in exactly one world
+ALTER TABLE players ADD COLUMN world_id INTEGER REFERENCES worlds(id);
+UPDATE players SET world_id = (SELECT MIN(id) FROM worlds);
+
+CREATE INDEX idx_players_world ON players(world_id);
+
+-- 3. Galaxies: coordinates are unique per world. Rebuilt like planet_tiles in
+--    20261018100000; star_systems has no ON DELETE action, so nothing cascades.
+CREATE TEMP TABLE galaxies_backup AS SELECT * FROM galaxies;
+DROP TABLE galaxies;
+
+CREATE TABLE galaxies (
+  id          INTEGER  PRIMARY KEY AUTOINCREMENT,
+  world_id    INTEGER  NOT NULL REFERENCES worlds(id),
+  seed        INTEGER  NOT NULL,
+  x           INTEGER     NOT NULL,
+  y           INTEGER     NOT NULL,
+  z           INTEGER     NOT NULL,
+  created_at  TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  updated_at  TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  UNIQUE(world_id, x, y, z)
+);
+
+INSERT INTO galaxies (id, world_id, seed, x, y, z, created_at, updated_at)
+SELECT id, (SELECT MIN(id) FROM worlds), seed, x, y, z, created_at, updated_at
+FROM galaxies_backup;
+
+DROP TABLE galaxies_backup;
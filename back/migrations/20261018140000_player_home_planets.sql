@@ -0,0 +1,6 @@
+-- Spawn allocation: every player is given a home planet of their own.
+-- The partial unique index is the last line of defence against two sign-ups sharing one.
+
+ALTER TABLE players ADD COLUMN home_planet_id INTEGER REFERENCES planets(id);
+
+CREATE UNIQUE INDEX idx_players_home_planet ON players(home_planet_id) WHERE home_planet_id IS NOT NULL;
@@ -0,0 +1,47 @@
+-- Space object state: asteroids and anomalies are regenerated from the star system seed
+-- (see game/proc_gen/space_object.rs), so only what players did to them is stored here,
+-- keyed by their generator index. Objects spawned at runtime (pirate bases, wrecks, events)
+-- have no generator index and keep storing their own position.
+--
+-- The table has never been written to; it is rebuilt to relax the position columns.
+
+CREATE TEMP TABLE space_objects_backup AS SELECT * FROM space_objects;
+
+DROP TABLE space_objects;
+
+CREATE TABLE space_objects (
+  id              INTEGER  PRIMARY KEY AUTOINCREMENT,
+  star_system_id  INTEGER  NOT NULL REFERENCES star_systems(id),
+  object_type     TEXT     NOT NULL
+                  CHECK(object_type IN ('asteroid','pirate_base','anomaly','wreck','event')),
+
+  -- Index of a generated asteroid or anomaly inside its system (NULL = spawned at runtime)
+  object_index    INTEGER,
+
+  -- Position in AU around the system's primary, for objects spawned at runtime only
+  x               REAL,
+  y               REAL,
+  z               REAL,
+
+  -- Player who claimed the object, and how much of its yield was mined out
+  owner_player_id INTEGER  REFERENCES players(id),
+  extracted       REAL     NOT NULL DEFAULT 0 CHECK(extracted >= 0),
+
+  properties      TEXT,                          -- JSON: hp, loot table, difficulty, etc.
+  spawned_at      TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  despawned_at    TEXT,                          -- NULL = still active
+
+  CHECK((object_index IS NULL) = (x IS NOT NULL)),
+  CHECK(object_index IS NULL OR object_type IN ('asteroid','anomaly')),
+  UNIQUE(star_system_id, object_type, object_index)
+);
+
+INSERT INTO space_objects (
+  id, star_system_id, object_type, x, y, z, properties, spawned_at, despawned_at
+)
+SELECT id, star_system_id, object_type, x, y, z, properties, spawned_at, despawned_at
+FROM space_objects_backup;
+
+DROP TABLE space_objects_backup;
+
+CREATE INDEX idx_space_objects_system ON space_objects(star_system_id);
@@ -0,0 +1,9 @@
+-- Scheduler: the background worker picks due rows by time (see worker/runner.rs).
+--   move_orders.arrival_time        Unix epoch milliseconds, like start_time
+--   buildings.construction_done_at  stored timestamp, NULL once built
+--   battles                         a round every minute after COALESCE(last_tick_at, started_at)
+-- Claiming deletes the order, clears construction_done_at or moves last_tick_at in the same
+-- transaction as the event's effects, so an event is never applied twice.
+
+CREATE INDEX idx_buildings_construction ON buildings(construction_done_at)
+  WHERE construction_done_at IS NOT NULL;
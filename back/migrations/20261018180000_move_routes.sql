@@ -0,0 +1,24 @@
+-- Surface routes: a walk is stored as one tile_walk move order per tile crossed (a leg), each
+-- with its own start and arrival time, so the unit really stands on every tile along the way
+-- and can be caught there. The route remembers where the walk started, to lead the unit back
+-- when the order is cancelled.
+
+CREATE TABLE move_routes (
+  id            INTEGER  PRIMARY KEY AUTOINCREMENT,
+  unit_id       INTEGER  NOT NULL REFERENCES units(id),
+  planet_id     INTEGER  NOT NULL REFERENCES planets(id),
+
+  origin_face   INTEGER  NOT NULL,
+  origin_u      INTEGER  NOT NULL,
+  origin_v      INTEGER  NOT NULL,
+
+  created_at    TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+);
+
+CREATE INDEX idx_move_routes_unit ON move_routes(unit_id);
+
+-- Legs of a route, in walking order (NULL for single-step orders)
+ALTER TABLE move_orders ADD COLUMN route_id  INTEGER REFERENCES move_routes(id);
+ALTER TABLE move_orders ADD COLUMN leg_index INTEGER;
+
+CREATE INDEX idx_move_orders_route ON move_orders(route_id) WHERE route_id IS NOT NULL;
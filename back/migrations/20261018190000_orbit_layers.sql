@@ -0,0 +1,79 @@
+-- Orbit layers (design doc §6.4): a unit in orbit is either in low orbit, where troops land
+-- and planetary defences reach, or in high orbit, the way in and out of the planet's space.
+-- Units only move one layer at a time (see game/flight.rs):
+--   planet_surface <-> low orbit <-> high orbit <-> in_space
+
+ALTER TABLE units ADD COLUMN orbit_layer TEXT CHECK(orbit_layer IN ('low','high'));
+
+-- Nothing could reach orbit before, but be safe: anything already there sits in low orbit
+UPDATE units SET orbit_layer = 'low' WHERE location_mode = 'in_orbit';
+
+-- move_orders is rebuilt to add the orbit changes between layers to its move types
+
+CREATE TEMP TABLE move_orders_backup AS SELECT * FROM move_orders;
+
+DROP TABLE move_orders;
+
+CREATE TABLE move_orders (
+  id INTEGER PRIMARY KEY,
+
+  -- Exactly one of these must be set
+
+  unit_id         INTEGER  REFERENCES units(id),
+  building_id     INTEGER  REFERENCES buildings(id),
+  mover_type      TEXT     NOT NULL CHECK(mover_type IN ('unit','building')),
+
+  move_type       TEXT     NOT NULL CHECK(move_type IN (
+                    'tile_walk',
+                    'launch_to_orbit',   -- surface -> low orbit
+                    'raise_orbit',       -- low orbit -> high orbit
+                    'orbit_to_space',    -- high orbit -> space
+                    'space_travel',
+                    'enter_orbit',       -- space -> high orbit
+                    'lower_orbit',       -- high orbit -> low orbit
+                    'land',              -- low orbit -> surface
+                    'building_liftoff',
+                    'building_land',
+                    'loot_and_retreat'   -- triggers loot then auto-generates retreat move_order
+                  )),
+  -- Surface origin
+  from_planet_id   INTEGER  REFERENCES planets(id),
+  from_planet_face INTEGER,
+  from_planet_u    INTEGER,
+  from_planet_v    INTEGER,
+
+  -- Surface destination
+  to_planet_id     INTEGER  REFERENCES planets(id),
+  to_planet_face   INTEGER,
+  to_planet_u      INTEGER,
+  to_planet_v      INTEGER,
+
+  -- Space origin / destination (for space_travel)
+  from_star_system_id  INTEGER  REFERENCES star_systems(id),
+  from_space_x         REAL,
+  from_space_y         REAL,
+  from_space_z         REAL,
+
+  to_star_system_id    INTEGER  REFERENCES star_systems(id),
+  to_space_x           REAL,
+  to_space_y           REAL,
+  to_space_z           REAL,
+
+  start_time INTEGER NOT NULL,   -- Unix epoch milliseconds
+  arrival_time INTEGER NOT NULL,
+
+  -- Legs of a surface route, in walking order (NULL for single-step orders)
+  route_id  INTEGER  REFERENCES move_routes(id),
+  leg_index INTEGER,
+
+  FOREIGN KEY(unit_id) REFERENCES units(id)
+);
+
+INSERT INTO move_orders SELECT * FROM move_orders_backup;
+
+DROP TABLE move_orders_backup;
+
+CREATE INDEX idx_move_orders_unit     ON move_orders(unit_id)     WHERE unit_id IS NOT NULL;
+CREATE INDEX idx_move_orders_building ON move_orders(building_id) WHERE building_id IS NOT NULL;
+CREATE INDEX idx_move_orders_arrival  ON move_orders(arrival_time);
+CREATE INDEX idx_move_orders_route    ON move_orders(route_id)    WHERE route_id IS NOT NULL;
@@ -0,0 +1,35 @@
+-- Asteroid mining pays into the player's pool. Asteroids yield every mineable resource of
+-- game/resource.rs, so the pool is rebuilt to also accept gold and gems.
+
+CREATE TEMP TABLE player_resources_backup AS SELECT * FROM player_resources;
+
+DROP TABLE player_resources;
+
+CREATE TABLE player_resources (
+  player_id      INTEGER  NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+  resource_type  TEXT     NOT NULL
+                 CHECK(resource_type IN (
+                   -- Stone age
+                   'wood','stone','food','water',
+                   -- Industrial
+                   'coal','iron','petrol','copper','gold','gems',
+                   -- Modern
+                   'silicon','uranium','rare_earths','electricity',
+                   -- Space
+                   'deuterium','dark_matter','titanium','antimatter'
+                 )),
+  amount         REAL     NOT NULL DEFAULT 0,
+  -- Hard cap — enforced by game logic, not DB constraint.
+  -- Determined by sum of player's storage buildings.
+  cap            REAL     NOT NULL DEFAULT 1000,
+  updated_at     TEXT     NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  PRIMARY KEY (player_id, resource_type)
+);
+
+INSERT INTO player_resources (player_id, resource_type, amount, cap, updated_at)
+SELECT player_id, resource_type, amount, cap, updated_at
+FROM player_resources_backup;
+
+DROP TABLE player_resources_backup;
+
+CREATE INDEX idx_player_resources_player ON player_resources(player_id);
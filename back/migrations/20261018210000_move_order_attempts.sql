@@ -0,0 +1,4 @@
+-- Failed applications of a move order. The worker postpones an order that fails and drops it,
+-- with the rest of its route, once it failed too often (see worker/runner.rs).
+
+ALTER TABLE move_orders ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- New players start with deuterium for their scout's first flights (STARTING_DEUTERIUM in
+-- repositories/player_state_repo.rs). Players who spawned before get the same amount.
+
+INSERT INTO player_resources (player_id, resource_type, amount)
+SELECT id, 'deuterium', 200
+FROM players
+WHERE home_planet_id IS NOT NULL
+ON CONFLICT(player_id, resource_type) DO NOTHING;
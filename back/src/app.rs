@@ -1,15 +1,25 @@
 // use axum::extract::FromRef;
 use std::sync::Arc;
 
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 // use chrono::Utc;
 use sqlx::SqlitePool;
 
 use anyhow::Result;
-use tokio::sync::broadcast;
 use tracing_subscriber::FmtSubscriber;
 
+use crate::config::Config;
+use crate::game::proc_gen::seed::CURRENT_GENERATION_VERSION;
+use crate::game::start_area::FairnessBand;
+use crate::game::world::{Ruleset, World};
 use crate::handlers;
+use crate::repositories::worlds_repo;
+use crate::services::events::EventHub;
+use crate::services::universe::cache::UniverseCache;
+use crate::worker::runner::{self, Scheduler};
 
 // #[derive(Clone)]
 // pub enum DbPool {
@@ -27,7 +37,15 @@ pub type DbPool = sqlx::PgPool;
 #[cfg(feature = "local_mode")]
 pub struct AppState {
     pub db: sqlx::SqlitePool,
-    pub notify: broadcast::Sender<String>,
+    // Game events pushed to the players they concern
+    pub events: EventHub,
+    // The universe served by this process
+    pub world: World,
+    pub universe_cache: UniverseCache,
+    // Accepted quality range of new players' starting areas
+    pub spawn_band: FairnessBand,
+    // Wakes the background worker when something new is scheduled
+    pub scheduler: Scheduler,
 }
 
 #[cfg(feature = "production_mode")]
@@ -50,8 +68,8 @@ pub fn init_tracing() -> Result<()> {
     Ok(())
 }
 
-pub async fn init_state() -> anyhow::Result<Arc<AppState>> {
-    let db = SqlitePool::connect("sqlite://./game.db?mode=rwc").await?;
+pub async fn init_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
+    let db = SqlitePool::connect(&config.database_url).await?;
 
     // Set WAL mode immediately after connecting
     sqlx::query("PRAGMA journal_mode = WAL;")
@@ -60,29 +78,65 @@ pub async fn init_state() -> anyhow::Result<Arc<AppState>> {
 
     sqlx::migrate!("./migrations").run(&db).await?;
 
-    let (tx, _) = broadcast::channel(128);
-
-    Ok(Arc::new(AppState { db, notify: tx }))
+    let world = load_world(&db, config).await?;
+    tracing::info!(
+        "serving world '{}' (seed {}, generation v{}, {} ruleset)",
+        world.name,
+        world.seed,
+        world.generation_version,
+        world.ruleset.as_str()
+    );
+
+    Ok(Arc::new(AppState {
+        db,
+        events: EventHub::new(),
+        world,
+        universe_cache: UniverseCache::new(),
+        spawn_band: config.spawn_band,
+        scheduler: Scheduler::new(),
+    }))
 }
 
-// pub fn spawn_worker(state: Arc<AppState>) {
-//     tokio::spawn(async move {
-//         if let Err(e) = run_worker(state).await {
-//             tracing::error!("worker failed: {:?}", e);
-//         }
-//     });
-// }
-
-// // Background worker that checks move_orders and resolves arrivals
-// async fn run_worker(state: Arc<AppState>) {
-//     tracing::info!("worker started");
-//     let db = state.db.clone();
-//     let tx = state.notify.clone();
-
-//     loop {
+/// Loads the configured world, creating it on the current generation version the first time.
+async fn load_world(db: &SqlitePool, config: &Config) -> anyhow::Result<World> {
+    if let Some(row) = worlds_repo::fetch_world_by_name(db, &config.world).await? {
+        let world = World::try_from(row)?;
+
+        // Refuse to silently serve a different universe than the one asked for
+        if let Some(seed) = config.seed
+            && seed != world.seed
+        {
+            anyhow::bail!(
+                "world '{}' already exists with seed {}, not {}",
+                world.name,
+                world.seed,
+                seed
+            );
+        }
+
+        return Ok(world);
+    }
+
+    let seed = config
+        .seed
+        .unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+    let ruleset = config.ruleset.unwrap_or(Ruleset::Standard);
+
+    let row = worlds_repo::create_world(
+        db,
+        &config.world,
+        seed,
+        CURRENT_GENERATION_VERSION,
+        ruleset.as_str(),
+    )
+    .await?;
+    World::try_from(row)
+}
 
-//     }
-// }
+/// Starts the background worker resolving move orders, constructions and battle rounds.
+pub fn spawn_worker(state: Arc<AppState>) {
+    tokio::spawn(runner::run(state));
+}
 
 pub fn router(state: Arc<AppState>) -> Router {
     Router::new()
@@ -91,8 +145,47 @@ pub fn router(state: Arc<AppState>) -> Router {
         // .route("/auth/login",    post(handlers::auth::login))
         // Game
         .route("/api/state", get(handlers::state::get_game_state))
+        .route("/api/events", get(handlers::events::stream_events))
+        .route(
+            "/api/players/{id}/messages",
+            post(handlers::events::send_message),
+        )
+        .route("/api/planets/{id}", get(handlers::planet::get_planet))
+        .route(
+            "/api/units/{id}/move",
+            post(handlers::unit::move_unit).delete(handlers::unit::cancel_move),
+        )
+        .route("/api/units/{id}/flight", post(handlers::unit::order_flight))
+        .route("/api/units/{id}/travel", post(handlers::unit::order_travel))
+        .route(
+            "/api/units/{id}/position",
+            get(handlers::unit::get_position),
+        )
+        .route(
+            "/api/units/{id}/extract",
+            post(handlers::unit::extract_asteroid),
+        )
+        .route(
+            "/api/universe/chunks/{cx}/{cy}/{cz}",
+            get(handlers::universe::get_galaxy_chunk),
+        )
+        .route(
+            "/api/universe/galaxies/{gx}/{gy}/{gz}/chunks/{cx}/{cy}/{cz}",
+            get(handlers::universe::get_star_system_chunk),
+        )
+        .route(
+            "/api/universe/galaxies/{gx}/{gy}/{gz}/systems/nearby",
+            get(handlers::universe::get_nearby_star_systems),
+        )
+        .route(
+            "/api/universe/galaxies/{gx}/{gy}/{gz}/systems/{sx}/{sy}/{sz}",
+            get(handlers::universe::get_star_system),
+        )
+        .route(
+            "/api/universe/galaxies/{gx}/{gy}/{gz}/systems/{sx}/{sy}/{sz}/objects",
+            get(handlers::universe::get_system_space_objects),
+        )
         // .route("/api/state/:player_id", get(api::state::get_state))
         // .route("/api/move", post(api::move_unit::handler))
-        // .route("/api/events", get(api::events::handler))
         .with_state(state)
 }
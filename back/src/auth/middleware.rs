@@ -1,13 +1,22 @@
 use crate::app::AppState;
 use axum::{
-    extract::{FromRef, FromRequestParts},
-    http::{StatusCode, request::Parts},
+    extract::{FromRef, FromRequestParts, Query},
+    http::{Method, StatusCode, request::Parts},
 };
+use serde::Deserialize;
 use std::sync::Arc;
 
 // ALWAYS compiled: This guarantees AuthPlayer is a local type under all flag variations.
 pub struct AuthPlayer(pub i64);
 
+// The only route a session key may come in the query string of, where it also ends up in logs
+const EVENT_STREAM_PATH: &str = "/api/events";
+
+#[derive(Deserialize)]
+struct SessionKeyQuery {
+    session_key: Option<String>,
+}
+
 // ==========================================
 // LOCAL BUILD ONLY: SQLite + Session Key
 // ==========================================
@@ -23,44 +32,63 @@ where
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let app_state = Arc::<AppState>::from_ref(state);
 
-        // 1. Get the key the TypeScript app sent
+        // 1. Get the key the TypeScript app sent. A browser EventSource cannot set headers: the
+        //    event stream passes it as ?session_key= instead
         let session_key = parts
             .headers
             .get("X-Session-Key")
             .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+            .or_else(|| {
+                if parts.method != Method::GET || parts.uri.path() != EVENT_STREAM_PATH {
+                    return None;
+                }
+                Query::<SessionKeyQuery>::try_from_uri(&parts.uri)
+                    .ok()
+                    .and_then(|query| query.0.session_key)
+            })
             .ok_or_else(|| {
                 (
                     StatusCode::UNAUTHORIZED,
                     "Missing X-Session-Key".to_string(),
                 )
             })?;
+        let session_key = session_key.as_str();
 
         // 2. FAST PATH: Check if they are already in the database (99% of requests)
-        let existing: Option<(i64,)> =
-            sqlx::query_as("SELECT id FROM players WHERE session_key = ?")
+        let existing: Option<(i64, Option<i64>)> =
+            sqlx::query_as("SELECT id, world_id FROM players WHERE session_key = ?")
                 .bind(session_key)
                 .fetch_optional(&app_state.db)
                 .await
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-        if let Some(row) = existing {
-            return Ok(AuthPlayer(row.0)); // Found them! Return their ID.
+        if let Some((player_id, world_id)) = existing {
+            // A session key plays in one world only
+            if world_id != Some(app_state.world.id) {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    "Session belongs to another world".to_string(),
+                ));
+            }
+            return Ok(AuthPlayer(player_id)); // Found them! Return their ID.
         }
 
         // 3. SLOW PATH: This is their very first connection. Automatically create them.
-        let username_len = std::cmp::min(6, session_key.len());
-        let default_username = format!("Player_{}", &session_key[..username_len]);
+        let default_username =
+            format!("Player_{}", session_key.chars().take(6).collect::<String>());
 
         let player_id: i64 = sqlx::query_scalar(
             r#"
-            INSERT INTO players (session_key, username)
-            VALUES (?, ?)
+            INSERT INTO players (session_key, username, world_id)
+            VALUES (?, ?, ?)
             ON CONFLICT(session_key) DO UPDATE SET session_key = excluded.session_key
             RETURNING id
             "#,
         )
         .bind(session_key)
         .bind(default_username)
+        .bind(app_state.world.id)
         .fetch_one(&app_state.db)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
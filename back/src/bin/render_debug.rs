@@ -0,0 +1,476 @@
+//! Renders generator output to PNG to eyeball it: a planet's tile map, or a slice of a galaxy's
+//! star system density.
+//!
+//! ```text
+//! cargo run --release --features debug_render --bin render-debug -- planet \
+//!     --seed 1234 --galaxy -1,0,0 --system 12,-3,1 --orbit 2 --layer biome --out planet.png
+//! cargo run --release --features debug_render --bin render-debug -- galaxy \
+//!     --seed 1234 --galaxy -1,0,0 --plane disk --out galaxy.png
+//! ```
+//!
+//! Common flags: `--seed` (world seed, default 0), `--generation-version` (generator version of
+//! the world, default the current one), `--galaxy` (galaxy grid position, default 0,0,0),
+//! `--size` (image width in pixels, default 1024) and `--out` (file, default `<command>.png`).
+//! Asking for a galaxy or star system that does not exist reports the nearest one.
+//!
+//! `planet` draws an equirectangular map, north up, each pixel coloured by the tile nearest to
+//! it. Flags: `--system` (star system grid position, required), `--orbit` (orbit index,
+//! required), `--moon` (moon index, default none) and `--layer` (`biome`, `elevation` or
+//! `deposit`, default biome).
+//!
+//! `galaxy` draws the density on a log scale from the spawn threshold (black) to 1.0 (white).
+//! Flags: `--plane` (`disk` for the galaxy's own plane seen face-on, or one of the grid planes
+//! `xy`, `xz`, `yz`; default disk), `--at` (offset of the slice from the core along the plane's
+//! normal, in cells, default 0), `--extent` (cells from the core to the image edge, default the
+//! galaxy radius) and `--systems` (`true` marks cells that spawn a star system in green; only
+//! readable once a pixel covers at most one cell).
+
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use axosm::game::proc_gen::galaxy::{Galaxy, MIN_SPAWN_DENSITY, compute_star_system_density};
+use axosm::game::proc_gen::goldberg::GoldbergTopology;
+use axosm::game::proc_gen::nearby::nearest_star_systems;
+use axosm::game::proc_gen::planet::Planet;
+use axosm::game::proc_gen::seed::{CURRENT_GENERATION_VERSION, RootSeed, check_generation_version};
+use axosm::game::proc_gen::star_system::StarSystem;
+use axosm::game::proc_gen::tile::{DynamicTileProperties, TileType};
+use axosm::game::proc_gen::universe::should_spawn_galaxy;
+use axosm::game::resource::Resource;
+use axosm::maths::spiral_3d::Spiral3D;
+
+type Cell = (i32, i32, i32);
+type Rgb = [u8; 3];
+
+// Deposit layer: tiles without a deposit keep this fraction of their biome colour
+const DEPOSIT_BACKGROUND_DIM: f32 = 0.3;
+const SPAWNED_SYSTEM_COLOR: Rgb = [40, 220, 90];
+// How far to look for the nearest galaxy or star system when the requested one does not exist
+const NEAREST_GALAXY_SEARCH_RADIUS: i32 = 8;
+const NEAREST_SYSTEM_SEARCH_RADIUS: f32 = 64.0;
+
+#[derive(Debug)]
+enum Command {
+    Planet {
+        system: Cell,
+        orbit: u32,
+        moon: Option<u32>,
+        layer: Layer,
+    },
+    Galaxy {
+        plane: Plane,
+        at: f32,
+        extent: Option<f32>,
+        systems: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Layer {
+    Biome,
+    Elevation,
+    Deposit,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Plane {
+    Disk,
+    Xy,
+    Xz,
+    Yz,
+}
+
+#[derive(Debug)]
+struct Options {
+    seed: u64,
+    generation_version: u32,
+    galaxy: Cell,
+    size: u32,
+    out: String,
+    command: Command,
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let name = args
+            .next()
+            .context("missing command, expected 'planet' or 'galaxy'")?;
+
+        let mut seed = 0;
+        let mut generation_version = CURRENT_GENERATION_VERSION;
+        let mut galaxy = (0, 0, 0);
+        let mut size = 1024;
+        let mut out = None;
+        // planet
+        let mut system = None;
+        let mut orbit = None;
+        let mut moon = None;
+        let mut layer = Layer::Biome;
+        // galaxy
+        let mut plane = Plane::Disk;
+        let mut at = 0.0;
+        let mut extent = None;
+        let mut systems = false;
+
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .with_context(|| format!("missing value for {flag}"))?;
+            match (name.as_str(), flag.as_str()) {
+                (_, "--seed") => seed = value.parse().context("invalid seed")?,
+                (_, "--generation-version") => {
+                    generation_version = value.parse().context("invalid generation version")?
+                }
+                (_, "--galaxy") => galaxy = parse_cell(&value).context("invalid galaxy")?,
+                (_, "--size") => size = value.parse().context("invalid size")?,
+                (_, "--out") => out = Some(value),
+                ("planet", "--system") => {
+                    system = Some(parse_cell(&value).context("invalid system")?)
+                }
+                ("planet", "--orbit") => orbit = Some(value.parse().context("invalid orbit")?),
+                ("planet", "--moon") => moon = Some(value.parse().context("invalid moon")?),
+                ("planet", "--layer") => {
+                    layer = match value.as_str() {
+                        "biome" => Layer::Biome,
+                        "elevation" => Layer::Elevation,
+                        "deposit" => Layer::Deposit,
+                        other => bail!("unknown layer '{other}'"),
+                    }
+                }
+                ("galaxy", "--plane") => {
+                    plane = match value.as_str() {
+                        "disk" => Plane::Disk,
+                        "xy" => Plane::Xy,
+                        "xz" => Plane::Xz,
+                        "yz" => Plane::Yz,
+                        other => bail!("unknown plane '{other}'"),
+                    }
+                }
+                ("galaxy", "--at") => at = value.parse().context("invalid slice offset")?,
+                ("galaxy", "--extent") => extent = Some(value.parse().context("invalid extent")?),
+                ("galaxy", "--systems") => systems = value.parse().context("invalid --systems")?,
+                (_, other) => bail!("unknown argument '{other}' for {name}"),
+            }
+        }
+
+        let command = match name.as_str() {
+            "planet" => Command::Planet {
+                system: system.context("--system is required")?,
+                orbit: orbit.context("--orbit is required")?,
+                moon,
+                layer,
+            },
+            "galaxy" => Command::Galaxy {
+                plane,
+                at,
+                extent,
+                systems,
+            },
+            other => bail!("unknown command '{other}', expected 'planet' or 'galaxy'"),
+        };
+        if size == 0 {
+            bail!("--size must be positive");
+        }
+        check_generation_version(generation_version)?;
+
+        Ok(Self {
+            seed,
+            generation_version,
+            galaxy,
+            size,
+            out: out.unwrap_or_else(|| format!("{name}.png")),
+            command,
+        })
+    }
+}
+
+/// `x,y,z` grid coordinates.
+fn parse_cell(value: &str) -> Result<Cell> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    match parts[..] {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(anyhow!("expected x,y,z, got '{value}'")),
+    }
+}
+
+/// RGB pixels, row by row from the top left.
+struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: u32, height: u32, pixel: impl FnMut(u32, u32) -> Rgb) -> Self {
+        let mut pixel = pixel;
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 3);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend(pixel(x, y));
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn save(&self, path: &str) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("creating {path}"))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()?
+            .write_image_data(&self.pixels)
+            .with_context(|| format!("writing {path}"))
+    }
+}
+
+fn main() -> Result<()> {
+    let options = Options::from_args(env::args().skip(1))?;
+    let root_seed = RootSeed::new(options.seed, options.generation_version);
+
+    if !should_spawn_galaxy(root_seed, options.galaxy) {
+        let (x, y, z) = options.galaxy;
+        let nearest = Spiral3D::new(x, y, z)
+            .take_while(|&(gx, gy, gz)| {
+                (gx - x).abs().max((gy - y).abs()).max((gz - z).abs())
+                    <= NEAREST_GALAXY_SEARCH_RADIUS
+            })
+            .find(|&position| should_spawn_galaxy(root_seed, position));
+        match nearest {
+            Some(nearest) => bail!(
+                "no galaxy at {:?} for seed {}, nearest is at {nearest:?}",
+                options.galaxy,
+                options.seed
+            ),
+            None => bail!(
+                "no galaxy within {NEAREST_GALAXY_SEARCH_RADIUS} cells of {:?} for seed {}",
+                options.galaxy,
+                options.seed
+            ),
+        }
+    }
+    let galaxy = Galaxy::new(root_seed, options.galaxy);
+
+    let image = match options.command {
+        Command::Planet {
+            system,
+            orbit,
+            moon,
+            layer,
+        } => {
+            if !galaxy.should_spawn_star_system(system) {
+                match nearest_star_systems(&galaxy, system, NEAREST_SYSTEM_SEARCH_RADIUS, 1).first()
+                {
+                    Some(nearest) => bail!(
+                        "no star system at {system:?} in galaxy {:?}, nearest is at {:?}",
+                        options.galaxy,
+                        nearest.position
+                    ),
+                    None => bail!(
+                        "no star system within {NEAREST_SYSTEM_SEARCH_RADIUS} cells of {system:?} in galaxy {:?}",
+                        options.galaxy
+                    ),
+                }
+            }
+            let system = StarSystem::new(&galaxy, system);
+            let planet = Planet::from_address(0, &system, orbit, moon)
+                .with_context(|| format!("no body at orbit {orbit}, moon {moon:?}"))?;
+            render_planet(&planet, layer, options.size)
+        }
+        Command::Galaxy {
+            plane,
+            at,
+            extent,
+            systems,
+        } => render_galaxy(&galaxy, plane, at, extent, systems, options.size),
+    };
+
+    image.save(&options.out)?;
+    println!("wrote {}x{} {}", image.width, image.height, options.out);
+    Ok(())
+}
+
+/// Equirectangular map: longitude -180° to 180° left to right, latitude 90° to -90° top to
+/// bottom, the planet's polar axis being z as in `calculate_tile_properties`.
+fn render_planet(planet: &Planet, layer: Layer, width: u32) -> Image {
+    let topology = planet.topology();
+    let height = (width / 2).max(1);
+
+    // Tiles are generated on first use; consecutive pixels mostly share a tile
+    let mut tiles: Vec<Option<DynamicTileProperties>> = vec![None; topology.tile_count() as usize];
+    let mut nearest = 0;
+
+    Image::new(width, height, |x, y| {
+        let longitude = ((x as f32 + 0.5) / width as f32 * 2.0 - 1.0) * std::f32::consts::PI;
+        let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * std::f32::consts::PI;
+        let point = [
+            latitude.cos() * longitude.cos(),
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+        ];
+
+        nearest = nearest_tile(&topology, point, nearest);
+        let tile = tiles[nearest as usize].get_or_insert_with(|| planet.query_tile_at(nearest));
+        tile_color(planet, tile, layer)
+    })
+}
+
+/// Tile whose center is closest to `point`, walking the tile graph from `start`: tiles are the
+/// Voronoi cells of their centers, so a greedy walk cannot get stuck before reaching it.
+fn nearest_tile(topology: &GoldbergTopology, point: [f32; 3], start: u32) -> u32 {
+    let closeness = |index: u32| {
+        let center = topology.position(index);
+        (0..3).map(|axis| center[axis] * point[axis]).sum::<f32>()
+    };
+
+    let mut current = start;
+    let mut best = closeness(current);
+    loop {
+        let next = topology
+            .neighbors(current)
+            .iter()
+            .map(|&neighbor| (neighbor, closeness(neighbor)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match next {
+            Some((neighbor, value)) if value > best => {
+                current = neighbor;
+                best = value;
+            }
+            _ => return current,
+        }
+    }
+}
+
+fn tile_color(planet: &Planet, tile: &DynamicTileProperties, layer: Layer) -> Rgb {
+    match layer {
+        Layer::Biome => biome_color(tile.tile_type),
+        Layer::Elevation => {
+            // Blue below sea level, grey ramp above it
+            let sea_level = planet.climate.sea_level;
+            if tile.elevation < sea_level {
+                let depth = tile.elevation / sea_level.max(f32::EPSILON);
+                [0, (40.0 * depth) as u8, (60.0 + 160.0 * depth) as u8]
+            } else {
+                let height = (tile.elevation - sea_level) / (1.0 - sea_level).max(f32::EPSILON);
+                let grey = (60.0 + 195.0 * height.clamp(0.0, 1.0)) as u8;
+                [grey, grey, grey]
+            }
+        }
+        Layer::Deposit => match tile.rare_deposit {
+            // Richer deposits are drawn brighter
+            Some(deposit) => scale(
+                resource_color(deposit.resource),
+                0.4 + 0.2 * deposit.richness.level() as f32,
+            ),
+            None => scale(biome_color(tile.tile_type), DEPOSIT_BACKGROUND_DIM),
+        },
+    }
+}
+
+fn biome_color(tile_type: TileType) -> Rgb {
+    match tile_type {
+        TileType::Plains => [140, 190, 90],
+        TileType::Forest => [35, 110, 50],
+        TileType::Mountain => [130, 115, 100],
+        TileType::Desert => [225, 200, 130],
+        TileType::Snow => [240, 245, 250],
+        TileType::Lava => [200, 60, 20],
+        TileType::Water => [70, 130, 200],
+        TileType::Ocean => [20, 50, 120],
+        TileType::Tundra => [150, 160, 140],
+        TileType::Jungle => [20, 140, 60],
+        TileType::Swamp => [80, 100, 70],
+        TileType::IceSheet => [200, 225, 240],
+    }
+}
+
+fn resource_color(resource: Resource) -> Rgb {
+    match resource {
+        Resource::Coal => [90, 90, 90],
+        Resource::Iron => [190, 90, 60],
+        Resource::Gold => [255, 210, 40],
+        Resource::Gems => [230, 60, 200],
+        Resource::Petrol => [150, 110, 60],
+        Resource::Uranium => [120, 255, 60],
+        Resource::RareEarths => [60, 220, 220],
+        Resource::Silicon => [200, 200, 255],
+        Resource::Titanium => [240, 240, 240],
+        Resource::Deuterium => [60, 120, 255],
+        Resource::DarkMatter => [150, 60, 255],
+    }
+}
+
+fn scale([r, g, b]: Rgb, factor: f32) -> Rgb {
+    [r, g, b].map(|channel| (channel as f32 * factor).clamp(0.0, 255.0) as u8)
+}
+
+/// Square slice centred on the galaxy's core, `extent` cells from the core to each edge, with
+/// the slice's first axis to the right and its second axis up.
+fn render_galaxy(
+    galaxy: &Galaxy,
+    plane: Plane,
+    at: f32,
+    extent: Option<f32>,
+    systems: bool,
+    size: u32,
+) -> Image {
+    let structure = &galaxy.structure;
+    let extent = extent.unwrap_or(structure.radius);
+
+    Image::new(size, size, |x, y| {
+        let u = ((x as f32 + 0.5) / size as f32 * 2.0 - 1.0) * extent;
+        let v = (1.0 - (y as f32 + 0.5) / size as f32 * 2.0) * extent;
+        let cell = match plane {
+            Plane::Disk => structure.grid_position([
+                u / structure.radius,
+                v / structure.radius,
+                at / structure.radius,
+            ]),
+            Plane::Xy => grid_cell(u, v, at),
+            Plane::Xz => grid_cell(u, at, v),
+            Plane::Yz => grid_cell(at, u, v),
+        };
+
+        if systems && galaxy.should_spawn_star_system(cell) {
+            return SPAWNED_SYSTEM_COLOR;
+        }
+        density_color(compute_star_system_density(galaxy, cell))
+    })
+}
+
+fn grid_cell(x: f32, y: f32, z: f32) -> Cell {
+    (x.round() as i32, y.round() as i32, z.round() as i32)
+}
+
+/// Log scale: black at the spawn threshold and below, white at 1.0, through deep blue and
+/// orange so the arms and the bulge stand apart.
+fn density_color(density: f32) -> Rgb {
+    if density <= MIN_SPAWN_DENSITY {
+        return [0, 0, 0];
+    }
+    let t = (density.log10() / MIN_SPAWN_DENSITY.log10()).clamp(0.0, 1.0);
+    let t = 1.0 - t;
+
+    let ramp = |from: Rgb, to: Rgb, t: f32| {
+        let mut color = [0; 3];
+        for channel in 0..3 {
+            color[channel] =
+                (from[channel] as f32 + (to[channel] as f32 - from[channel] as f32) * t) as u8;
+        }
+        color
+    };
+    match t {
+        t if t < 0.5 => ramp([10, 10, 60], [200, 90, 30], t * 2.0),
+        t => ramp([200, 90, 30], [255, 255, 255], (t - 0.5) * 2.0),
+    }
+}
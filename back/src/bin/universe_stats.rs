@@ -0,0 +1,501 @@
+//! Samples a universe and reports the distributions the generator's magic numbers produce, so
+//! balance changes can be compared before they ship:
+//!
+//! ```text
+//! cargo run --release --bin universe-stats -- --seed 1234 --galaxies 50 --format csv --out stats.csv
+//! ```
+//!
+//! Flags: `--seed` (world seed, default 0), `--generation-version` (generator version of the
+//! world, default the current one), `--galaxies` (galaxies sampled, default 40),
+//! `--systems` (star systems sampled per galaxy, default 50), `--planets` (planets and moons
+//! whose every tile is surveyed, default 60), `--players` (successive spawn searches, default 8),
+//! `--format` (`json` or `csv`, default json) and `--out` (file, default stdout).
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::time::Instant;
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+
+use axosm::game::game_init::{SpawnExclusions, find_starting_location};
+use axosm::game::proc_gen::chunk::{ChunkCoord, galaxies_in_chunk};
+use axosm::game::proc_gen::galaxy::{Galaxy, GalaxyType};
+use axosm::game::proc_gen::planet::{Planet, PlanetType};
+use axosm::game::proc_gen::seed::{CURRENT_GENERATION_VERSION, RootSeed, check_generation_version};
+use axosm::game::proc_gen::star_system::{BodyType, StarSystem, StarType};
+use axosm::game::proc_gen::tile::TileType;
+use axosm::game::resource::Resource;
+use axosm::game::start_area::FairnessBand;
+use axosm::maths::spiral_3d::Spiral3D;
+
+// Random cells tried per star system wanted before giving up on a sparse galaxy
+const ATTEMPTS_PER_SYSTEM: u32 = 2000;
+// Cosmic chunks walked before giving up on finding enough galaxies
+const MAX_GALAXY_CHUNKS: usize = 4096;
+
+const SPECTRAL_CLASSES: [&str; 9] = [
+    "O",
+    "B",
+    "A",
+    "F",
+    "G",
+    "K",
+    "M",
+    "stellar_black_hole",
+    "supermassive_black_hole",
+];
+const GALAXY_TYPES: [GalaxyType; 4] = [
+    GalaxyType::Spiral,
+    GalaxyType::Lenticular,
+    GalaxyType::Elliptical,
+    GalaxyType::Irregular,
+];
+const BODY_TYPES: [BodyType; 4] = [
+    BodyType::Terrestrial,
+    BodyType::GasGiant,
+    BodyType::IceGiant,
+    BodyType::AsteroidBelt,
+];
+const PLANET_TYPES: [PlanetType; 5] = [
+    PlanetType::Terrestrial,
+    PlanetType::GasGiant,
+    PlanetType::IceGiant,
+    PlanetType::AsteroidField,
+    PlanetType::Moon,
+];
+
+#[derive(Debug)]
+struct Options {
+    seed: u64,
+    generation_version: u32,
+    galaxies: usize,
+    systems_per_galaxy: usize,
+    planets: usize,
+    players: usize,
+    format: Format,
+    out: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Json,
+    Csv,
+}
+
+impl Options {
+    fn from_args(args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut options = Self {
+            seed: 0,
+            generation_version: CURRENT_GENERATION_VERSION,
+            galaxies: 40,
+            systems_per_galaxy: 50,
+            planets: 60,
+            players: 8,
+            format: Format::Json,
+            out: None,
+        };
+
+        let mut args = args;
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .with_context(|| format!("missing value for {flag}"))?;
+            match flag.as_str() {
+                "--seed" => options.seed = value.parse().context("invalid seed")?,
+                "--generation-version" => {
+                    options.generation_version =
+                        value.parse().context("invalid generation version")?
+                }
+                "--galaxies" => options.galaxies = value.parse().context("invalid galaxy count")?,
+                "--systems" => {
+                    options.systems_per_galaxy = value.parse().context("invalid system count")?
+                }
+                "--planets" => options.planets = value.parse().context("invalid planet count")?,
+                "--players" => options.players = value.parse().context("invalid player count")?,
+                "--format" => {
+                    options.format = match value.as_str() {
+                        "json" => Format::Json,
+                        "csv" => Format::Csv,
+                        other => return Err(anyhow!("unknown format '{other}'")),
+                    }
+                }
+                "--out" => options.out = Some(value),
+                other => return Err(anyhow!("unknown argument '{other}'")),
+            }
+        }
+        check_generation_version(options.generation_version)?;
+
+        Ok(options)
+    }
+}
+
+/// Counts per bin, in a fixed bin order. Shares are relative to the histogram's total.
+#[derive(Debug, Serialize)]
+struct Histogram {
+    name: String,
+    total: u64,
+    bins: Vec<Bin>,
+}
+
+#[derive(Debug, Serialize)]
+struct Bin {
+    key: String,
+    count: u64,
+    share: f64,
+}
+
+impl Histogram {
+    /// One bin per key of `keys`, in that order, empty ones included.
+    fn new<K: Display>(name: impl Into<String>, keys: impl IntoIterator<Item = K>) -> Self {
+        Self {
+            name: name.into(),
+            total: 0,
+            bins: keys
+                .into_iter()
+                .map(|key| Bin {
+                    key: key.to_string(),
+                    count: 0,
+                    share: 0.0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Histogram of numeric values, one bin per distinct value, ascending.
+    fn of_values(name: impl Into<String>, counts: &BTreeMap<u64, u64>) -> Self {
+        let mut histogram = Self::new(name, counts.keys());
+        for (value, count) in counts {
+            histogram.add(&value.to_string(), *count);
+        }
+        histogram.finish()
+    }
+
+    fn add(&mut self, key: &str, count: u64) {
+        let bin = match self.bins.iter_mut().find(|bin| bin.key == key) {
+            Some(bin) => bin,
+            None => {
+                self.bins.push(Bin {
+                    key: key.to_string(),
+                    count: 0,
+                    share: 0.0,
+                });
+                self.bins.last_mut().unwrap()
+            }
+        };
+        bin.count += count;
+        self.total += count;
+    }
+
+    fn finish(mut self) -> Self {
+        for bin in &mut self.bins {
+            bin.share = if self.total > 0 {
+                bin.count as f64 / self.total as f64
+            } else {
+                0.0
+            };
+        }
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Metric {
+    name: &'static str,
+    value: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    seed: u64,
+    generation_version: u32,
+    galaxies_sampled: usize,
+    systems_sampled: usize,
+    planets_surveyed: usize,
+    metrics: Vec<Metric>,
+    histograms: Vec<Histogram>,
+}
+
+impl Report {
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("histogram,key,count,share\n");
+        for metric in &self.metrics {
+            csv.push_str(&format!("metric,{},{},\n", metric.name, metric.value));
+        }
+        for histogram in &self.histograms {
+            for bin in &histogram.bins {
+                csv.push_str(&format!(
+                    "{},{},{},{:.6}\n",
+                    histogram.name, bin.key, bin.count, bin.share
+                ));
+            }
+        }
+        csv
+    }
+}
+
+/// SplitMix64: the tool's own sampling stream, independent of every game seed.
+struct Sampler(u64);
+
+impl Sampler {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in -radius..=radius.
+    fn offset(&mut self, radius: i32) -> i32 {
+        let span = 2 * radius as u64 + 1;
+        (self.next_u64() % span) as i32 - radius
+    }
+}
+
+fn main() -> Result<()> {
+    let options = Options::from_args(env::args().skip(1))?;
+    let report = sample(&options)?;
+
+    let output = match options.format {
+        Format::Json => serde_json::to_string_pretty(&report)? + "\n",
+        Format::Csv => report.to_csv(),
+    };
+    match &options.out {
+        Some(path) => std::fs::write(path, output).with_context(|| format!("writing {path}"))?,
+        None => print!("{output}"),
+    }
+    Ok(())
+}
+
+fn sample(options: &Options) -> Result<Report> {
+    let root_seed = RootSeed::new(options.seed, options.generation_version);
+    let galaxies = sample_galaxies(root_seed, options.galaxies);
+    let mut sampler = Sampler(options.seed);
+
+    let mut histograms = Vec::new();
+    let mut metrics = Vec::new();
+
+    let mut galaxy_types = Histogram::new("galaxy_type", GALAXY_TYPES.map(|t| t.as_str()));
+    let mut arm_counts = BTreeMap::new();
+    let mut barred = Histogram::new("galaxy_barred", ["barred", "unbarred"]);
+    for galaxy in &galaxies {
+        galaxy_types.add(galaxy.galaxy_type.as_str(), 1);
+        if galaxy.galaxy_type == GalaxyType::Spiral {
+            *arm_counts
+                .entry(galaxy.structure.arm_count as u64)
+                .or_default() += 1;
+        }
+        let bar = if galaxy.structure.bar_length.is_some() {
+            "barred"
+        } else {
+            "unbarred"
+        };
+        barred.add(bar, 1);
+    }
+    histograms.push(galaxy_types.finish());
+    histograms.push(Histogram::of_values("spiral_arm_count", &arm_counts));
+    histograms.push(barred.finish());
+
+    // Star systems, weighted by where they actually spawn: uniform cells, rejection sampled
+    let mut systems = Vec::new();
+    for galaxy in &galaxies {
+        let reach = galaxy.structure.radius.ceil() as i32;
+        let mut found = 0;
+        let mut attempts = 0;
+        while found < options.systems_per_galaxy
+            && attempts < ATTEMPTS_PER_SYSTEM * options.systems_per_galaxy as u32
+        {
+            attempts += 1;
+            let cell = (
+                sampler.offset(reach),
+                sampler.offset(reach),
+                sampler.offset(reach),
+            );
+            if galaxy.should_spawn_star_system(cell) {
+                systems.push(StarSystem::new(galaxy, cell));
+                found += 1;
+            }
+        }
+    }
+
+    let mut spectral = Histogram::new("star_class", SPECTRAL_CLASSES);
+    let mut configurations = Histogram::new(
+        "star_configuration",
+        ["single", "close_binary", "wide_binary", "trinary"],
+    );
+    let mut bodies_per_system = BTreeMap::new();
+    let mut body_types = Histogram::new("body_type", BODY_TYPES.map(|t| t.as_str()));
+    let mut moons_per_body = BTreeMap::new();
+    let mut habitable_bodies = 0u64;
+    let mut systems_with_habitable_terrestrial = 0u64;
+    let mut body_count = 0u64;
+
+    for system in &systems {
+        for star in &system.stars {
+            let class = match star.star_type {
+                StarType::Spectral(spectral) => spectral.as_str(),
+                other => other.as_str(),
+            };
+            spectral.add(class, 1);
+        }
+        configurations.add(system.configuration.as_str(), 1);
+        *bodies_per_system
+            .entry(system.bodies.len() as u64)
+            .or_default() += 1;
+
+        for body in &system.bodies {
+            body_count += 1;
+            body_types.add(body.body_type.as_str(), 1);
+            *moons_per_body.entry(body.moons.len() as u64).or_default() += 1;
+            habitable_bodies += body.is_in_habitable_zone as u64;
+        }
+        systems_with_habitable_terrestrial += system
+            .bodies
+            .iter()
+            .any(|body| body.body_type == BodyType::Terrestrial && body.is_in_habitable_zone)
+            as u64;
+    }
+    histograms.push(spectral.finish());
+    histograms.push(configurations.finish());
+    histograms.push(Histogram::of_values(
+        "bodies_per_system",
+        &bodies_per_system,
+    ));
+    histograms.push(body_types.finish());
+    histograms.push(Histogram::of_values("moons_per_body", &moons_per_body));
+    metrics.push(Metric {
+        name: "habitable_zone_body_rate",
+        value: ratio(habitable_bodies, body_count),
+    });
+    metrics.push(Metric {
+        name: "systems_with_habitable_terrestrial_rate",
+        value: ratio(systems_with_habitable_terrestrial, systems.len() as u64),
+    });
+
+    // Tile surveys are the slow part: spread the planet budget evenly over the sample
+    let planets: Vec<Planet> = systems
+        .iter()
+        .flat_map(|system| {
+            system.bodies.iter().flat_map(move |body| {
+                let star = system.host_star(body.host);
+                std::iter::once(Planet::new(0, system, body, &star))
+                    .chain(Planet::moons_of(0, system, body, &star))
+            })
+        })
+        .collect();
+    let surveyed: Vec<&Planet> = if planets.len() <= options.planets {
+        planets.iter().collect()
+    } else {
+        (0..options.planets)
+            .map(|i| &planets[i * planets.len() / options.planets])
+            .collect()
+    };
+    histograms.extend(survey_planets(&surveyed));
+
+    let (spawn_metrics, start_scores) = time_spawn_searches(options)?;
+    metrics.extend(spawn_metrics);
+    histograms.push(start_scores);
+
+    Ok(Report {
+        seed: options.seed,
+        generation_version: options.generation_version,
+        galaxies_sampled: galaxies.len(),
+        systems_sampled: systems.len(),
+        planets_surveyed: surveyed.len(),
+        metrics,
+        histograms,
+    })
+}
+
+/// The first `count` galaxies met walking cosmic chunks outward from the origin.
+fn sample_galaxies(root_seed: RootSeed, count: usize) -> Vec<Galaxy> {
+    Spiral3D::default()
+        .take(MAX_GALAXY_CHUNKS)
+        .flat_map(|(x, y, z)| galaxies_in_chunk(root_seed, ChunkCoord::new(x, y, z)))
+        .take(count)
+        .collect()
+}
+
+/// Biome and deposit shares of every tile, per planet class.
+fn survey_planets(planets: &[&Planet]) -> Vec<Histogram> {
+    let mut histograms = Vec::new();
+
+    for class in PLANET_TYPES {
+        let mut biomes = Histogram::new(
+            format!("biome.{}", class.as_str()),
+            TileType::ALL.map(|t| t.as_str()),
+        );
+        let mut deposits = Histogram::new(
+            format!("deposit.{}", class.as_str()),
+            std::iter::once("none").chain(Resource::ALL.map(|r| r.as_str())),
+        );
+
+        for planet in planets.iter().filter(|planet| planet.class == class) {
+            for index in 0..planet.topology().tile_count() {
+                let tile = planet.query_tile_at(index);
+                biomes.add(tile.tile_type.as_str(), 1);
+                let deposit = tile
+                    .rare_deposit
+                    .map_or("none", |deposit| deposit.resource.as_str());
+                deposits.add(deposit, 1);
+            }
+        }
+
+        histograms.push(biomes.finish());
+        histograms.push(deposits.finish());
+    }
+
+    histograms
+}
+
+/// Spawns `players` homes one after the other, like successive sign-ups, and times each search.
+fn time_spawn_searches(options: &Options) -> Result<(Vec<Metric>, Histogram)> {
+    let band = FairnessBand::default();
+    let mut exclusions = SpawnExclusions::default();
+    let mut durations_ms = Vec::new();
+    let mut scores = BTreeMap::new();
+
+    for _ in 0..options.players {
+        let started = Instant::now();
+        let location =
+            find_starting_location(options.seed, options.generation_version, &exclusions, &band)?;
+        durations_ms.push(started.elapsed().as_secs_f64() * 1000.0);
+
+        *scores
+            .entry(location.survey.score().round() as u64)
+            .or_default() += 1;
+        exclusions.add_planet(
+            location.galaxy.position,
+            location.star_system.position,
+            location.planet.orbit_index,
+            None,
+            true,
+        );
+    }
+
+    let mean = durations_ms.iter().sum::<f64>() / durations_ms.len().max(1) as f64;
+    let max = durations_ms.iter().copied().fold(0.0, f64::max);
+    Ok((
+        vec![
+            Metric {
+                name: "spawn_search_ms_mean",
+                value: mean,
+            },
+            Metric {
+                name: "spawn_search_ms_max",
+                value: max,
+            },
+        ],
+        Histogram::of_values("start_score", &scores),
+    ))
+}
+
+fn ratio(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
@@ -0,0 +1,106 @@
+use std::env;
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::game::start_area::{FairnessBand, MAX_START_SCORE};
+use crate::game::world::Ruleset;
+
+const DEFAULT_DATABASE_URL: &str = "sqlite://./game.db?mode=rwc";
+const DEFAULT_WORLD: &str = "main";
+const DEFAULT_PORT: u16 = 3000;
+
+/// Server settings, from command line flags with environment variables as fallback:
+///
+/// ```text
+/// axosm --world speed --ruleset speed --seed 1234 --port 3001
+/// AXOSM_WORLD=speed AXOSM_RULESET=speed AXOSM_SEED=1234 AXOSM_PORT=3001 axosm
+/// ```
+///
+/// `seed` and `ruleset` only apply when the named world does not exist yet; an existing world
+/// always keeps its stored settings. `--start-score-min` and `--start-score-max`
+/// (`AXOSM_START_SCORE_MIN`, `AXOSM_START_SCORE_MAX`) bound how good a new player's start may be.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database_url: String,
+    pub port: u16,
+    pub world: String,
+    pub seed: Option<u64>,
+    pub ruleset: Option<Ruleset>,
+    pub spawn_band: FairnessBand,
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        Self::from_args(env::args().skip(1))
+    }
+
+    fn from_args(args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut database_url = env::var("DATABASE_URL").ok();
+        let mut port = env::var("AXOSM_PORT").ok();
+        let mut world = env::var("AXOSM_WORLD").ok();
+        let mut seed = env::var("AXOSM_SEED").ok();
+        let mut ruleset = env::var("AXOSM_RULESET").ok();
+        let mut start_score_min = env::var("AXOSM_START_SCORE_MIN").ok();
+        let mut start_score_max = env::var("AXOSM_START_SCORE_MAX").ok();
+
+        let mut args = args;
+        while let Some(flag) = args.next() {
+            let target = match flag.as_str() {
+                "--database" => &mut database_url,
+                "--port" => &mut port,
+                "--world" => &mut world,
+                "--seed" => &mut seed,
+                "--ruleset" => &mut ruleset,
+                "--start-score-min" => &mut start_score_min,
+                "--start-score-max" => &mut start_score_max,
+                other => return Err(anyhow!("unknown argument '{other}'")),
+            };
+            let value = args
+                .next()
+                .with_context(|| format!("missing value for {flag}"))?;
+            *target = Some(value);
+        }
+
+        let default_band = FairnessBand::default();
+        let spawn_band = FairnessBand {
+            min: match start_score_min {
+                Some(min) => min.parse().context("invalid start score minimum")?,
+                None => default_band.min,
+            },
+            max: match start_score_max {
+                Some(max) => max.parse().context("invalid start score maximum")?,
+                None => default_band.max,
+            },
+        };
+        if spawn_band.min > spawn_band.max {
+            return Err(anyhow!(
+                "start score minimum {} is above the maximum {}",
+                spawn_band.min,
+                spawn_band.max
+            ));
+        }
+        // No start could ever qualify: every sign-up would search the whole universe and fail
+        if spawn_band.min > MAX_START_SCORE {
+            return Err(anyhow!(
+                "start score minimum {} is above the best possible start score {}",
+                spawn_band.min,
+                MAX_START_SCORE
+            ));
+        }
+
+        Ok(Self {
+            database_url: database_url.unwrap_or_else(|| DEFAULT_DATABASE_URL.to_string()),
+            port: match port {
+                Some(port) => port.parse().context("invalid port")?,
+                None => DEFAULT_PORT,
+            },
+            world: world.unwrap_or_else(|| DEFAULT_WORLD.to_string()),
+            seed: seed
+                .map(|seed| seed.parse::<u64>())
+                .transpose()
+                .context("invalid seed")?,
+            ruleset: ruleset.as_deref().map(Ruleset::parse).transpose()?,
+            spawn_band,
+        })
+    }
+}
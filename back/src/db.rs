@@ -1,3 +1,12 @@
+pub mod battle;
 pub mod building;
+pub mod galaxy;
+pub mod move_order;
+pub mod planet;
 pub mod player;
+pub mod space_object;
+pub mod star_system;
 pub mod unit;
+pub mod world;
+#[cfg(test)]
+pub mod test_db;
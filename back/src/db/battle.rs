@@ -0,0 +1,12 @@
+use sqlx::prelude::FromRow;
+
+/// The next round of an ongoing battle. `previous_tick_at` is the last round (or the start of
+/// the battle) and `tick_at` when this one is due.
+#[derive(Debug, Clone, FromRow)]
+pub struct BattleTickRow {
+    pub id: i64,
+    pub attacker_id: i64,
+    pub defender_id: i64,
+    pub previous_tick_at: String,
+    pub tick_at: String,
+}
@@ -10,12 +10,6 @@ pub struct BuildingRow {
     pub level: i32,
     pub hp: i32,
     pub max_hp: i32,
-    pub under_attack: i32,
-    pub can_fly: i32,
-    pub flight_state: Option<String>,
-    pub construction_done_at: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
 }
 
 impl From<BuildingRow> for BuildingDto {
@@ -31,6 +25,14 @@ impl From<BuildingRow> for BuildingDto {
     }
 }
 
+/// A building whose construction is due to finish at `construction_done_at`.
+#[derive(Debug, Clone, FromRow)]
+pub struct DueConstructionRow {
+    pub id: i64,
+    pub player_id: i64,
+    pub construction_done_at: String,
+}
+
 // #[derive(FromRow, Debug, Serialize)]
 // pub struct BuildingRow {
 //     pub id: i64,
@@ -0,0 +1,11 @@
+use sqlx::prelude::FromRow;
+
+/// A persisted galaxy and whether the requesting player has a foothold in it.
+#[derive(Debug, FromRow)]
+pub struct GalaxyPresenceRow {
+    pub id: i64,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub has_presence: bool,
+}
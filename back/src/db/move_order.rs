@@ -0,0 +1,69 @@
+use sqlx::prelude::FromRow;
+
+/// A pending move of a unit or a building. Times are Unix epoch milliseconds.
+#[derive(Debug, Clone, FromRow)]
+pub struct MoveOrderRow {
+    pub id: i64,
+    // Owner of the mover
+    pub player_id: i64,
+    pub unit_id: Option<i64>,
+    pub building_id: Option<i64>,
+    pub mover_type: String,
+    pub move_type: String,
+    pub from_planet_id: Option<i64>,
+    pub to_planet_id: Option<i64>,
+    pub to_planet_face: Option<i32>,
+    pub to_planet_u: Option<i32>,
+    pub to_planet_v: Option<i32>,
+    pub from_star_system_id: Option<i64>,
+    pub from_space_x: Option<f64>,
+    pub from_space_y: Option<f64>,
+    pub from_space_z: Option<f64>,
+    pub to_star_system_id: Option<i64>,
+    pub to_space_x: Option<f64>,
+    pub to_space_y: Option<f64>,
+    pub to_space_z: Option<f64>,
+    pub start_time: i64,
+    pub arrival_time: i64,
+    // Set on the legs of a surface route
+    pub route_id: Option<i64>,
+    // Failed applications so far
+    pub attempts: i64,
+}
+
+/// A surface walk of a unit, made of one `tile_walk` order per tile.
+#[derive(Debug, Clone, FromRow)]
+pub struct MoveRouteRow {
+    pub id: i64,
+    pub unit_id: i64,
+    pub planet_id: i64,
+    pub origin_face: i32,
+    pub origin_u: i32,
+    pub origin_v: i32,
+}
+
+/// One step of a route: from the tile the unit stands on to an adjacent one.
+#[derive(Debug, Clone, Copy)]
+pub struct TileWalkLeg {
+    pub leg_index: i32,
+    pub from: (i32, i32, i32),
+    pub to: (i32, i32, i32),
+    pub start_time: i64,
+    pub arrival_time: i64,
+}
+
+/// A single-step move of a unit, such as a launch or a landing.
+#[derive(Debug, Clone)]
+pub struct NewUnitMoveOrder {
+    pub unit_id: i64,
+    pub move_type: &'static str,
+    pub from_planet_id: Option<i64>,
+    pub from_star_system_id: Option<i64>,
+    pub from_space: Option<[f64; 3]>,
+    pub to_planet_id: Option<i64>,
+    pub to_tile: Option<(i32, i32, i32)>,
+    pub to_star_system_id: Option<i64>,
+    pub to_space: Option<[f64; 3]>,
+    pub start_time: i64,
+    pub arrival_time: i64,
+}
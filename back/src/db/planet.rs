@@ -0,0 +1,49 @@
+use sqlx::prelude::FromRow;
+
+/// A persisted planet with the grid positions needed to regenerate it.
+#[derive(Debug, FromRow)]
+pub struct PlanetLocationRow {
+    pub id: i64,
+    pub star_system_id: i64,
+    pub seed: i64,
+    pub orbit_index: i64,
+    pub moon_index: Option<i64>,
+    pub system_x: i32,
+    pub system_y: i32,
+    pub system_z: i32,
+    pub galaxy_x: i32,
+    pub galaxy_y: i32,
+    pub galaxy_z: i32,
+}
+
+/// Persisted state of a tile. Its terrain, yield and deposit are regenerated instead.
+#[derive(Debug, FromRow)]
+pub struct PlanetTileRow {
+    pub id: i64,
+    pub face: i32,
+    pub u: i32,
+    pub v: i32,
+    pub owner_player_id: Option<i64>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TileInfluenceRow {
+    pub tile_id: i64,
+    pub player_id: i64,
+    pub score: f64,
+}
+
+/// Address of a persisted planet, for keeping new homes away from it.
+#[derive(Debug, FromRow)]
+pub struct OccupiedPlanetRow {
+    pub galaxy_x: i32,
+    pub galaxy_y: i32,
+    pub galaxy_z: i32,
+    pub system_x: i32,
+    pub system_y: i32,
+    pub system_z: i32,
+    pub orbit_index: i64,
+    pub moon_index: Option<i64>,
+    // Someone's home planet, or settled by a player from before homes were recorded
+    pub is_home: bool,
+}
@@ -2,8 +2,6 @@ use sqlx::prelude::FromRow;
 
 #[derive(Debug, FromRow)]
 pub struct PlayerRow {
-    pub id: i64,
-    pub session_key: String,
     pub username: String,
 }
 
@@ -0,0 +1,24 @@
+use sqlx::prelude::FromRow;
+
+/// Stored state of a generated asteroid or anomaly, matched to the generator by
+/// (`object_type`, `object_index`).
+#[derive(Debug, FromRow)]
+pub struct SpaceObjectStateRow {
+    pub object_type: String,
+    pub object_index: i64,
+    pub owner_player_id: Option<i64>,
+    pub extracted: f64,
+    pub despawned_at: Option<String>,
+}
+
+/// A haul mined from a generated asteroid. `extracted_before` is what the asteroid's row held
+/// when the haul was planned: the booking fails if someone mined it meanwhile.
+#[derive(Debug, Clone)]
+pub struct NewAsteroidExtraction {
+    pub star_system_id: i64,
+    pub asteroid_index: u32,
+    pub player_id: i64,
+    pub extracted_before: f64,
+    pub amount: f64,
+    pub mined_out: bool,
+}
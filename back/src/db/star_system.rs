@@ -0,0 +1,28 @@
+use sqlx::prelude::FromRow;
+
+/// One player's footprint in a persisted star system.
+/// `player_id` is None for systems that are stored but hold nobody's assets.
+#[derive(Debug, FromRow)]
+pub struct SystemPresenceRow {
+    pub star_system_id: i64,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub player_id: Option<i64>,
+    pub owned_tiles: i64,
+    pub buildings: i64,
+    pub fleet_units: i64,
+}
+
+/// Where a persisted star system sits: its galaxy and its cell of that galaxy's grid.
+#[derive(Debug, Clone, FromRow)]
+pub struct StarSystemCellRow {
+    pub id: i64,
+    pub galaxy_id: i64,
+    pub galaxy_x: i32,
+    pub galaxy_y: i32,
+    pub galaxy_z: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
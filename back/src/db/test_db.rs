@@ -0,0 +1,48 @@
+// In-memory databases for the tests of services and the worker, migrated like a real one. Each
+// connection to `sqlite::memory:` opens a database of its own, so the pool keeps a single one.
+
+use crate::game::proc_gen::seed::CURRENT_GENERATION_VERSION;
+use crate::game::world::World;
+use crate::repositories::worlds_repo;
+use sqlx::SqlitePool;
+use sqlx::sqlite::SqlitePoolOptions;
+
+const TEST_WORLD_SEED: u64 = 1234;
+
+pub async fn memory_pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
+/// A world on the test ruleset, whose flights are 20 times faster.
+pub async fn create_world(pool: &SqlitePool) -> World {
+    worlds_repo::create_world(
+        pool,
+        "test",
+        TEST_WORLD_SEED,
+        CURRENT_GENERATION_VERSION,
+        "test",
+    )
+    .await
+    .unwrap()
+    .try_into()
+    .unwrap()
+}
+
+/// A player of `world` who has not connected yet: no home, units or resources.
+pub async fn create_player(pool: &SqlitePool, world: &World, session_key: &str) -> i64 {
+    sqlx::query_scalar(
+        "INSERT INTO players (session_key, username, world_id) VALUES (?, ?, ?) RETURNING id",
+    )
+    .bind(session_key)
+    .bind(session_key)
+    .bind(world.id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
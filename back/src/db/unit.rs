@@ -5,7 +5,6 @@ use sqlx::prelude::FromRow;
 pub struct UnitRow {
     pub id: i64,
     pub unit_type: String,
-    pub is_squad: i32,
     pub count: i32,
     pub hp: i32,
     pub player_id: i64,
@@ -20,6 +19,7 @@ pub struct UnitRow {
     pub star_system_x: Option<f64>,
     pub star_system_y: Option<f64>,
     pub star_system_z: Option<f64>,
+    pub orbit_layer: Option<String>, // 'low' or 'high' when in orbit
 }
 
 impl From<UnitRow> for UnitDto {
@@ -34,6 +34,13 @@ impl From<UnitRow> for UnitDto {
             planet_face: row.planet_face,
             planet_u: row.planet_u,
             planet_v: row.planet_v,
+            orbit_planet_id: row.orbit_planet_id,
+            orbit_layer: row.orbit_layer,
+            star_system_id: row.star_system_id,
+            star_system_position: match (row.star_system_x, row.star_system_y) {
+                (Some(x), Some(y)) => Some([x, y, row.star_system_z.unwrap_or(0.0)]),
+                _ => None,
+            },
         }
     }
 }
@@ -0,0 +1,13 @@
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, FromRow)]
+pub struct WorldRow {
+    pub id: i64,
+    pub name: String,
+    pub seed: i64,
+    pub generation_version: i64,
+    pub ruleset: String,
+    pub started_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
@@ -1,7 +1,15 @@
 // pub mod auth;
 pub mod building;
+pub mod event;
+pub mod flight;
+pub mod move_route;
+pub mod planet;
+pub mod space_object;
+pub mod star_system;
 pub mod state;
+pub mod travel;
 pub mod unit;
+pub mod universe;
 // pub mod events;
 // pub mod move_unit;
 // pub mod state;
@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// Something that happened in the world, pushed as JSON over `GET /api/events` to the players
+/// it concerns (see `recipients`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEventDto {
+    MoveArrived {
+        player_id: i64,
+        order_id: i64,
+        unit_id: Option<i64>,
+        building_id: Option<i64>,
+        move_type: String,
+        route_id: Option<i64>, // Set on each leg of a surface walk
+    },
+    // A walking unit got caught in a battle and dropped the rest of its route
+    MoveHalted {
+        player_id: i64,
+        unit_id: i64,
+        route_id: i64,
+    },
+    ConstructionCompleted {
+        player_id: i64,
+        building_id: i64,
+    },
+    // The battle is over and its report written
+    BattleEnded {
+        battle_id: i64,
+        attacker_id: i64,
+        defender_id: i64,
+        outcome: String, // As in battle_reports.outcome
+        ended_at: String,
+    },
+    // A unit of another player is heading for a planet where the player holds tiles or buildings
+    IncomingAttack {
+        player_id: i64,
+        attacker_id: i64,
+        unit_id: i64,
+        planet_id: i64,
+        move_type: String,
+        arrival_time: i64, // Unix epoch milliseconds
+    },
+    DiplomacyMessage {
+        from_player_id: i64,
+        from_username: String,
+        to_player_id: i64,
+        text: String,
+        sent_at: i64, // Unix epoch milliseconds
+    },
+}
+
+impl GameEventDto {
+    /// Players who may see the event.
+    pub fn recipients(&self) -> Vec<i64> {
+        match self {
+            Self::MoveArrived { player_id, .. }
+            | Self::MoveHalted { player_id, .. }
+            | Self::ConstructionCompleted { player_id, .. }
+            | Self::IncomingAttack { player_id, .. } => vec![*player_id],
+            Self::BattleEnded {
+                attacker_id,
+                defender_id,
+                ..
+            } => vec![*attacker_id, *defender_id],
+            // The sender too, so that their other sessions see the conversation
+            Self::DiplomacyMessage {
+                from_player_id,
+                to_player_id,
+                ..
+            } => vec![*from_player_id, *to_player_id],
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+/// A launch, orbit change or landing under way. Times are Unix epoch milliseconds.
+#[derive(Debug, Serialize)]
+pub struct FlightOrderDto {
+    pub order_id: i64,
+    pub unit_id: i64,
+    pub maneuver: &'static str, // The move order type
+    pub from: &'static str,
+    pub to: &'static str,
+    pub planet_id: i64,
+    pub start_time: i64,
+    pub arrival_time: i64,
+}
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/// A surface walk, leg by leg. Times are Unix epoch milliseconds.
+#[derive(Debug, Serialize)]
+pub struct MoveRouteDto {
+    pub route_id: Option<i64>, // None when the unit stays where it is
+    pub unit_id: i64,
+    pub planet_id: i64,
+    pub legs: Vec<MoveLegDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MoveLegDto {
+    pub order_id: i64,
+    // Tile entered by this leg, as a tile index and as stored coordinates
+    pub index: u32,
+    pub face: u8,
+    pub u: u32,
+    pub v: u32,
+    pub start_time: i64,
+    pub arrival_time: i64,
+}
@@ -0,0 +1,192 @@
+use crate::dto::building::BuildingDto;
+use crate::dto::unit::UnitDto;
+use crate::game::proc_gen::goldberg::GoldbergTopology;
+use crate::game::proc_gen::planet::Planet;
+use crate::game::proc_gen::tile::{DynamicTileProperties, TileType};
+use crate::game::resource::Resource;
+use serde::Serialize;
+
+// Tile data is columnar: every array below is indexed by the stable Goldberg tile index, so a
+// 10k-tile planet ships as a handful of flat arrays instead of 10k objects.
+
+#[derive(Debug, Serialize)]
+pub struct PlanetSurfaceDto {
+    pub id: i64,
+    pub star_system_id: i64,
+    pub orbit_index: u32,
+    pub moon_index: Option<u32>,
+    pub class: &'static str,
+    pub physics: PlanetPhysicsDto,
+    pub topology: TopologyDto,
+    pub tiles: TileColumnsDto,
+    pub overlay: SurfaceOverlayDto,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlanetPhysicsDto {
+    pub mass_earth: f32,
+    pub radius_earth: f32,
+    pub surface_gravity_g: f32,
+    pub atmosphere: &'static str,
+    pub surface_pressure_atm: f32,
+    pub water_fraction: f32,
+    pub axial_tilt_deg: f32,
+    pub mean_surface_temp_k: f32,
+    pub sea_level: f32,
+}
+
+/// Everything the renderer needs to rebuild the tile mesh without re-running generation.
+#[derive(Debug, Serialize)]
+pub struct TopologyDto {
+    pub subdivision: u32,
+    pub tile_count: u32,
+    pub face: Vec<u8>,
+    pub u: Vec<u32>,
+    pub v: Vec<u32>,
+    // Unit-sphere tile centers, flattened as x0, y0, z0, x1, ...
+    pub positions: Vec<f32>,
+    // Neighbours of tile i are neighbors[neighbor_offsets[i]..neighbor_offsets[i + 1]],
+    // counter-clockwise
+    pub neighbor_offsets: Vec<u32>,
+    pub neighbors: Vec<u32>,
+    pub pentagons: Vec<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TileColumnsDto {
+    // tile_type[i] indexes tile_type_names
+    pub tile_type_names: Vec<&'static str>,
+    pub tile_type: Vec<u8>,
+    pub elevation: Vec<f32>,
+    pub moisture: Vec<f32>,
+    pub temperature_k: Vec<f32>,
+    pub yield_quality: Vec<f32>,
+    // rare_deposit[i] is 0 for no deposit, otherwise 1 + an index into deposit_names;
+    // deposit_richness[i] is 0 for no deposit, otherwise 1 (poor) to 3 (rich)
+    pub deposit_names: Vec<&'static str>,
+    pub rare_deposit: Vec<u8>,
+    pub deposit_richness: Vec<u8>,
+}
+
+/// Persisted state, sparse and keyed by tile index.
+#[derive(Debug, Default, Serialize)]
+pub struct SurfaceOverlayDto {
+    pub tiles: Vec<TileStateDto>,
+    pub buildings: Vec<SurfaceBuildingDto>,
+    pub influence: Vec<TileInfluenceDto>,
+    pub units: Vec<SurfaceUnitDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TileStateDto {
+    pub index: u32,
+    pub tile_id: i64,
+    pub owner_player_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SurfaceBuildingDto {
+    pub index: u32,
+    pub player_id: i64,
+    #[serde(flatten)]
+    pub building: BuildingDto,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TileInfluenceDto {
+    pub index: u32,
+    pub player_id: i64,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SurfaceUnitDto {
+    pub index: u32,
+    pub player_id: i64,
+    #[serde(flatten)]
+    pub unit: UnitDto,
+}
+
+impl From<&Planet> for PlanetPhysicsDto {
+    fn from(planet: &Planet) -> Self {
+        let physics = &planet.physics;
+        Self {
+            mass_earth: physics.mass_earth,
+            radius_earth: physics.radius_earth,
+            surface_gravity_g: physics.surface_gravity_g,
+            atmosphere: physics.atmosphere.as_str(),
+            surface_pressure_atm: physics.surface_pressure_atm,
+            water_fraction: physics.water_fraction,
+            axial_tilt_deg: physics.axial_tilt_deg,
+            mean_surface_temp_k: planet.climate.mean_surface_temp_k,
+            sea_level: planet.climate.sea_level,
+        }
+    }
+}
+
+impl From<&GoldbergTopology> for TopologyDto {
+    fn from(topology: &GoldbergTopology) -> Self {
+        let tile_count = topology.tile_count();
+        let mut dto = Self {
+            subdivision: topology.subdivision,
+            tile_count,
+            face: Vec::with_capacity(tile_count as usize),
+            u: Vec::with_capacity(tile_count as usize),
+            v: Vec::with_capacity(tile_count as usize),
+            positions: Vec::with_capacity(3 * tile_count as usize),
+            neighbor_offsets: Vec::with_capacity(tile_count as usize + 1),
+            neighbors: Vec::with_capacity(6 * tile_count as usize),
+            pentagons: topology.pentagons().collect(),
+        };
+
+        dto.neighbor_offsets.push(0);
+        for index in 0..tile_count {
+            let (face, u, v) = topology.tile_coords(index);
+            dto.face.push(face);
+            dto.u.push(u);
+            dto.v.push(v);
+            dto.positions.extend(topology.position(index));
+            dto.neighbors.extend(topology.neighbors(index));
+            dto.neighbor_offsets.push(dto.neighbors.len() as u32);
+        }
+
+        dto
+    }
+}
+
+impl From<&[DynamicTileProperties]> for TileColumnsDto {
+    fn from(tiles: &[DynamicTileProperties]) -> Self {
+        let mut dto = Self {
+            tile_type_names: TileType::ALL.iter().map(TileType::as_str).collect(),
+            tile_type: Vec::with_capacity(tiles.len()),
+            elevation: Vec::with_capacity(tiles.len()),
+            moisture: Vec::with_capacity(tiles.len()),
+            temperature_k: Vec::with_capacity(tiles.len()),
+            yield_quality: Vec::with_capacity(tiles.len()),
+            deposit_names: Resource::ALL.iter().map(Resource::as_str).collect(),
+            rare_deposit: Vec::with_capacity(tiles.len()),
+            deposit_richness: Vec::with_capacity(tiles.len()),
+        };
+
+        for tile in tiles {
+            dto.tile_type.push(tile.tile_type as u8);
+            dto.elevation.push(tile.elevation);
+            dto.moisture.push(tile.moisture);
+            dto.temperature_k.push(tile.temperature_k);
+            dto.yield_quality.push(tile.yield_quality);
+
+            match tile.rare_deposit {
+                Some(deposit) => {
+                    dto.rare_deposit.push(deposit.resource as u8 + 1);
+                    dto.deposit_richness.push(deposit.richness.level());
+                }
+                None => {
+                    dto.rare_deposit.push(0);
+                    dto.deposit_richness.push(0);
+                }
+            }
+        }
+
+        dto
+    }
+}
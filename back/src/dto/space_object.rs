@@ -0,0 +1,77 @@
+use crate::game::proc_gen::space_object::{Anomaly, Asteroid};
+use crate::game::proc_gen::star_system::OrbitHost;
+use serde::Serialize;
+
+/// Asteroids and anomalies of one star system, generated and merged with their stored state.
+#[derive(Debug, Serialize)]
+pub struct SystemSpaceObjectsDto {
+    pub galaxy: [i32; 3],
+    pub system: [i32; 3],
+    // Database id, once someone has settled the system
+    pub star_system_id: Option<i64>,
+    pub asteroids: Vec<AsteroidDto>,
+    pub anomalies: Vec<AnomalyDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AsteroidDto {
+    pub index: u32,
+    pub belt_index: u32,
+    pub host_stars: Vec<usize>,
+    // Relative to the host stars, in AU
+    pub position_au: [f32; 3],
+    pub size: &'static str,
+    pub radius_km: f32,
+    pub composition: &'static str,
+    pub total_yield: f64,
+    pub remaining_yield: f64,
+    pub owner_player_id: Option<i64>,
+}
+
+/// What a unit just mined, and the asteroid as it is left.
+#[derive(Debug, Serialize)]
+pub struct AsteroidExtractionDto {
+    pub unit_id: i64,
+    pub star_system_id: i64,
+    pub resource: &'static str,
+    pub amount: f64,
+    pub asteroid: AsteroidDto,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnomalyDto {
+    pub index: u32,
+    pub kind: &'static str,
+    // Relative to the primary star, in AU
+    pub position_au: [f32; 3],
+}
+
+impl AsteroidDto {
+    pub fn new(asteroid: &Asteroid, extracted: f64, owner_player_id: Option<i64>) -> Self {
+        Self {
+            index: asteroid.index,
+            belt_index: asteroid.belt_index,
+            host_stars: match asteroid.host {
+                OrbitHost::Star(index) => vec![index],
+                OrbitHost::Pair(a, b) => vec![a, b],
+            },
+            position_au: asteroid.position_au(),
+            size: asteroid.size.as_str(),
+            radius_km: asteroid.radius_km,
+            composition: asteroid.composition.as_str(),
+            total_yield: asteroid.total_yield,
+            remaining_yield: asteroid.remaining_yield(extracted),
+            owner_player_id,
+        }
+    }
+}
+
+impl From<&Anomaly> for AnomalyDto {
+    fn from(anomaly: &Anomaly) -> Self {
+        Self {
+            index: anomaly.index,
+            kind: anomaly.kind.as_str(),
+            position_au: anomaly.position_au(),
+        }
+    }
+}
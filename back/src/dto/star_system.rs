@@ -0,0 +1,189 @@
+use crate::game::proc_gen::orbit::Orbit;
+use crate::game::proc_gen::star_system::{
+    OrbitHost, OrbitalBody, Star, StarConfiguration, StarSystem, StarType,
+};
+use serde::Serialize;
+
+/// Full view of a system: its stars and its bodies, placed on their orbits at `game_time_s`.
+#[derive(Debug, Serialize)]
+pub struct StarSystemDto {
+    pub galaxy: [i32; 3],
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    // Database id, once someone has settled the system
+    pub star_system_id: Option<i64>,
+    pub configuration: &'static str,
+    pub stars: Vec<StarDto>,
+    pub bodies: Vec<OrbitalBodyDto>,
+    // Seconds since the world started; the client advances orbits from here
+    pub game_time_s: f64,
+}
+
+/// Lightweight view of a system for space browsing: its stars, without the bodies.
+#[derive(Debug, Clone, Serialize)]
+pub struct StarSystemSummaryDto {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub configuration: &'static str,
+    pub stars: Vec<StarDto>,
+    pub body_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StarDto {
+    pub star_type: &'static str,
+    pub spectral_class: Option<&'static str>,
+    pub mass: f32,
+    pub radius: f32,
+    pub luminosity: f32,
+    pub surface_temp: u32,
+    pub metallicity: f32,
+    // Star this one orbits (None for the primary) and their separation
+    pub companion_of: Option<usize>,
+    pub separation_au: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrbitalBodyDto {
+    pub index: u32,
+    pub body_type: &'static str,
+    pub host_stars: Vec<usize>,
+    pub semi_major_axis_au: f32,
+    pub orbit: OrbitDto,
+    // Relative to the host stars at the system's game time, in AU
+    pub position_au: [f32; 3],
+    pub is_in_habitable_zone: bool,
+    pub moon_count: usize,
+    pub has_rings: bool,
+}
+
+/// Keplerian elements: the mean anomaly grows by 360° every `period_s` from its value at the
+/// world's start.
+#[derive(Debug, Serialize)]
+pub struct OrbitDto {
+    pub eccentricity: f32,
+    pub inclination_deg: f32,
+    pub ascending_node_deg: f32,
+    pub argument_of_periapsis_deg: f32,
+    pub mean_anomaly_at_epoch_deg: f32,
+    pub period_s: f64,
+}
+
+impl StarSystemDto {
+    pub fn new(
+        galaxy: (i32, i32, i32),
+        system: &StarSystem,
+        star_system_id: Option<i64>,
+        game_time_s: f64,
+    ) -> Self {
+        Self {
+            galaxy: [galaxy.0, galaxy.1, galaxy.2],
+            x: system.position.0,
+            y: system.position.1,
+            z: system.position.2,
+            star_system_id,
+            configuration: system.configuration.as_str(),
+            stars: StarDto::all_of(system),
+            bodies: system
+                .bodies
+                .iter()
+                .map(|body| OrbitalBodyDto::new(body, game_time_s))
+                .collect(),
+            game_time_s,
+        }
+    }
+}
+
+impl From<&StarSystem> for StarSystemSummaryDto {
+    fn from(system: &StarSystem) -> Self {
+        Self {
+            x: system.position.0,
+            y: system.position.1,
+            z: system.position.2,
+            configuration: system.configuration.as_str(),
+            stars: StarDto::all_of(system),
+            body_count: system.bodies.len(),
+        }
+    }
+}
+
+impl StarDto {
+    fn all_of(system: &StarSystem) -> Vec<Self> {
+        // (companion_of, separation_au) per star, in `StarSystem::stars` order
+        let orbits: &[(Option<usize>, f32)] = match system.configuration {
+            StarConfiguration::Single => &[(None, 0.0)],
+            StarConfiguration::CloseBinary { separation_au }
+            | StarConfiguration::WideBinary { separation_au } => {
+                &[(None, 0.0), (Some(0), separation_au)]
+            }
+            StarConfiguration::Trinary {
+                inner_separation_au,
+                outer_separation_au,
+            } => &[
+                (None, 0.0),
+                (Some(0), inner_separation_au),
+                (Some(0), outer_separation_au),
+            ],
+        };
+
+        system
+            .stars
+            .iter()
+            .zip(orbits)
+            .map(|(star, &(companion_of, separation_au))| {
+                StarDto::new(star, companion_of, separation_au)
+            })
+            .collect()
+    }
+
+    fn new(star: &Star, companion_of: Option<usize>, separation_au: f32) -> Self {
+        Self {
+            star_type: star.star_type.as_str(),
+            spectral_class: match star.star_type {
+                StarType::Spectral(spectral_type) => Some(spectral_type.as_str()),
+                _ => None,
+            },
+            mass: star.mass,
+            radius: star.radius,
+            luminosity: star.luminosity,
+            surface_temp: star.surface_temp,
+            metallicity: star.metallicity,
+            companion_of,
+            separation_au,
+        }
+    }
+}
+
+impl OrbitalBodyDto {
+    fn new(body: &OrbitalBody, game_time_s: f64) -> Self {
+        Self {
+            index: body.index,
+            body_type: body.body_type.as_str(),
+            host_stars: match body.host {
+                OrbitHost::Star(index) => vec![index],
+                OrbitHost::Pair(a, b) => vec![a, b],
+            },
+            semi_major_axis_au: body.semi_major_axis_au,
+            orbit: OrbitDto::from(&body.orbit),
+            position_au: body.orbit.position_au(game_time_s),
+            is_in_habitable_zone: body.is_in_habitable_zone,
+            moon_count: body.moons.len(),
+            has_rings: body.rings.is_some(),
+        }
+    }
+}
+
+impl From<&Orbit> for OrbitDto {
+    fn from(orbit: &Orbit) -> Self {
+        Self {
+            eccentricity: orbit.eccentricity,
+            inclination_deg: orbit.inclination_rad.to_degrees(),
+            ascending_node_deg: orbit.ascending_node_rad.to_degrees(),
+            argument_of_periapsis_deg: orbit.argument_of_periapsis_rad.to_degrees(),
+            mean_anomaly_at_epoch_deg: orbit.mean_anomaly_at_epoch_rad.to_degrees(),
+            period_s: orbit.period_s,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+use crate::game::travel::SpacePosition;
+
+/// A flight through space under way. Positions are in AU from the system's primary star,
+/// times are Unix epoch milliseconds.
+#[derive(Debug, Serialize)]
+pub struct SpaceTravelDto {
+    pub order_id: i64,
+    pub unit_id: i64,
+    pub jump: bool, // Between star systems
+    pub from_star_system_id: i64,
+    pub from: [f64; 3],
+    pub to_star_system_id: i64,
+    pub to: [f64; 3],
+    pub planet_id: Option<i64>, // The planet met on arrival
+    pub deuterium: f64,         // Burnt for the whole flight
+    pub start_time: i64,
+    pub arrival_time: i64,
+}
+
+/// Where a unit in space is at `at`. Inside a system: `star_system_id` and `position` in AU.
+/// Jumping between systems: `grid_position`, in cells of the galaxy grid.
+#[derive(Debug, Serialize)]
+pub struct UnitPositionDto {
+    pub unit_id: i64,
+    pub at: i64,
+    pub moving: bool,
+    pub star_system_id: Option<i64>,
+    pub position: Option<[f64; 3]>,
+    pub grid_position: Option<[f64; 3]>,
+}
+
+impl UnitPositionDto {
+    pub fn new(unit_id: i64, at: i64, moving: bool, position: SpacePosition) -> Self {
+        let (star_system_id, position, grid_position) = match position {
+            SpacePosition::InSystem {
+                star_system_id,
+                position_au,
+            } => (Some(star_system_id), Some(position_au), None),
+            SpacePosition::Interstellar { grid_position } => (None, None, Some(grid_position)),
+        };
+        Self {
+            unit_id,
+            at,
+            moving,
+            star_system_id,
+            position,
+            grid_position,
+        }
+    }
+}
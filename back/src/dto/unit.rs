@@ -11,4 +11,8 @@ pub struct UnitDto {
     pub planet_face: Option<i32>,
     pub planet_u: Option<i32>,
     pub planet_v: Option<i32>,
+    pub orbit_planet_id: Option<i64>,
+    pub orbit_layer: Option<String>,
+    pub star_system_id: Option<i64>,
+    pub star_system_position: Option<[f64; 3]>, // AU
 }
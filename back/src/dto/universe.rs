@@ -0,0 +1,125 @@
+use crate::dto::star_system::StarSystemSummaryDto;
+use crate::game::proc_gen::galaxy::{Galaxy, GalaxyStructure};
+use serde::Serialize;
+
+// A chunk response carries its cache key: the generated part of a chunk never changes for a
+// given (world_id, generation_version, chunk), only the persisted overlay does.
+
+#[derive(Debug, Serialize)]
+pub struct GalaxyChunkDto {
+    pub world_id: i64,
+    pub generation_version: u32,
+    pub chunk: [i32; 3],
+    pub chunk_size: i32,
+    pub galaxies: Vec<GalaxyEntryDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GalaxySummaryDto {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub galaxy_type: &'static str,
+    pub structure: GalaxyStructureDto,
+}
+
+/// Shape of a galaxy, for drawing it and for knowing how far its star system grid reaches.
+/// Lengths other than `radius` are fractions of the radius.
+#[derive(Debug, Clone, Serialize)]
+pub struct GalaxyStructureDto {
+    pub radius: f32,
+    pub arm_count: u32,
+    pub arm_pitch_deg: f32,
+    pub bar_length: Option<f32>,
+    pub bulge_radius: f32,
+    pub thickness: f32,
+    pub inclination_deg: f32,
+    pub position_angle_deg: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GalaxyEntryDto {
+    #[serde(flatten)]
+    pub galaxy: GalaxySummaryDto,
+    // Database id, once someone has settled the galaxy
+    pub id: Option<i64>,
+    pub has_presence: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StarSystemChunkDto {
+    pub world_id: i64,
+    pub generation_version: u32,
+    pub galaxy: [i32; 3],
+    pub chunk: [i32; 3],
+    pub chunk_size: i32,
+    pub systems: Vec<StarSystemEntryDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StarSystemEntryDto {
+    #[serde(flatten)]
+    pub system: StarSystemSummaryDto,
+    // Database id, once someone has settled the system
+    pub id: Option<i64>,
+    pub owners: Vec<SystemOwnerDto>,
+    pub fleets: Vec<FleetPresenceDto>,
+}
+
+/// Star systems around a point of a galaxy, nearest first.
+#[derive(Debug, Serialize)]
+pub struct NearbyStarSystemsDto {
+    pub world_id: i64,
+    pub generation_version: u32,
+    pub galaxy: [i32; 3],
+    pub center: [i32; 3],
+    pub radius: f32,
+    pub systems: Vec<NearbyStarSystemDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NearbyStarSystemDto {
+    #[serde(flatten)]
+    pub system: StarSystemSummaryDto,
+    pub distance: f32, // In grid cells from the center
+}
+
+#[derive(Debug, Serialize)]
+pub struct SystemOwnerDto {
+    pub player_id: i64,
+    pub owned_tiles: i64,
+    pub buildings: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FleetPresenceDto {
+    pub player_id: i64,
+    pub unit_count: i64,
+}
+
+impl From<&Galaxy> for GalaxySummaryDto {
+    fn from(galaxy: &Galaxy) -> Self {
+        Self {
+            x: galaxy.position.0,
+            y: galaxy.position.1,
+            z: galaxy.position.2,
+            galaxy_type: galaxy.galaxy_type.as_str(),
+            structure: GalaxyStructureDto::from(&galaxy.structure),
+        }
+    }
+}
+
+impl From<&GalaxyStructure> for GalaxyStructureDto {
+    fn from(structure: &GalaxyStructure) -> Self {
+        Self {
+            radius: structure.radius,
+            arm_count: structure.arm_count,
+            arm_pitch_deg: structure.arm_pitch_deg,
+            bar_length: structure.bar_length,
+            bulge_radius: structure.bulge_radius,
+            thickness: structure.thickness,
+            inclination_deg: structure.inclination_deg,
+            position_angle_deg: structure.position_angle_deg,
+        }
+    }
+}
@@ -1,9 +1,39 @@
+use axum::http::StatusCode;
+
+/// Failure of a request that services can tell apart, turned into the handlers' error
+/// response. Anything unexpected is `Internal`, which `?` produces from an `anyhow::Error`.
+#[derive(Debug)]
 pub enum ApiError {
     BadRequest(&'static str),
     Forbidden(&'static str),
+    NotFound(&'static str),
+    Conflict(&'static str),
     Internal(anyhow::Error),
 }
 
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Internal(error)
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Internal(error.into())
+    }
+}
+
+impl From<ApiError> for (StatusCode, String) {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message.to_string()),
+            ApiError::Forbidden(message) => (StatusCode::FORBIDDEN, message.to_string()),
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message.to_string()),
+            ApiError::Conflict(message) => (StatusCode::CONFLICT, message.to_string()),
+            ApiError::Internal(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        }
+    }
+}
 
 // from https://claude.ai/share/31ffaae3-fc58-44d1-9165-352f2bd53d29
 // #[derive(Debug, thiserror::Error)]
@@ -27,4 +57,4 @@ pub enum ApiError {
 //         };
 //         (status, self.to_string()).into_response()
 //     }
-// }
\ No newline at end of file
+// }
@@ -1,3 +1,10 @@
+pub mod flight;
 pub mod game_init;
+pub mod movement;
 pub mod proc_gen;
+pub mod resource;
+pub mod start_area;
+pub mod travel;
+pub mod unit_stats;
+pub mod world;
 // pub mod tile;
@@ -0,0 +1,275 @@
+use crate::game::proc_gen::physics::PlanetPhysics;
+use crate::game::unit_stats::UnitStats;
+
+// Thrust and surface gravity are in g
+const STANDARD_GRAVITY_M_S2: f64 = 9.806_65;
+
+// Orbit layers, in planet radii from its centre: low orbit skims the atmosphere, high orbit
+// sits out of reach of most planetary defences (design doc §6.4)
+const LOW_ORBIT_RADII: f64 = 1.1;
+const HIGH_ORBIT_RADII: f64 = 6.0;
+
+// Even around pebble-sized moons a maneuver takes this long
+const MIN_MANEUVER_S: f64 = 30.0;
+
+/// Where a unit is, layer by layer from the ground up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlightState {
+    Surface,
+    LowOrbit,
+    HighOrbit,
+    Space,
+    // Carried by another unit: moves with its carrier only
+    Embarked,
+}
+
+impl FlightState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Surface => "surface",
+            Self::LowOrbit => "low_orbit",
+            Self::HighOrbit => "high_orbit",
+            Self::Space => "space",
+            Self::Embarked => "embarked",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "surface" => Some(Self::Surface),
+            "low_orbit" => Some(Self::LowOrbit),
+            "high_orbit" => Some(Self::HighOrbit),
+            "space" => Some(Self::Space),
+            "embarked" => Some(Self::Embarked),
+            _ => None,
+        }
+    }
+
+    /// State of a unit stored with `location_mode` and `orbit_layer`.
+    pub fn from_location(location_mode: &str, orbit_layer: Option<&str>) -> Option<Self> {
+        match (location_mode, orbit_layer) {
+            ("planet_surface", _) => Some(Self::Surface),
+            ("in_orbit", Some("high")) => Some(Self::HighOrbit),
+            ("in_orbit", _) => Some(Self::LowOrbit),
+            ("in_space", _) => Some(Self::Space),
+            ("embarked", _) => Some(Self::Embarked),
+            _ => None,
+        }
+    }
+}
+
+/// A move between two adjacent layers. Each is stored as the move order type `move_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Maneuver {
+    LaunchToOrbit,
+    RaiseOrbit,
+    OrbitToSpace,
+    EnterOrbit,
+    LowerOrbit,
+    Land,
+}
+
+impl Maneuver {
+    pub const ALL: [Maneuver; 6] = [
+        Self::LaunchToOrbit,
+        Self::RaiseOrbit,
+        Self::OrbitToSpace,
+        Self::EnterOrbit,
+        Self::LowerOrbit,
+        Self::Land,
+    ];
+
+    pub fn move_type(&self) -> &'static str {
+        match self {
+            Self::LaunchToOrbit => "launch_to_orbit",
+            Self::RaiseOrbit => "raise_orbit",
+            Self::OrbitToSpace => "orbit_to_space",
+            Self::EnterOrbit => "enter_orbit",
+            Self::LowerOrbit => "lower_orbit",
+            Self::Land => "land",
+        }
+    }
+
+    /// The state a unit starts the maneuver in, and the one it ends in.
+    pub fn transition(&self) -> (FlightState, FlightState) {
+        use FlightState::*;
+
+        match self {
+            Self::LaunchToOrbit => (Surface, LowOrbit),
+            Self::RaiseOrbit => (LowOrbit, HighOrbit),
+            Self::OrbitToSpace => (HighOrbit, Space),
+            Self::EnterOrbit => (Space, HighOrbit),
+            Self::LowerOrbit => (HighOrbit, LowOrbit),
+            Self::Land => (LowOrbit, Surface),
+        }
+    }
+
+    /// The maneuver from `from` to `to`. `None` for jumps over a layer, such as the surface
+    /// straight to deep space, and for embarked units.
+    pub fn between(from: FlightState, to: FlightState) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|maneuver| maneuver.transition() == (from, to))
+    }
+
+    /// Game seconds a unit with `stats` needs for this maneuver around a planet with
+    /// `physics`, before the world's fleet speed. The delta-v of the maneuver is spent at full
+    /// thrust; lifting off also fights gravity. `None` if the unit cannot fly, or cannot lift
+    /// off against this gravity.
+    pub fn duration_s(&self, stats: &UnitStats, physics: &PlanetPhysics) -> Option<f64> {
+        if !stats.orbit_capable {
+            return None;
+        }
+
+        // Circular speed at `radii` planet radii is the surface escape speed / √(2·radii)
+        let escape_m_s = physics.escape_velocity_km_s() as f64 * 1000.0;
+        let circular_m_s = |radii: f64| escape_m_s / (2.0 * radii).sqrt();
+        let (low, high) = (LOW_ORBIT_RADII, HIGH_ORBIT_RADII);
+
+        let delta_v_m_s = match self {
+            Self::LaunchToOrbit | Self::Land => circular_m_s(low),
+            // Hohmann transfer between the two layers
+            Self::RaiseOrbit | Self::LowerOrbit => {
+                circular_m_s(low) * ((2.0 * high / (low + high)).sqrt() - 1.0)
+                    + circular_m_s(high) * (1.0 - (2.0 * low / (low + high)).sqrt())
+            }
+            // From circular to escape speed, or back when captured
+            Self::OrbitToSpace | Self::EnterOrbit => {
+                circular_m_s(high) * (std::f64::consts::SQRT_2 - 1.0)
+            }
+        };
+
+        let thrust_g = match self {
+            Self::LaunchToOrbit => stats.thrust_g - physics.surface_gravity_g as f64,
+            _ => stats.thrust_g,
+        };
+        if thrust_g <= 0.0 {
+            return None;
+        }
+
+        Some((delta_v_m_s / (thrust_g * STANDARD_GRAVITY_M_S2)).max(MIN_MANEUVER_S))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::proc_gen::physics::AtmosphereType;
+
+    const ALL_STATES: [FlightState; 5] = [
+        FlightState::Surface,
+        FlightState::LowOrbit,
+        FlightState::HighOrbit,
+        FlightState::Space,
+        FlightState::Embarked,
+    ];
+
+    fn rocky_planet(mass_earth: f32, radius_earth: f32, surface_gravity_g: f32) -> PlanetPhysics {
+        PlanetPhysics {
+            mass_earth,
+            radius_earth,
+            surface_gravity_g,
+            atmosphere: AtmosphereType::None,
+            surface_pressure_atm: 0.0,
+            water_fraction: 0.0,
+            axial_tilt_deg: 0.0,
+        }
+    }
+
+    #[test]
+    fn adjacent_layers_map_to_their_move_type() {
+        use FlightState::*;
+
+        for (from, to, move_type) in [
+            (Surface, LowOrbit, "launch_to_orbit"),
+            (LowOrbit, HighOrbit, "raise_orbit"),
+            (HighOrbit, Space, "orbit_to_space"),
+            (Space, HighOrbit, "enter_orbit"),
+            (HighOrbit, LowOrbit, "lower_orbit"),
+            (LowOrbit, Surface, "land"),
+        ] {
+            let maneuver = Maneuver::between(from, to)
+                .unwrap_or_else(|| panic!("no maneuver from {from:?} to {to:?}"));
+            assert_eq!(maneuver.move_type(), move_type);
+        }
+    }
+
+    #[test]
+    fn skipped_layers_and_embarked_units_have_no_maneuver() {
+        use FlightState::*;
+
+        for (from, to) in [
+            (Surface, Space),
+            (Space, Surface),
+            (Surface, HighOrbit),
+            (HighOrbit, Surface),
+            (LowOrbit, Space),
+            (Space, LowOrbit),
+        ] {
+            assert_eq!(Maneuver::between(from, to), None, "{from:?} to {to:?}");
+        }
+        for state in ALL_STATES {
+            assert_eq!(Maneuver::between(Embarked, state), None);
+            assert_eq!(Maneuver::between(state, Embarked), None);
+            assert_eq!(Maneuver::between(state, state), None);
+        }
+    }
+
+    #[test]
+    fn launch_needs_more_thrust_than_surface_gravity() {
+        let scout = UnitStats::for_type("scout");
+        let heavy = rocky_planet(30.0, 2.0, scout.thrust_g as f32);
+
+        assert_eq!(Maneuver::LaunchToOrbit.duration_s(&scout, &heavy), None);
+        assert_eq!(
+            Maneuver::LaunchToOrbit.duration_s(&scout, &rocky_planet(30.0, 2.0, 4.0)),
+            None
+        );
+        assert!(
+            Maneuver::LaunchToOrbit
+                .duration_s(&scout, &rocky_planet(30.0, 2.0, 2.9))
+                .is_some()
+        );
+        // Up there gravity no longer holds the unit back
+        for maneuver in Maneuver::ALL
+            .into_iter()
+            .filter(|&maneuver| maneuver != Maneuver::LaunchToOrbit)
+        {
+            assert!(
+                maneuver.duration_s(&scout, &heavy).is_some(),
+                "{maneuver:?}"
+            );
+        }
+        // Ground units never fly
+        let ground = UnitStats::for_type("infantry");
+        assert_eq!(
+            Maneuver::Land.duration_s(&ground, &rocky_planet(1.0, 1.0, 1.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn maneuvers_take_at_least_the_minimum() {
+        let scout = UnitStats::for_type("scout");
+        let pebble = rocky_planet(1e-6, 0.01, 0.01);
+        for maneuver in Maneuver::ALL {
+            assert_eq!(maneuver.duration_s(&scout, &pebble), Some(MIN_MANEUVER_S));
+        }
+
+        for (mass_earth, radius_earth, gravity_g) in [
+            (0.01, 0.2, 0.25),
+            (1.0, 1.0, 1.0),
+            (5.0, 1.5, 2.2),
+            (300.0, 11.0, 2.5),
+        ] {
+            let planet = rocky_planet(mass_earth, radius_earth, gravity_g);
+            for maneuver in Maneuver::ALL {
+                let duration_s = maneuver.duration_s(&scout, &planet).unwrap();
+                assert!(
+                    duration_s >= MIN_MANEUVER_S,
+                    "{maneuver:?} took {duration_s}s"
+                );
+            }
+        }
+    }
+}
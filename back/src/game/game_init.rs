@@ -1,9 +1,23 @@
 use crate::game::proc_gen::galaxy::Galaxy;
 use crate::game::proc_gen::planet::Planet;
+use crate::game::proc_gen::seed::RootSeed;
 use crate::game::proc_gen::star_system::{BodyType, OrbitalBody, StarSystem};
 use crate::game::proc_gen::tile::{DynamicTileProperties, TileType};
 use crate::game::proc_gen::universe::should_spawn_galaxy;
+use crate::game::start_area::{FairnessBand, START_AREA_RINGS, StartAreaSurvey};
 use crate::maths::spiral_3d::Spiral3D;
+use anyhow::{Result, bail};
+use std::collections::{HashMap, HashSet};
+
+type Cell = (i32, i32, i32);
+
+// Home systems of different players stay at least this many grid cells apart (design doc:
+// players start alone), and each galaxy is searched this many cells out from its core
+const MIN_HOME_SYSTEM_DISTANCE: i32 = 6;
+const MAX_SYSTEM_SEARCHES: u32 = 8000;
+// Galaxy cells searched out from the universe origin, as a Chebyshev distance. Thousands of
+// galaxies: reaching the edge means the fairness band cannot be met, not that space ran out
+const MAX_GALAXY_SEARCH_RADIUS: i32 = 16;
 
 #[derive(Debug, Clone)]
 pub struct StartingLocation {
@@ -11,6 +25,7 @@ pub struct StartingLocation {
     pub star_system: StarSystem,
     pub planet: Planet,
     pub tile: DynamicTileProperties,
+    pub survey: StartAreaSurvey,
 }
 
 impl StartingLocation {
@@ -27,39 +42,120 @@ impl StartingLocation {
     }
 }
 
-pub fn find_starting_location(world_seed: u64) -> StartingLocation {
-    let mut galaxy_spiral = Spiral3D::new(0, 0, 0);
+/// What a new player's home must avoid: planets that already exist in the database, and the
+/// neighbourhood of every other player's home system.
+#[derive(Debug, Default)]
+pub struct SpawnExclusions {
+    // (galaxy, system, orbit index) of persisted planets
+    taken_planets: HashSet<(Cell, Cell, u32)>,
+    home_systems_by_galaxy: HashMap<Cell, Vec<Cell>>,
+}
+
+impl SpawnExclusions {
+    pub fn add_planet(
+        &mut self,
+        galaxy: Cell,
+        system: Cell,
+        orbit_index: u32,
+        moon_index: Option<u32>,
+        is_home: bool,
+    ) {
+        // Moons are never handed out as homes, but a settled moon still crowds its system
+        if moon_index.is_none() {
+            self.taken_planets.insert((galaxy, system, orbit_index));
+        }
+        if is_home {
+            self.home_systems_by_galaxy
+                .entry(galaxy)
+                .or_default()
+                .push(system);
+        }
+    }
+
+    /// Whether `location` is still acceptable, e.g. after other players settled meanwhile.
+    pub fn allows(&self, location: &StartingLocation, band: &FairnessBand) -> bool {
+        let galaxy = location.galaxy.position;
+        let system = location.star_system.position;
+
+        // A new neighbour also lowers the start's isolation score
+        let survey = StartAreaSurvey {
+            nearest_player_distance: self.nearest_home_distance(galaxy, system),
+            ..location.survey
+        };
+
+        !self.is_crowded(galaxy, system)
+            && !self.is_taken(galaxy, system, location.planet.orbit_index)
+            && band.contains(survey.score())
+    }
+
+    fn is_crowded(&self, galaxy: Cell, system: Cell) -> bool {
+        self.nearest_home_distance(galaxy, system)
+            .is_some_and(|distance| distance < MIN_HOME_SYSTEM_DISTANCE as f32)
+    }
+
+    /// Grid distance from `system` to the closest home system of the same galaxy.
+    fn nearest_home_distance(&self, galaxy: Cell, system: Cell) -> Option<f32> {
+        let homes = self.home_systems_by_galaxy.get(&galaxy)?;
+        homes
+            .iter()
+            .map(|home| {
+                let (dx, dy, dz) = (
+                    (home.0 - system.0) as f32,
+                    (home.1 - system.1) as f32,
+                    (home.2 - system.2) as f32,
+                );
+                (dx * dx + dy * dy + dz * dz).sqrt()
+            })
+            .min_by(f32::total_cmp)
+    }
+
+    fn is_taken(&self, galaxy: Cell, system: Cell, orbit_index: u32) -> bool {
+        self.taken_planets.contains(&(galaxy, system, orbit_index))
+    }
+}
+
+/// First viable home found walking outward, galaxy by galaxy and then system by system from
+/// each galaxy's core, that `exclusions` allows and whose surroundings score inside `band`.
+/// Homes therefore fill the universe in rings, and every player gets a comparable start.
+/// Fails once `MAX_GALAXY_SEARCH_RADIUS` is searched without a match.
+pub fn find_starting_location(
+    world_seed: u64,
+    generation_version: u32,
+    exclusions: &SpawnExclusions,
+    band: &FairnessBand,
+) -> Result<StartingLocation> {
+    let root = RootSeed::new(world_seed, generation_version);
+    let galaxy_spiral = Spiral3D::new(0, 0, 0)
+        .take_while(|&(x, y, z)| x.abs().max(y.abs()).max(z.abs()) <= MAX_GALAXY_SEARCH_RADIUS);
 
     // 1. Iterate through Galaxy spatial coordinates
-    while let Some(galaxy_pos) = galaxy_spiral.next() {
-        if !should_spawn_galaxy(world_seed, galaxy_pos) {
+    for galaxy_pos in galaxy_spiral {
+        if !should_spawn_galaxy(root, galaxy_pos) {
             continue;
         }
 
-        let galaxy = Galaxy::new(world_seed, galaxy_pos);
-        let mut system_spiral = Spiral3D::new(0, 0, 0);
-
-        // Limit star system search radius to avoid infinite loops inside a sparse galaxy
-        let mut searched_systems = 0;
-        const MAX_SYSTEM_SEARCHES: u32 = 500;
+        let galaxy = Galaxy::new(root, galaxy_pos);
+        // Limit star system search radius to avoid infinite loops inside a sparse galaxy, then
+        // move on to the next galaxy
+        let system_spiral = Spiral3D::new(0, 0, 0).take(MAX_SYSTEM_SEARCHES as usize);
 
         // 2. Iterate through Star System coordinates within the current galaxy
-        while let Some(star_pos) = system_spiral.next() {
-            searched_systems += 1;
-            if searched_systems > MAX_SYSTEM_SEARCHES {
-                break; // Move on to the next galaxy if this one yields no valid starts
-            }
-
-            if !galaxy.should_spawn_star_system(star_pos) {
+        for star_pos in system_spiral {
+            // Cheap neighbourhood check before paying for system generation
+            if exclusions.is_crowded(galaxy_pos, star_pos)
+                || !galaxy.should_spawn_star_system(star_pos)
+            {
                 continue;
             }
 
             // Bug fix from original code: pass `star_pos` here, not `galaxy.position`
-            let star_system = StarSystem::new(galaxy.seed, galaxy.galaxy_type, star_pos);
+            let star_system = StarSystem::new(&galaxy, star_pos);
 
             // 3. Find a terrestrial, habitable orbital body
             for body in &star_system.bodies {
-                if !StartingLocation::is_viable_planet(body) {
+                if !StartingLocation::is_viable_planet(body)
+                    || exclusions.is_taken(galaxy_pos, star_pos, body.index)
+                {
                     continue;
                 }
 
@@ -67,34 +163,64 @@ pub fn find_starting_location(world_seed: u64) -> StartingLocation {
                 let star_system_id = 0;
                 let planet = Planet::new(
                     star_system_id,
-                    star_system.seed,
-                    star_system.position,
-                    body.index,
-                    body.is_in_habitable_zone,
-                    body.semi_major_axis_au,
+                    &star_system,
+                    body,
+                    &star_system.host_star(body.host),
                 );
 
-                // 4. Search planet tiles for a valid spawn biome
-                for face in 0..6 {
-                    for u in 0..planet.subdivision {
-                        for v in 0..planet.subdivision {
-                            let tile = planet.query_tile(face, u, v);
-
-                            if StartingLocation::is_viable_start_tile(&tile) {
-                                // Found a complete match across all hierarchy levels!
-                                return StartingLocation {
-                                    galaxy,
-                                    star_system,
-                                    planet,
-                                    tile,
-                                };
-                            }
-                        }
+                // 4. Search planet tiles for a valid spawn biome with a fair surrounding area.
+                //    Tiles are generated on first use only, since neighbouring areas overlap
+                let topology = planet.topology();
+                let mut tiles: Vec<Option<DynamicTileProperties>> =
+                    vec![None; topology.tile_count() as usize];
+                let nearest_player_distance =
+                    exclusions.nearest_home_distance(galaxy_pos, star_pos);
+
+                for index in 0..topology.tile_count() {
+                    let tile = generated_tile(&planet, &mut tiles, index).clone();
+                    if !StartingLocation::is_viable_start_tile(&tile) {
+                        continue;
+                    }
+
+                    // Sorted so the float sums, and so the band decision, never vary
+                    let mut area: Vec<u32> = topology
+                        .tiles_within([index], START_AREA_RINGS)
+                        .into_iter()
+                        .collect();
+                    area.sort_unstable();
+                    let area: Vec<_> = area
+                        .into_iter()
+                        .map(|i| generated_tile(&planet, &mut tiles, i).clone())
+                        .collect();
+
+                    let survey = StartAreaSurvey::new(&area, nearest_player_distance);
+                    if survey.has_essentials() && band.contains(survey.score()) {
+                        // Found a complete match across all hierarchy levels!
+                        return Ok(StartingLocation {
+                            galaxy,
+                            star_system,
+                            planet,
+                            tile,
+                            survey,
+                        });
                     }
                 }
             }
         }
     }
 
-    panic!("Failed to find a valid starting location in the generated universe!");
+    bail!(
+        "no starting location scoring {} to {} within {} galaxy cells of the origin",
+        band.min,
+        band.max,
+        MAX_GALAXY_SEARCH_RADIUS
+    )
+}
+
+fn generated_tile<'a>(
+    planet: &Planet,
+    tiles: &'a mut [Option<DynamicTileProperties>],
+    index: u32,
+) -> &'a DynamicTileProperties {
+    tiles[index as usize].get_or_insert_with(|| planet.query_tile_at(index))
 }
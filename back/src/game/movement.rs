@@ -0,0 +1,317 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::game::proc_gen::goldberg::GoldbergTopology;
+use crate::game::proc_gen::planet::Planet;
+use crate::game::proc_gen::tile::TileType;
+
+// A ground unit walks into a plains tile in this many seconds, other terrain takes a multiple
+pub const GROUND_SECONDS_PER_TILE: f64 = 60.0;
+
+// Cheapest terrain factor, for the A* distance estimate
+const MIN_GROUND_COST: f64 = 1.0;
+
+/// Time factor for a ground unit to enter a tile of `tile_type`, `None` where it cannot go.
+pub fn ground_move_cost(tile_type: TileType) -> Option<f64> {
+    match tile_type {
+        TileType::Plains => Some(1.0),
+        TileType::Tundra => Some(1.25),
+        TileType::Desert | TileType::Forest => Some(1.5),
+        TileType::Snow | TileType::Jungle | TileType::IceSheet => Some(2.0),
+        TileType::Swamp => Some(2.5),
+        TileType::Mountain => Some(3.0),
+        // Seas, shallow or deep, need ships; lava is not crossed at all
+        TileType::Water | TileType::Ocean | TileType::Lava => None,
+    }
+}
+
+/// One tile of a path and the time factor paid to enter it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathStep {
+    pub tile: u32,
+    pub cost: f64,
+}
+
+/// Cheapest ground path from tile `from` to tile `to` of `planet`, found with A* over the tile
+/// graph (the adjacency `get_hex_neighbors` exposes). The steps exclude `from`, which the unit
+/// already stands on whatever its terrain. `None` if `to` cannot be reached on foot.
+pub fn find_ground_path(planet: &Planet, from: u32, to: u32) -> Option<Vec<PathStep>> {
+    let topology = planet.topology();
+
+    // Terrain is regenerated per tile, so only tiles the search touches are evaluated
+    let mut costs: HashMap<u32, Option<f64>> = HashMap::new();
+    let mut cost_of = |tile: u32| {
+        *costs
+            .entry(tile)
+            .or_insert_with(|| ground_move_cost(planet.query_tile_at(tile).tile_type))
+    };
+
+    cost_of(to)?;
+
+    let max_step = max_step_chord(&topology);
+    let goal = topology.position(to);
+    // No step covers more than `max_step` in a straight line, so this never overestimates
+    let estimate = |tile: u32| chord(topology.position(tile), goal) / max_step * MIN_GROUND_COST;
+
+    let mut best: HashMap<u32, f64> = HashMap::from([(from, 0.0)]);
+    let mut came_from: HashMap<u32, u32> = HashMap::new();
+    let mut frontier = BinaryHeap::from([Frontier {
+        estimate: estimate(from),
+        spent: 0.0,
+        tile: from,
+    }]);
+
+    while let Some(Frontier { spent, tile, .. }) = frontier.pop() {
+        if tile == to {
+            return Some(rebuild_path(&came_from, &mut cost_of, from, to));
+        }
+        if spent > best[&tile] {
+            continue; // Stale entry, the tile was reached cheaper since
+        }
+
+        for &neighbor in topology.neighbors(tile) {
+            let Some(cost) = cost_of(neighbor) else {
+                continue;
+            };
+            let spent = spent + cost;
+            if best.get(&neighbor).is_some_and(|&known| known <= spent) {
+                continue;
+            }
+
+            best.insert(neighbor, spent);
+            came_from.insert(neighbor, tile);
+            frontier.push(Frontier {
+                estimate: spent + estimate(neighbor),
+                spent,
+                tile: neighbor,
+            });
+        }
+    }
+
+    None
+}
+
+fn rebuild_path(
+    came_from: &HashMap<u32, u32>,
+    cost_of: &mut impl FnMut(u32) -> Option<f64>,
+    from: u32,
+    to: u32,
+) -> Vec<PathStep> {
+    let mut steps = Vec::new();
+    let mut tile = to;
+    while tile != from {
+        steps.push(PathStep {
+            tile,
+            cost: cost_of(tile).unwrap_or(MIN_GROUND_COST),
+        });
+        tile = came_from[&tile];
+    }
+    steps.reverse();
+    steps
+}
+
+/// Longest straight-line distance between two adjacent tile centres.
+fn max_step_chord(topology: &GoldbergTopology) -> f64 {
+    (0..topology.tile_count())
+        .flat_map(|tile| {
+            topology
+                .neighbors(tile)
+                .iter()
+                .map(move |&neighbor| chord(topology.position(tile), topology.position(neighbor)))
+        })
+        .fold(f64::MIN_POSITIVE, f64::max)
+}
+
+fn chord(a: [f32; 3], b: [f32; 3]) -> f64 {
+    (0..3)
+        .map(|axis| (a[axis] as f64 - b[axis] as f64).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+// Open tile of the search, the heap pops the lowest estimate first
+#[derive(Debug, PartialEq)]
+struct Frontier {
+    estimate: f64,
+    spent: f64,
+    tile: u32,
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| other.tile.cmp(&self.tile))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::proc_gen::chunk::{ChunkCoord, galaxies_in_chunk};
+    use crate::game::proc_gen::nearby::nearest_star_systems;
+    use crate::game::proc_gen::planet::PlanetType;
+    use crate::game::proc_gen::seed::{CURRENT_GENERATION_VERSION, RootSeed};
+    use crate::game::proc_gen::star_system::StarSystem;
+    use std::collections::BTreeSet;
+
+    // Small enough to search the whole sphere: 10 * 6^2 + 2 = 362 tiles
+    const SUBDIVISION: u32 = 6;
+    const PLANETS: usize = 6;
+
+    // A few terrestrial planets with walkable and blocked tiles, shrunk to `SUBDIVISION`
+    fn small_planets() -> Vec<(Planet, Vec<Option<f64>>)> {
+        let root = RootSeed::new(42, CURRENT_GENERATION_VERSION);
+        let galaxies = galaxies_in_chunk(root, ChunkCoord::new(0, 0, 0));
+        let galaxy = &galaxies[0];
+
+        let mut planets = Vec::new();
+        for nearby in nearest_star_systems(galaxy, (0, 0, 0), 10.0, 40) {
+            let system = StarSystem::new(galaxy, nearby.position);
+            for body in &system.bodies {
+                let star = system.host_star(body.host);
+                let mut planet = Planet::new(0, &system, body, &star);
+                if planet.class != PlanetType::Terrestrial {
+                    continue;
+                }
+                planet.subdivision = SUBDIVISION;
+                let costs = tile_costs(&planet);
+                let walkable = costs.iter().flatten().count();
+                if walkable > 1 && walkable < costs.len() {
+                    planets.push((planet, costs));
+                }
+                if planets.len() == PLANETS {
+                    return planets;
+                }
+            }
+        }
+        assert!(!planets.is_empty(), "no terrestrial planet to walk on");
+        planets
+    }
+
+    // Terrain is slow to generate: each test reads it once per tile
+    fn tile_costs(planet: &Planet) -> Vec<Option<f64>> {
+        (0..planet.topology().tile_count())
+            .map(|tile| ground_move_cost(planet.query_tile_at(tile).tile_type))
+            .collect()
+    }
+
+    fn walkable_tiles(costs: &[Option<f64>]) -> Vec<u32> {
+        (0..costs.len() as u32)
+            .filter(|&tile| costs[tile as usize].is_some())
+            .collect()
+    }
+
+    // Plain Dijkstra from `from`: cheapest cost to every reachable tile
+    fn dijkstra(planet: &Planet, costs: &[Option<f64>], from: u32) -> HashMap<u32, f64> {
+        let topology = planet.topology();
+        let mut best = HashMap::from([(from, 0.0)]);
+        let mut open = BTreeSet::from([(0u64, from)]);
+        while let Some((spent_bits, tile)) = open.pop_first() {
+            let spent = f64::from_bits(spent_bits);
+            for &neighbor in topology.neighbors(tile) {
+                let Some(cost) = costs[neighbor as usize] else {
+                    continue;
+                };
+                let spent = spent + cost;
+                if best.get(&neighbor).is_none_or(|&known| spent < known) {
+                    best.insert(neighbor, spent);
+                    open.insert((spent.to_bits(), neighbor));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn paths_walk_adjacent_tiles_and_avoid_seas_and_lava() {
+        for (planet, costs) in small_planets() {
+            let topology = planet.topology();
+            let walkable = walkable_tiles(&costs);
+            let from = walkable[0];
+            for &to in walkable.iter().step_by(13) {
+                let Some(path) = find_ground_path(&planet, from, to) else {
+                    continue;
+                };
+                assert_eq!(path.last().map_or(from, |step| step.tile), to);
+
+                let mut previous = from;
+                for step in &path {
+                    assert!(topology.neighbors(previous).contains(&step.tile));
+                    let tile_type = planet.query_tile_at(step.tile).tile_type;
+                    assert!(
+                        !matches!(
+                            tile_type,
+                            TileType::Ocean | TileType::Water | TileType::Lava
+                        ),
+                        "path of planet {} enters a {tile_type:?} tile",
+                        planet.seed
+                    );
+                    assert_eq!(Some(step.cost), costs[step.tile as usize]);
+                    previous = step.tile;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unreachable_targets_have_no_path() {
+        let mut cut_off = 0;
+        for (planet, costs) in small_planets() {
+            let walkable = walkable_tiles(&costs);
+            let from = walkable[0];
+
+            let blocked = costs.iter().position(Option::is_none).unwrap() as u32;
+            assert_eq!(find_ground_path(&planet, from, blocked), None);
+
+            // Walkable but on another landmass than `from`
+            let reachable = dijkstra(&planet, &costs, from);
+            if let Some(&island) = walkable.iter().find(|tile| !reachable.contains_key(tile)) {
+                assert_eq!(find_ground_path(&planet, from, island), None);
+                cut_off += 1;
+            }
+        }
+        assert!(cut_off > 0, "no planet has two landmasses");
+    }
+
+    #[test]
+    fn a_path_to_the_tile_stood_on_is_empty() {
+        for (planet, costs) in small_planets() {
+            for tile in walkable_tiles(&costs).into_iter().take(5) {
+                assert_eq!(find_ground_path(&planet, tile, tile), Some(Vec::new()));
+            }
+        }
+    }
+
+    #[test]
+    fn costs_as_little_as_dijkstra() {
+        for (planet, costs) in small_planets() {
+            let walkable = walkable_tiles(&costs);
+            for &from in walkable.iter().step_by(61) {
+                let cheapest = dijkstra(&planet, &costs, from);
+                for &to in walkable.iter().step_by(11) {
+                    let path = find_ground_path(&planet, from, to);
+                    assert_eq!(path.is_some(), cheapest.contains_key(&to));
+                    if let Some(path) = path {
+                        let cost: f64 = path.iter().map(|step| step.cost).sum();
+                        assert!(
+                            (cost - cheapest[&to]).abs() < 1e-9,
+                            "planet {} from {from} to {to}: {cost} instead of {}",
+                            planet.seed,
+                            cheapest[&to]
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
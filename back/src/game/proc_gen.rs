@@ -1,6 +1,17 @@
+pub mod chunk;
+pub mod climate;
+pub mod deposit;
 pub mod galaxy;
+pub mod goldberg;
+pub mod nearby;
+pub mod noise;
+pub mod orbit;
+pub mod physics;
 pub mod planet;
 pub mod seed;
+#[cfg(test)]
+mod snapshot;
+pub mod space_object;
 pub mod star_system;
 pub mod tile;
 pub mod universe;
@@ -12,8 +23,24 @@ pub mod universe;
 
 // galaxy.rs: Contains struct Galaxy. It takes a coordinate, checks if it should exist, and calculates galaxy-specific density (like spiral arms) to determine where star systems spawn.
 
-// star_system.rs: Contains struct StarSystem. It determines how many planets spawn based on local system density/mass.
+// star_system.rs: Contains struct StarSystem. It determines how many planets spawn based on local system density/mass, and their moons and rings.
+
+// space_object.rs: The small bodies of a system: individual asteroids of its belts and drifting anomalies. Only what players do to them (mining, claims) is stored.
+
+// chunk.rs: Cuts the galaxy and star system grids into fixed-size cubes so the client can browse space piece by piece. Nothing is stored: a chunk is regenerated from the seeds on demand.
+
+// nearby.rs: Finds the star systems around a point of a galaxy (within a radius, or the nearest N) without testing every cell: blocks whose density bound rules out any system are skipped whole.
+
+// orbit.rs: Keplerian orbits of the bodies around their stars. Positions follow from the game time since the world started, so nothing moving is stored.
 
 // planet.rs: Contains struct Planet. Handles biomes and terrain generation.
 
+// physics.rs: The bulk profile of a planet (mass, radius, gravity, atmosphere, water, tilt). Drives tile resolution and climate.
+
+// deposit.rs: Where rare deposits form on a planet's tiles: veins driven by biome, relief, planet class and the host star's metallicity.
+
+// snapshot.rs (tests only): Golden dump of a fixed sample of the universe. Fails on any drift of the generator for the current generation version.
+
+// goldberg.rs: The planet tile graph (icosahedral Goldberg polyhedron). Maps (face, u, v) to stable tile indices, positions and neighbours.
+
 // It keeps your code perfectly encapsulated. universe.rs doesn't need to know how a planet is generated; it only cares about galaxies.
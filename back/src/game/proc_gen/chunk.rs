@@ -0,0 +1,68 @@
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::seed::RootSeed;
+use crate::game::proc_gen::star_system::StarSystem;
+use crate::game::proc_gen::universe::should_spawn_galaxy;
+
+// Edge length, in grid cells, of the cubes the universe is browsed in.
+// Galaxies live on the cosmic grid, star systems on their galaxy's own grid.
+pub const GALAXY_CHUNK_SIZE: i32 = 16;
+pub const STAR_SYSTEM_CHUNK_SIZE: i32 = 16;
+
+type Cell = (i32, i32, i32);
+
+/// Address of a cube of `size`³ grid cells: chunk (0, 0, 0) covers cells 0..size on each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkCoord {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl ChunkCoord {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Inclusive (min, max) cells of the chunk, or None if it runs off the i32 grid.
+    pub fn bounds(&self, size: i32) -> Option<(Cell, Cell)> {
+        let axis = |c: i32| {
+            let min = c.checked_mul(size)?;
+            Some((min, min.checked_add(size - 1)?))
+        };
+        let (x0, x1) = axis(self.x)?;
+        let (y0, y1) = axis(self.y)?;
+        let (z0, z1) = axis(self.z)?;
+        Some(((x0, y0, z0), (x1, y1, z1)))
+    }
+
+    // Cells in x, y, z order so generation always returns the same sequence
+    fn cells(&self, size: i32) -> impl Iterator<Item = Cell> {
+        self.bounds(size).into_iter().flat_map(|(min, max)| {
+            (min.0..=max.0).flat_map(move |x| {
+                (min.1..=max.1).flat_map(move |y| (min.2..=max.2).map(move |z| (x, y, z)))
+            })
+        })
+    }
+}
+
+/// Every galaxy of a cosmic chunk of the world at `root`.
+pub fn galaxies_in_chunk(root: RootSeed, chunk: ChunkCoord) -> Vec<Galaxy> {
+    chunk
+        .cells(GALAXY_CHUNK_SIZE)
+        .filter(|&pos| should_spawn_galaxy(root, pos))
+        .map(|pos| Galaxy::new(root, pos))
+        .collect()
+}
+
+/// Every star system of a chunk of `galaxy`'s grid.
+pub fn star_systems_in_chunk(galaxy: &Galaxy, chunk: ChunkCoord) -> Vec<StarSystem> {
+    let max_density = chunk
+        .bounds(STAR_SYSTEM_CHUNK_SIZE)
+        .map_or(0.0, |(min, max)| galaxy.max_star_system_density(min, max));
+
+    chunk
+        .cells(STAR_SYSTEM_CHUNK_SIZE)
+        .filter(|&pos| galaxy.should_spawn_star_system_below(pos, max_density))
+        .map(|pos| StarSystem::new(galaxy, pos))
+        .collect()
+}
@@ -0,0 +1,110 @@
+use crate::game::proc_gen::physics::PlanetPhysics;
+use crate::game::proc_gen::star_system::Star;
+
+// Elevation scale (0.0 .. 1.0) reference level shared with the biome rules in tile.rs.
+// Sea level is per planet (see `PlanetClimate::sea_level`), mountains start at a fixed height.
+pub const MOUNTAIN_LEVEL: f32 = 0.80;
+// Elevation is roughly bell-shaped around 0.5, so this maps water fraction to a sea level that
+// floods about that share of tiles
+const SEA_LEVEL_PER_WATER_FRACTION: f32 = 1.0;
+const DEEP_OCEAN_DEPTH: f32 = 0.10;
+
+// Equilibrium temperature of a body at 1 AU from a 1 L_sun star, with Earth's 0.3 albedo
+const EQUILIBRIUM_TEMP_AT_1AU_K: f32 = 255.0;
+// Equator-to-pole temperature gap of a planet with no axial tilt
+const MAX_POLE_EQUATOR_GAP_K: f32 = 60.0;
+// Cooling between sea level and the highest peaks (elevation 1.0)
+const MOUNTAIN_LAPSE_K: f32 = 60.0;
+// Deep space floor for planets around dark bodies (black holes)
+const MIN_SURFACE_TEMP_K: f32 = 40.0;
+
+pub const WATER_FREEZING_K: f32 = 271.0;
+pub const WATER_BOILING_K: f32 = 373.0;
+
+/// Per-planet climate inputs, computed once from the host star and orbit.
+#[derive(Debug, Clone, Copy)]
+pub struct PlanetClimate {
+    pub mean_surface_temp_k: f32,
+    pub pole_equator_gap_k: f32,
+    // How well the star's light drives photosynthesis: dim red dwarfs and harsh blue stars
+    // grow fewer forests than sun-like stars. 0.0 (none) to 1.0.
+    pub vegetation_factor: f32,
+    // Elevations below these are seas and deep oceans. A dry planet's sea level sits under
+    // nearly all of its terrain.
+    pub sea_level: f32,
+    pub deep_ocean_level: f32,
+}
+
+/// Airless equilibrium temperature of an orbit, before any greenhouse warming.
+pub fn equilibrium_temperature_k(star: &Star, semi_major_axis_au: f32) -> f32 {
+    EQUILIBRIUM_TEMP_AT_1AU_K * star.luminosity.max(0.0).powf(0.25)
+        / semi_major_axis_au.max(0.01).sqrt()
+}
+
+impl PlanetClimate {
+    pub fn new(star: &Star, semi_major_axis_au: f32, physics: &PlanetPhysics) -> Self {
+        let mean_surface_temp_k = (equilibrium_temperature_k(star, semi_major_axis_au)
+            + physics.greenhouse_warming_k())
+        .max(MIN_SURFACE_TEMP_K);
+
+        // Tilt spreads sunlight towards the poles and flattens the gradient, while thick air
+        // carries heat poleward and flattens it further
+        let pole_equator_gap_k = MAX_POLE_EQUATOR_GAP_K
+            * (1.0 - 0.6 * physics.axial_tilt_deg.to_radians().sin())
+            / (1.0 + 0.1 * physics.surface_pressure_atm).min(3.0);
+
+        let sea_level = SEA_LEVEL_PER_WATER_FRACTION * physics.water_fraction;
+
+        Self {
+            mean_surface_temp_k,
+            pole_equator_gap_k,
+            // No surface water, no water cycle, nothing grows
+            vegetation_factor: if physics.water_fraction > 0.0 {
+                vegetation_factor(star.surface_temp)
+            } else {
+                0.0
+            },
+            sea_level,
+            deep_ocean_level: sea_level - DEEP_OCEAN_DEPTH,
+        }
+    }
+
+    /// Annual mean surface temperature at a latitude (given as sin, i.e. the polar axis
+    /// coordinate on the unit sphere) and elevation.
+    pub fn surface_temperature_k(&self, sin_latitude: f32, elevation: f32) -> f32 {
+        // sin² averages to 1/3 over a sphere, so 0.3 keeps the planet mean close to the input
+        let latitude_offset = self.pole_equator_gap_k * (0.3 - sin_latitude * sin_latitude);
+        let altitude_offset = MOUNTAIN_LAPSE_K * (elevation - self.sea_level).max(0.0);
+
+        (self.mean_surface_temp_k + latitude_offset - altitude_offset).max(MIN_SURFACE_TEMP_K)
+    }
+}
+
+/// Prevailing wind direction from the three-cell circulation model: easterly trade winds
+/// below 30°, westerlies between 30° and 60°, polar easterlies above.
+/// Returns the longitude step (radians, sign only) pointing upwind.
+pub fn upwind_direction(sin_latitude: f32) -> f32 {
+    let latitude_deg = sin_latitude.abs().min(1.0).asin().to_degrees();
+
+    if (30.0..60.0).contains(&latitude_deg) {
+        -1.0 // Wind from the west
+    } else {
+        1.0 // Wind from the east
+    }
+}
+
+fn vegetation_factor(star_surface_temp: u32) -> f32 {
+    let temp = star_surface_temp as f32;
+
+    if temp <= 0.0 {
+        0.0
+    } else if temp < 5200.0 {
+        // M and K dwarfs: 0.4 at the coolest red dwarfs, full at sun-like stars
+        (0.4 + 0.6 * (temp - 2400.0) / (5200.0 - 2400.0)).clamp(0.4, 1.0)
+    } else if temp <= 7500.0 {
+        1.0
+    } else {
+        // A, B and O stars: UV-heavy light is harder on surface life
+        (1.0 - 0.5 * (temp - 7500.0) / (30000.0 - 7500.0)).clamp(0.5, 1.0)
+    }
+}
@@ -0,0 +1,347 @@
+use crate::game::proc_gen::noise::{Fbm, NoiseField};
+use crate::game::proc_gen::physics::PlanetPhysics;
+use crate::game::proc_gen::planet::PlanetType;
+use crate::game::proc_gen::seed::{TILE_DEPOSIT_TAG, TILE_VEIN_NOISE_TAG, derive_seed};
+use crate::game::proc_gen::star_system::{Star, StarType};
+use crate::game::proc_gen::tile::TileType;
+use crate::game::resource::{Deposit, DepositRichness, Resource};
+
+// Every resource has its own vein field per planet. Its frequency (in unit-sphere space)
+// follows the tile resolution, so veins span a few neighbouring tiles on any body: 2.0 on an
+// Earth-sized planet.
+const VEIN_OCTAVES: u32 = 2;
+const VEIN_FREQUENCY_PER_SUBDIVISION: f64 = 0.1;
+// A tile lies in a vein where the field exceeds the threshold. At abundance 1.0 it covers
+// about 3% of the tiles; each tenfold of abundance lowers it by VEIN_THRESHOLD_PER_DECADE,
+// which roughly tenfolds the covered area.
+const VEIN_THRESHOLD: f64 = 0.58;
+const VEIN_THRESHOLD_PER_DECADE: f64 = 0.22;
+const MAX_ABUNDANCE: f32 = 2.0;
+// Per-tile jitter of the vein margin, for ragged vein edges
+const VEIN_EDGE_JITTER: f64 = 0.02;
+// Margins past the threshold above which a deposit is Standard, then Rich
+const STANDARD_MARGIN: f64 = 0.05;
+const RICH_MARGIN: f64 = 0.12;
+
+const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
+
+/// Planet-wide inputs of deposit generation, fixed by the planet's class and host star.
+#[derive(Debug, Clone, Copy)]
+pub struct PlanetGeology {
+    pub class: PlanetType,
+    // [Fe/H] of the host star, inherited from the cloud the system formed in
+    pub metallicity: f32,
+    // Fossil fuels are buried life: only worlds whose air was oxygenated by it have any
+    pub biosphere: bool,
+    // Black hole hosts warp space enough to trap dark matter
+    pub exotic: bool,
+    pub vein_fbm: Fbm,
+}
+
+impl PlanetGeology {
+    pub fn new(class: PlanetType, star: &Star, physics: &PlanetPhysics) -> Self {
+        Self {
+            class,
+            metallicity: star.metallicity,
+            biosphere: physics.is_breathable(),
+            exotic: !matches!(star.star_type, StarType::Spectral(_)),
+            vein_fbm: Fbm::new(
+                VEIN_OCTAVES,
+                VEIN_FREQUENCY_PER_SUBDIVISION * physics.subdivision() as f64,
+            ),
+        }
+    }
+
+    /// Relative likelihood of `resource` on a tile, 0.0 where it cannot occur.
+    fn abundance(&self, resource: Resource, tile_type: TileType, elevation: f32) -> f32 {
+        let class_factor = match (self.class, resource) {
+            // Giants only expose their atmosphere
+            (PlanetType::GasGiant | PlanetType::IceGiant, Resource::Deuterium) => return 1.0,
+            (PlanetType::GasGiant | PlanetType::IceGiant, _) => return 0.0,
+            (_, Resource::Coal | Resource::Petrol) if !self.biosphere => return 0.0,
+            // Bare metallic cores, and the ilmenite-rich basalts of airless moons
+            (PlanetType::AsteroidField, resource) if resource.is_metal() => 2.0,
+            (PlanetType::Moon, Resource::Titanium) => 1.5,
+            _ => 1.0,
+        };
+
+        let mut abundance = class_factor * biome_affinity(resource, tile_type);
+
+        if resource.is_metal() {
+            // Ores are exposed by uplift: highlands carry more than lowlands
+            abundance *= 10f32.powf(0.8 * self.metallicity) * (0.5 + elevation);
+        }
+        if resource == Resource::DarkMatter && self.exotic {
+            abundance *= 10.0;
+        }
+
+        abundance.min(MAX_ABUNDANCE)
+    }
+}
+
+/// Share of a biome's tiles that can hold each resource, before planet-wide factors.
+fn biome_affinity(resource: Resource, tile_type: TileType) -> f32 {
+    use TileType::*;
+
+    match resource {
+        Resource::Coal => match tile_type {
+            Swamp => 1.0,
+            Jungle => 0.8,
+            Forest => 0.6,
+            Plains | Tundra => 0.4,
+            _ => 0.0,
+        },
+        Resource::Petrol => match tile_type {
+            Desert => 0.8,
+            Swamp => 0.7,
+            Tundra | Water => 0.6,
+            Plains => 0.3,
+            _ => 0.0,
+        },
+        Resource::Iron => match tile_type {
+            Mountain => 1.0,
+            Snow | Desert => 0.6,
+            Lava => 0.5,
+            Tundra => 0.4,
+            Plains | IceSheet => 0.3,
+            Forest | Jungle => 0.2,
+            Water | Swamp => 0.1,
+            Ocean => 0.0,
+        },
+        Resource::Gold => match tile_type {
+            Mountain => 0.5,
+            Snow => 0.3,
+            Jungle | Water => 0.15, // Placer gold washed down by rivers
+            _ => 0.0,
+        },
+        Resource::Gems => match tile_type {
+            Mountain => 0.5,
+            Lava => 0.4, // Kimberlite pipes
+            Desert => 0.2,
+            _ => 0.0,
+        },
+        Resource::Uranium => match tile_type {
+            Desert => 0.6,
+            Mountain => 0.4,
+            Plains | Tundra => 0.2,
+            _ => 0.0,
+        },
+        Resource::RareEarths => match tile_type {
+            Mountain => 0.5,
+            Lava => 0.4,
+            Jungle | Snow => 0.2, // Weathered clays
+            _ => 0.0,
+        },
+        Resource::Silicon => match tile_type {
+            Desert => 1.0,
+            Lava => 0.4,
+            Plains | Tundra | IceSheet => 0.3,
+            _ => 0.0,
+        },
+        Resource::Titanium => match tile_type {
+            Lava => 0.8,
+            Mountain => 0.4,
+            Desert | IceSheet => 0.3,
+            _ => 0.0,
+        },
+        Resource::Deuterium => match tile_type {
+            Ocean => 1.0,
+            IceSheet => 0.8,
+            Water => 0.5,
+            Snow => 0.3,
+            _ => 0.0,
+        },
+        // Spread thinly and evenly, wherever space itself is
+        Resource::DarkMatter => 0.05,
+    }
+}
+
+/// Deposit of a tile, if it lies in a vein. Every resource the tile's geology allows is
+/// tested against its own vein field; the deepest vein wins and its depth sets the richness.
+pub fn deposit_at(
+    planet_seed: u64,
+    generation_version: u32,
+    geology: &PlanetGeology,
+    tile_seed: u64,
+    position: [f64; 3],
+    tile_type: TileType,
+    elevation: f32,
+) -> Option<Deposit> {
+    let [x, y, z] = position;
+    let jitter_roll = (derive_seed(tile_seed, generation_version, TILE_DEPOSIT_TAG, &[]) as f64)
+        * U64_TO_UNIT_F64;
+    let jitter = VEIN_EDGE_JITTER * (jitter_roll - 0.5);
+
+    let mut deepest: Option<(Resource, f64)> = None;
+    for resource in Resource::ALL {
+        let abundance = geology.abundance(resource, tile_type, elevation);
+        if abundance <= 0.0 {
+            continue;
+        }
+
+        let field = NoiseField::new(derive_seed(
+            planet_seed,
+            generation_version,
+            TILE_VEIN_NOISE_TAG,
+            &[resource as i64],
+        ));
+        let threshold = VEIN_THRESHOLD - VEIN_THRESHOLD_PER_DECADE * (abundance as f64).log10();
+        let margin = field.fbm(x, y, z, geology.vein_fbm) + jitter - threshold;
+
+        if margin > 0.0 && deepest.is_none_or(|(_, best)| margin > best) {
+            deepest = Some((resource, margin));
+        }
+    }
+
+    deepest.map(|(resource, margin)| Deposit {
+        resource,
+        richness: if margin > RICH_MARGIN {
+            DepositRichness::Rich
+        } else if margin > STANDARD_MARGIN {
+            DepositRichness::Standard
+        } else {
+            DepositRichness::Poor
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::proc_gen::goldberg::GoldbergTopology;
+    use crate::game::proc_gen::seed::{CURRENT_GENERATION_VERSION, TILE_TAG};
+
+    const PLANET_SEED: u64 = 0xDE9051;
+    // An Earth-sized planet: about 4000 tiles
+    const SUBDIVISION: u32 = 20;
+
+    fn geology(class: PlanetType, metallicity: f32, biosphere: bool) -> PlanetGeology {
+        PlanetGeology {
+            class,
+            metallicity,
+            biosphere,
+            exotic: false,
+            vein_fbm: Fbm::new(
+                VEIN_OCTAVES,
+                VEIN_FREQUENCY_PER_SUBDIVISION * SUBDIVISION as f64,
+            ),
+        }
+    }
+
+    /// Deposit of every tile of a planet covered with `tile_type`, by tile index.
+    fn deposits(geology: &PlanetGeology, tile_type: TileType) -> Vec<Option<Deposit>> {
+        let topology = GoldbergTopology::for_subdivision(SUBDIVISION);
+        (0..topology.tile_count())
+            .map(|index| {
+                let tile_seed = derive_seed(
+                    PLANET_SEED,
+                    CURRENT_GENERATION_VERSION,
+                    TILE_TAG,
+                    &[index as i64],
+                );
+                deposit_at(
+                    PLANET_SEED,
+                    CURRENT_GENERATION_VERSION,
+                    geology,
+                    tile_seed,
+                    topology.position(index).map(f64::from),
+                    tile_type,
+                    0.5,
+                )
+            })
+            .collect()
+    }
+
+    fn count(deposits: &[Option<Deposit>], matches: impl Fn(Resource) -> bool) -> usize {
+        deposits
+            .iter()
+            .flatten()
+            .filter(|deposit| matches(deposit.resource))
+            .count()
+    }
+
+    #[test]
+    fn fossil_fuels_need_a_biosphere() {
+        let lifeless = geology(PlanetType::Terrestrial, 0.0, false);
+        let living = geology(PlanetType::Terrestrial, 0.0, true);
+        let fossil = |resource| matches!(resource, Resource::Coal | Resource::Petrol);
+
+        for tile_type in TileType::ALL {
+            for resource in [Resource::Coal, Resource::Petrol] {
+                assert_eq!(lifeless.abundance(resource, tile_type, 0.5), 0.0);
+            }
+        }
+        for tile_type in [TileType::Swamp, TileType::Desert] {
+            assert_eq!(count(&deposits(&lifeless, tile_type), fossil), 0);
+            assert!(count(&deposits(&living, tile_type), fossil) > 0);
+        }
+    }
+
+    #[test]
+    fn giants_only_yield_deuterium() {
+        for class in [PlanetType::GasGiant, PlanetType::IceGiant] {
+            let giant = geology(class, 0.5, true);
+            for resource in Resource::ALL {
+                let expected = if resource == Resource::Deuterium {
+                    1.0
+                } else {
+                    0.0
+                };
+                assert_eq!(giant.abundance(resource, TileType::Ocean, 0.5), expected);
+            }
+
+            let giant_deposits = deposits(&giant, TileType::Ocean);
+            assert!(count(&giant_deposits, |resource| resource == Resource::Deuterium) > 0);
+            assert_eq!(
+                count(&giant_deposits, |resource| resource != Resource::Deuterium),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn metallicity_raises_metal_counts() {
+        let metals = |metallicity| {
+            count(
+                &deposits(
+                    &geology(PlanetType::Terrestrial, metallicity, false),
+                    TileType::Mountain,
+                ),
+                |resource| resource.is_metal(),
+            )
+        };
+
+        let (poor, solar, rich) = (metals(-0.5), metals(0.0), metals(0.5));
+        assert!(poor < solar && solar < rich, "{poor} {solar} {rich}");
+    }
+
+    #[test]
+    fn deposits_cluster_into_veins() {
+        let topology = GoldbergTopology::for_subdivision(SUBDIVISION);
+        let iron = |deposit: &Option<Deposit>| {
+            deposit.is_some_and(|deposit| deposit.resource == Resource::Iron)
+        };
+        let tiles = deposits(
+            &geology(PlanetType::Terrestrial, 0.0, false),
+            TileType::Mountain,
+        );
+
+        let base_rate =
+            tiles.iter().filter(|deposit| iron(deposit)).count() as f64 / tiles.len() as f64;
+        assert!(base_rate > 0.0 && base_rate < 0.5, "base rate {base_rate}");
+
+        // Share of iron tiles' neighbours holding iron too
+        let (mut pairs, mut both) = (0, 0);
+        for index in (0..topology.tile_count()).filter(|&index| iron(&tiles[index as usize])) {
+            for &neighbor in topology.neighbors(index) {
+                pairs += 1;
+                both += iron(&tiles[neighbor as usize]) as u32;
+            }
+        }
+        let co_occurrence = both as f64 / pairs as f64;
+        assert!(
+            co_occurrence > 2.0 * base_rate,
+            "co-occurrence {co_occurrence}, base rate {base_rate}"
+        );
+    }
+}
@@ -1,9 +1,32 @@
+use crate::game::proc_gen::noise::{Fbm, NoiseField};
 use crate::game::proc_gen::seed::{
-    GALAXY_FAST_3D_NOISE_TAG, GALAXY_TAG, GALAXY_TYPE_TAG, STAR_SYSTEM_SPAWN_TAG, derive_seed,
+    GALAXY_IRREGULAR_NOISE_TAG, GALAXY_STRUCTURE_TAG, GALAXY_TAG, GALAXY_TYPE_TAG, RootSeed,
+    STAR_SYSTEM_SPAWN_TAG, derive_seed,
 };
 
 const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
-const GALAXY_RADIUS: f32 = 10000.0;
+
+// Below this density a cell never holds a star system
+pub const MIN_SPAWN_DENSITY: f32 = 0.0001;
+
+// Irregular galaxies: a few clumpy star-forming regions across the normalised radius
+const IRREGULAR_CLUMP_FBM: Fbm = Fbm::new(3, 3.0);
+
+// Spiral arms start this far out (normalised radius) when there is no bar to start them
+const ARM_ORIGIN: f32 = 0.05;
+// Bars are this many times longer than they are wide
+const BAR_ASPECT: f32 = 3.0;
+
+// One component of GALAXY_STRUCTURE_TAG per structural parameter
+const ROLL_RADIUS: i64 = 0;
+const ROLL_ARMS: i64 = 1;
+const ROLL_PITCH: i64 = 2;
+const ROLL_BAR: i64 = 3;
+const ROLL_BAR_LENGTH: i64 = 4;
+const ROLL_BULGE: i64 = 5;
+const ROLL_THICKNESS: i64 = 6;
+const ROLL_INCLINATION: i64 = 7;
+const ROLL_POSITION_ANGLE: i64 = 8;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GalaxyType {
@@ -13,32 +36,181 @@ pub enum GalaxyType {
     Irregular = 3,
 }
 
+impl GalaxyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GalaxyType::Spiral => "spiral",
+            GalaxyType::Lenticular => "lenticular",
+            GalaxyType::Elliptical => "elliptical",
+            GalaxyType::Irregular => "irregular",
+        }
+    }
+}
+
+/// Seeded shape of one galaxy. Lengths other than `radius` are fractions of the radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GalaxyStructure {
+    pub radius: f32,    // In star system grid cells
+    pub arm_count: u32, // 0 for galaxies without arms
+    // Angle between an arm and the circle it crosses: low pitches wind tightly
+    pub arm_pitch_deg: f32,
+    pub bar_length: Option<f32>, // Half-length of the central bar, None if unbarred
+    // Scale length of the bulge, or of the whole body for ellipticals (0.0 for irregulars)
+    pub bulge_radius: f32,
+    // Disk scale height, or polar axis ratio for ellipticals and irregulars
+    pub thickness: f32,
+    // Orientation of the galactic plane in the grid: tilt away from the grid's x-y plane, then
+    // the rotation of the tilted plane about the z axis
+    pub inclination_deg: f32,
+    pub position_angle_deg: f32,
+}
+
+impl GalaxyStructure {
+    fn new(galaxy_seed: u64, generation_version: u32, galaxy_type: GalaxyType) -> Self {
+        let roll = |component: i64| {
+            ((derive_seed(
+                galaxy_seed,
+                generation_version,
+                GALAXY_STRUCTURE_TAG,
+                &[component],
+            ) as f64)
+                * U64_TO_UNIT_F64) as f32
+        };
+        let between = |min: f32, max: f32, component: i64| min + (max - min) * roll(component);
+
+        let (radius, bulge_radius, thickness) = match galaxy_type {
+            GalaxyType::Spiral => (
+                between(6000.0, 14000.0, ROLL_RADIUS),
+                between(0.1, 0.3, ROLL_BULGE),
+                between(0.08, 0.18, ROLL_THICKNESS),
+            ),
+            GalaxyType::Lenticular => (
+                between(5000.0, 11000.0, ROLL_RADIUS),
+                between(0.15, 0.35, ROLL_BULGE),
+                between(0.12, 0.25, ROLL_THICKNESS),
+            ),
+            // From compact dwarfs to giant cD galaxies, round (E0) to flattened (E7)
+            GalaxyType::Elliptical => (
+                3000.0 * (20000.0f32 / 3000.0).powf(roll(ROLL_RADIUS)),
+                between(0.2, 0.4, ROLL_BULGE),
+                between(0.3, 1.0, ROLL_THICKNESS),
+            ),
+            GalaxyType::Irregular => (
+                between(2000.0, 6000.0, ROLL_RADIUS),
+                0.0,
+                between(0.3, 0.8, ROLL_THICKNESS),
+            ),
+        };
+
+        // Grand-design two-armed spirals dominate, then four-armed, then three
+        let arm_count = match galaxy_type {
+            GalaxyType::Spiral => match roll(ROLL_ARMS) {
+                x if x < 0.6 => 2,
+                x if x < 0.85 => 4,
+                _ => 3,
+            },
+            _ => 0,
+        };
+
+        // About two thirds of spirals and a third of lenticulars are barred
+        let bar_chance = match galaxy_type {
+            GalaxyType::Spiral => 0.65,
+            GalaxyType::Lenticular => 0.3,
+            _ => 0.0,
+        };
+        let bar_length = (roll(ROLL_BAR) < bar_chance).then(|| between(0.1, 0.3, ROLL_BAR_LENGTH));
+
+        Self {
+            radius,
+            arm_count,
+            arm_pitch_deg: if arm_count > 0 {
+                between(10.0, 30.0, ROLL_PITCH)
+            } else {
+                0.0
+            },
+            bar_length,
+            bulge_radius,
+            thickness,
+            // Uniform over orientations: cos(inclination) is uniform
+            inclination_deg: roll(ROLL_INCLINATION).acos().to_degrees(),
+            position_angle_deg: between(0.0, 360.0, ROLL_POSITION_ANGLE),
+        }
+    }
+
+    /// Grid position in the galaxy's own frame, normalised by its radius: the disk lies in
+    /// the frame's x-y plane and the bar along its x axis.
+    fn local_position(&self, position: (i32, i32, i32)) -> [f32; 3] {
+        let (x, y, z) = (
+            position.0 as f32 / self.radius,
+            position.1 as f32 / self.radius,
+            position.2 as f32 / self.radius,
+        );
+
+        // Undo the rotation about z, then the tilt about x
+        let (sin_pa, cos_pa) = self.position_angle_deg.to_radians().sin_cos();
+        let (x, y) = (x * cos_pa + y * sin_pa, -x * sin_pa + y * cos_pa);
+        let (sin_i, cos_i) = self.inclination_deg.to_radians().sin_cos();
+        let (y, z) = (y * cos_i + z * sin_i, -y * sin_i + z * cos_i);
+
+        [x, y, z]
+    }
+
+    /// Grid cell at `local` (a position in the galaxy's own frame, normalised by its radius):
+    /// the inverse of `local_position`, up to rounding to the grid.
+    pub fn grid_position(&self, local: [f32; 3]) -> (i32, i32, i32) {
+        let [x, y, z] = local.map(|c| c * self.radius);
+
+        // Tilt about x, then rotate about z
+        let (sin_i, cos_i) = self.inclination_deg.to_radians().sin_cos();
+        let (y, z) = (y * cos_i - z * sin_i, y * sin_i + z * cos_i);
+        let (sin_pa, cos_pa) = self.position_angle_deg.to_radians().sin_cos();
+        let (x, y) = (x * cos_pa - y * sin_pa, x * sin_pa + y * cos_pa);
+
+        (x.round() as i32, y.round() as i32, z.round() as i32)
+    }
+
+    /// Unit normal of the galactic plane in grid axes: the frame's z axis, so that
+    /// `local_position(p)[2] * radius` is `p · normal`.
+    fn plane_normal(&self) -> [f32; 3] {
+        let (sin_pa, cos_pa) = self.position_angle_deg.to_radians().sin_cos();
+        let (sin_i, cos_i) = self.inclination_deg.to_radians().sin_cos();
+        [sin_pa * sin_i, -cos_pa * sin_i, cos_i]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Galaxy {
     pub seed: u64,
+    // Of its world, passed on to everything generated in the galaxy
+    pub generation_version: u32,
     pub galaxy_type: GalaxyType,
     pub position: (i32, i32, i32),
+    pub structure: GalaxyStructure,
 }
 
 impl Galaxy {
-    pub fn new(world_seed: u64, position: (i32, i32, i32)) -> Self {
+    pub fn new(root: RootSeed, position: (i32, i32, i32)) -> Self {
+        let generation_version = root.generation_version;
         let seed = derive_seed(
-            world_seed,
+            root.seed,
+            generation_version,
             GALAXY_TAG,
             &[position.0 as i64, position.1 as i64, position.2 as i64],
         );
 
-        let galaxy_type = Self::derive_galaxy_type(seed);
+        let galaxy_type = Self::derive_galaxy_type(seed, generation_version);
 
         Self {
             seed,
+            generation_version,
             galaxy_type,
             position,
+            structure: GalaxyStructure::new(seed, generation_version, galaxy_type),
         }
     }
 
-    fn derive_galaxy_type(galaxy_seed: u64) -> GalaxyType {
-        let galaxy_type_seed = derive_seed(galaxy_seed, GALAXY_TYPE_TAG, &[]);
+    fn derive_galaxy_type(galaxy_seed: u64, generation_version: u32) -> GalaxyType {
+        let galaxy_type_seed = derive_seed(galaxy_seed, generation_version, GALAXY_TYPE_TAG, &[]);
         let roll = (galaxy_type_seed as f64) * U64_TO_UNIT_F64;
 
         match roll {
@@ -50,14 +222,24 @@ impl Galaxy {
     }
 
     pub fn should_spawn_star_system(&self, star_system_pos: (i32, i32, i32)) -> bool {
-        let density = compute_star_system_density(self.seed, self.galaxy_type, star_system_pos);
+        self.should_spawn_star_system_below(star_system_pos, 1.0)
+    }
 
-        if density <= 0.0001 {
+    /// `should_spawn_star_system` for a cell whose density is known not to exceed
+    /// `max_density` (see `max_star_system_density`): most cells are then turned down on their
+    /// spawn roll alone, without evaluating the density.
+    pub fn should_spawn_star_system_below(
+        &self,
+        star_system_pos: (i32, i32, i32),
+        max_density: f32,
+    ) -> bool {
+        if max_density <= MIN_SPAWN_DENSITY {
             return false;
         }
 
         let star_system_seed = derive_seed(
             self.seed,
+            self.generation_version,
             STAR_SYSTEM_SPAWN_TAG,
             &[
                 star_system_pos.0 as i64,
@@ -67,18 +249,82 @@ impl Galaxy {
         );
 
         let spawn_roll = (star_system_seed as f64) * U64_TO_UNIT_F64;
-        spawn_roll < density as f64
+        if spawn_roll >= max_density as f64 {
+            return false;
+        }
+
+        let density = compute_star_system_density(self, star_system_pos);
+        density > MIN_SPAWN_DENSITY && spawn_roll < density as f64
+    }
+
+    /// Upper bound of the star system density over the box of cells `min..=max`. A box whose
+    /// bound is too low to spawn anything can be skipped whole.
+    pub fn max_star_system_density(&self, min: (i32, i32, i32), max: (i32, i32, i32)) -> f32 {
+        let structure = &self.structure;
+
+        // Cells are widened to unit cubes, which leaves ample slack for rounding in the
+        // frame rotation of `compute_star_system_density`
+        let lo = [min.0, min.1, min.2].map(|c| c as f32 - 0.5);
+        let hi = [max.0, max.1, max.2].map(|c| c as f32 + 0.5);
+
+        // Closest approach of the box to the galaxy's center...
+        let r = (0..3)
+            .map(|axis| (lo[axis].max(0.0) + (-hi[axis]).max(0.0)).powi(2))
+            .sum::<f32>()
+            .sqrt()
+            / structure.radius;
+        if r > 1.0 {
+            return 0.0;
+        }
+
+        // ...and to its plane: p · normal spans center · normal ± half-extents · |normal|
+        let normal = structure.plane_normal();
+        let (center_height, half_span) = (0..3).fold((0.0, 0.0), |(h, s), axis| {
+            let center = 0.5 * (lo[axis] + hi[axis]);
+            let half = 0.5 * (hi[axis] - lo[axis]);
+            (h + center * normal[axis], s + half * normal[axis].abs())
+        });
+        let z = (center_height.abs() - half_span).max(0.0) / structure.radius;
+
+        // Each term of `compute_star_system_density` at its largest. The bar falls off at
+        // least as fast as along its long axis; ellipticals and irregulars are squashed along
+        // the polar axis, and no faster than that across the other two
+        let bulge = (-r / structure.bulge_radius).exp();
+        let r_squashed_sq = r * r + z * z * (structure.thickness.powi(-2) - 1.0).max(0.0);
+        let bar = structure
+            .bar_length
+            .map_or(0.0, |bar_length| (-(r / bar_length).powi(2)).exp());
+        let disk_falloff = (-z / structure.thickness).exp();
+
+        let bound = match self.galaxy_type {
+            GalaxyType::Spiral => bulge + bar + (-2.5 * r).exp() * disk_falloff,
+            GalaxyType::Elliptical => (-r_squashed_sq.sqrt() / structure.bulge_radius).exp(),
+            GalaxyType::Lenticular => bulge + bar + (-2.0 * r).exp() * disk_falloff,
+            GalaxyType::Irregular => 1.0 - r_squashed_sq,
+        };
+        bound.clamp(0.0, 1.0)
+    }
+
+    /// Mean [Fe/H] (dex, 0.0 = solar) of stars born at `star_system_position`. Metals build
+    /// up in the dense cores of old galaxies and thin out towards the rim; irregulars are
+    /// young and metal-poor throughout.
+    pub fn mean_metallicity(&self, star_system_position: (i32, i32, i32)) -> f32 {
+        let [x, y, z] = self.structure.local_position(star_system_position);
+        let r = x.hypot(y).hypot(z).min(1.0);
+
+        match self.galaxy_type {
+            GalaxyType::Spiral => 0.3 - 0.8 * r,
+            GalaxyType::Lenticular => 0.2 - 0.6 * r,
+            GalaxyType::Elliptical => 0.4 - 0.8 * r,
+            GalaxyType::Irregular => -0.6 - 0.4 * r,
+        }
     }
 }
 
-fn compute_star_system_density(
-    seed: u64,
-    galaxy_type: GalaxyType,
-    star_system_position: (i32, i32, i32),
-) -> f32 {
-    let nx = star_system_position.0 as f32 / GALAXY_RADIUS;
-    let ny = star_system_position.1 as f32 / GALAXY_RADIUS;
-    let nz = star_system_position.2 as f32 / GALAXY_RADIUS;
+/// Probability, from 0.0 to 1.0, that a star system spawns at `star_system_position`.
+pub fn compute_star_system_density(galaxy: &Galaxy, star_system_position: (i32, i32, i32)) -> f32 {
+    let structure = &galaxy.structure;
+    let [nx, ny, nz] = structure.local_position(star_system_position);
 
     let r_sq = nx * nx + ny * ny + nz * nz;
 
@@ -88,72 +334,61 @@ fn compute_star_system_density(
     }
 
     let r = r_sq.sqrt();
+    let disk_falloff = (-nz.abs() / structure.thickness).exp();
 
-    match galaxy_type {
+    match galaxy.galaxy_type {
         GalaxyType::Spiral => {
             let theta = ny.atan2(nx);
-            let bulge = (-5.0 * r).exp();
+            let bulge = (-r / structure.bulge_radius).exp();
 
-            // Linear winding for spiral arms (k = 8.0 pitch)
-            let arm_wave = (8.0 * r - theta).cos();
-            let disk = (-2.5 * r).exp() * (-8.0 * nz.abs()).exp() * (arm_wave * arm_wave);
+            // Logarithmic arms, starting from the ends of the bar: cos² of (arms / 2) times
+            // the phase has one crest per arm
+            let arm_origin = structure.bar_length.unwrap_or(ARM_ORIGIN);
+            let winding = 1.0 / structure.arm_pitch_deg.to_radians().tan();
+            let arm_phase = winding * (r.max(arm_origin) / arm_origin).ln() - theta;
+            let arm_wave = (0.5 * structure.arm_count as f32 * arm_phase).cos();
+            let disk = (-2.5 * r).exp() * disk_falloff * (arm_wave * arm_wave);
 
-            (bulge + disk).clamp(0.0, 1.0)
+            (bulge + bar_density(structure, [nx, ny, nz]) + disk).clamp(0.0, 1.0)
         }
         GalaxyType::Elliptical => {
-            // E3 flattened ellipsoid
-            let r_ellipsoid = (nx * nx + (ny / 0.7).powi(2) + (nz / 0.5).powi(2)).sqrt();
-            (-3.5 * r_ellipsoid).exp().clamp(0.0, 1.0)
+            // Triaxial ellipsoid, flattened along the polar axis
+            let middle_axis = 0.5 * (1.0 + structure.thickness);
+            let r_ellipsoid =
+                (nx * nx + (ny / middle_axis).powi(2) + (nz / structure.thickness).powi(2)).sqrt();
+            (-r_ellipsoid / structure.bulge_radius)
+                .exp()
+                .clamp(0.0, 1.0)
         }
         GalaxyType::Lenticular => {
             // Dense bulge with flat featureless disk
-            let bulge = (-4.0 * r).exp();
-            let disk = (-2.0 * r).exp() * (-6.0 * nz.abs()).exp();
-            (bulge + disk).clamp(0.0, 1.0)
+            let bulge = (-r / structure.bulge_radius).exp();
+            let disk = (-2.0 * r).exp() * disk_falloff;
+            (bulge + bar_density(structure, [nx, ny, nz]) + disk).clamp(0.0, 1.0)
         }
         GalaxyType::Irregular => {
-            // Clumpy 3D noise scaled by radial falloff
-            let noise = fast_3d_noise(seed, nx * 3.0, ny * 3.0, nz * 3.0);
-            (noise * (1.0 - r_sq)).clamp(0.0, 1.0)
+            // Clumpy 3D noise scaled by radial falloff, squashed along the polar axis
+            let field = NoiseField::new(derive_seed(
+                galaxy.seed,
+                galaxy.generation_version,
+                GALAXY_IRREGULAR_NOISE_TAG,
+                &[],
+            ));
+            let noise = 0.5 + 0.5 * field.fbm(nx as f64, ny as f64, nz as f64, IRREGULAR_CLUMP_FBM);
+            let r_squashed_sq = nx * nx + ny * ny + (nz / structure.thickness).powi(2);
+            (noise as f32 * (1.0 - r_squashed_sq).max(0.0)).clamp(0.0, 1.0)
         }
     }
 }
 
-/// Zero-dependency 3D Value Noise for Irregular Galaxies
-fn fast_3d_noise(seed: u64, x: f32, y: f32, z: f32) -> f32 {
-    let xi = x.floor() as i64;
-    let yi = y.floor() as i64;
-    let zi = z.floor() as i64;
-
-    let fx = x - x.floor();
-    let fy = y - y.floor();
-    let fz = z - z.floor();
-
-    let u = fx * fx * (3.0 - 2.0 * fx);
-    let v = fy * fy * (3.0 - 2.0 * fy);
-    let w = fz * fz * (3.0 - 2.0 * fz);
-
-    let hash = |dx: i64, dy: i64, dz: i64| -> f32 {
-        let s = derive_seed(seed, GALAXY_FAST_3D_NOISE_TAG, &[xi + dx, yi + dy, zi + dz]);
-        (s as f32) * (1.0 / u64::MAX as f32)
+/// Elongated bar along the frame's x axis, as dense as the bulge at its center.
+fn bar_density(structure: &GalaxyStructure, [nx, ny, nz]: [f32; 3]) -> f32 {
+    let Some(bar_length) = structure.bar_length else {
+        return 0.0;
     };
-
-    let c000 = hash(0, 0, 0);
-    let c100 = hash(1, 0, 0);
-    let c010 = hash(0, 1, 0);
-    let c110 = hash(1, 1, 0);
-    let c001 = hash(0, 0, 1);
-    let c101 = hash(1, 0, 1);
-    let c011 = hash(0, 1, 1);
-    let c111 = hash(1, 1, 1);
-
-    let x00 = c000 + u * (c100 - c000);
-    let x10 = c010 + u * (c110 - c010);
-    let x01 = c001 + u * (c101 - c001);
-    let x11 = c011 + u * (c111 - c011);
-
-    let y0 = x00 + v * (x10 - x00);
-    let y1 = x01 + v * (x11 - x01);
-
-    y0 + w * (y1 - y0)
+    let bar_width = bar_length / BAR_ASPECT;
+    let bar_sq = (nx / bar_length).powi(2)
+        + (ny / bar_width).powi(2)
+        + (nz / structure.thickness.min(bar_width)).powi(2);
+    (-bar_sq).exp()
 }
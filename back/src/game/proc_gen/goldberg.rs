@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+// Class I Goldberg polyhedron GP(n, 0) built as the dual of an icosahedron whose 20 triangular
+// faces are each subdivided n times. Every lattice point of the subdivided icosahedron is the
+// center of one tile: the 12 icosahedron vertices become the pentagons, everything else a hexagon.
+//
+// A tile is addressed by (face, u, v) with face in 0..20 and u + v <= subdivision, using the
+// barycentric weights (subdivision - u - v, u, v) of the face's three corners. Points on a face
+// edge or corner are shared between faces, so each tile has one canonical (face, u, v): the one
+// on the lowest face index that contains it. Tile indices follow the canonical visiting order
+// (face, then u, then v) and never change for a given subdivision.
+//
+// The vertex and face tables match front/src/OLD/math/goldberg.ts so the renderer can rebuild
+// the exact same tile positions from (face, u, v).
+
+pub const ICOSAHEDRON_FACE_COUNT: u8 = 20;
+
+const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+
+const ICOSAHEDRON_VERTICES: [[f64; 3]; 12] = [
+    [-1.0, GOLDEN_RATIO, 0.0],
+    [1.0, GOLDEN_RATIO, 0.0],
+    [-1.0, -GOLDEN_RATIO, 0.0],
+    [1.0, -GOLDEN_RATIO, 0.0],
+    [0.0, -1.0, GOLDEN_RATIO],
+    [0.0, 1.0, GOLDEN_RATIO],
+    [0.0, -1.0, -GOLDEN_RATIO],
+    [0.0, 1.0, -GOLDEN_RATIO],
+    [GOLDEN_RATIO, 0.0, -1.0],
+    [GOLDEN_RATIO, 0.0, 1.0],
+    [-GOLDEN_RATIO, 0.0, -1.0],
+    [-GOLDEN_RATIO, 0.0, 1.0],
+];
+
+const ICOSAHEDRON_FACES: [[usize; 3]; 20] = [
+    [0, 11, 5],
+    [0, 5, 1],
+    [0, 1, 7],
+    [0, 7, 10],
+    [0, 10, 11],
+    [1, 5, 9],
+    [5, 11, 4],
+    [11, 10, 2],
+    [10, 7, 6],
+    [7, 1, 8],
+    [3, 9, 4],
+    [3, 4, 2],
+    [3, 2, 6],
+    [3, 6, 8],
+    [3, 8, 9],
+    [4, 9, 5],
+    [2, 4, 11],
+    [6, 2, 10],
+    [8, 6, 7],
+    [9, 8, 1],
+];
+
+// Lattice steps inside a face, in (du, dv)
+const LATTICE_STEPS: [(i64, i64); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+const NO_TILE: u32 = u32::MAX;
+
+/// Total tile count of GP(n, 0): 10n² + 2.
+pub fn tile_count_for_subdivision(subdivision: u32) -> u32 {
+    10 * subdivision * subdivision + 2
+}
+
+#[derive(Debug)]
+pub struct GoldbergTopology {
+    pub subdivision: u32,
+    // Per tile, indexed by tile index
+    coords: Vec<(u8, u32, u32)>,
+    positions: Vec<[f32; 3]>,
+    neighbors: Vec<Vec<u32>>,
+    // Per face lattice point: 20 * (n + 1) * (n + 1), NO_TILE outside the triangle
+    face_lookup: Vec<u32>,
+}
+
+impl GoldbergTopology {
+    /// Shared topology for a subdivision level. Built once and cached for the process lifetime.
+    pub fn for_subdivision(subdivision: u32) -> Arc<GoldbergTopology> {
+        static CACHE: OnceLock<Mutex<HashMap<u32, Arc<GoldbergTopology>>>> = OnceLock::new();
+
+        let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut cache = cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        cache
+            .entry(subdivision)
+            .or_insert_with(|| Arc::new(Self::build(subdivision)))
+            .clone()
+    }
+
+    fn build(subdivision: u32) -> Self {
+        assert!(subdivision >= 1, "Goldberg subdivision must be at least 1");
+
+        let n = subdivision;
+        let side = (n + 1) as usize;
+        let tile_count = tile_count_for_subdivision(n) as usize;
+
+        let mut coords = Vec::with_capacity(tile_count);
+        let mut positions = Vec::with_capacity(tile_count);
+        let mut face_lookup = vec![NO_TILE; ICOSAHEDRON_FACE_COUNT as usize * side * side];
+        let mut index_by_key: HashMap<[(usize, u32); 3], u32> = HashMap::with_capacity(tile_count);
+
+        // 1. Assign tile indices, deduplicating lattice points shared by edges and corners
+        for face in 0..ICOSAHEDRON_FACE_COUNT {
+            for u in 0..=n {
+                for v in 0..=(n - u) {
+                    let key = lattice_key(face, u, v, n);
+
+                    let index = *index_by_key.entry(key).or_insert_with(|| {
+                        coords.push((face, u, v));
+                        positions.push(lattice_position(face, u, v, n));
+                        (coords.len() - 1) as u32
+                    });
+
+                    face_lookup[lookup_slot(face, u, v, n)] = index;
+                }
+            }
+        }
+
+        debug_assert_eq!(coords.len(), tile_count);
+
+        // 2. Adjacency is the union of lattice steps over every face a tile appears on
+        let mut neighbors: Vec<Vec<u32>> = vec![Vec::with_capacity(6); tile_count];
+
+        for face in 0..ICOSAHEDRON_FACE_COUNT {
+            for u in 0..=n {
+                for v in 0..=(n - u) {
+                    let index = face_lookup[lookup_slot(face, u, v, n)];
+
+                    for (du, dv) in LATTICE_STEPS {
+                        let nu = u as i64 + du;
+                        let nv = v as i64 + dv;
+
+                        if nu < 0 || nv < 0 || nu + nv > n as i64 {
+                            continue;
+                        }
+
+                        let neighbor = face_lookup[lookup_slot(face, nu as u32, nv as u32, n)];
+                        if !neighbors[index as usize].contains(&neighbor) {
+                            neighbors[index as usize].push(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+
+        // 3. Sort each ring counter-clockwise around the outward normal so consecutive
+        //    neighbours share a polygon corner
+        for (index, ring) in neighbors.iter_mut().enumerate() {
+            sort_ring_counter_clockwise(positions[index], &positions, ring);
+        }
+
+        Self {
+            subdivision,
+            coords,
+            positions,
+            neighbors,
+            face_lookup,
+        }
+    }
+
+    pub fn tile_count(&self) -> u32 {
+        self.coords.len() as u32
+    }
+
+    /// Tile index for any valid (face, u, v), canonical or not.
+    pub fn tile_index(&self, face: u8, u: u32, v: u32) -> Option<u32> {
+        if face >= ICOSAHEDRON_FACE_COUNT || u.checked_add(v)? > self.subdivision {
+            return None;
+        }
+
+        Some(self.face_lookup[lookup_slot(face, u, v, self.subdivision)])
+    }
+
+    /// Canonical (face, u, v) of a tile index.
+    pub fn tile_coords(&self, index: u32) -> (u8, u32, u32) {
+        self.coords[index as usize]
+    }
+
+    /// Tile center on the unit sphere.
+    pub fn position(&self, index: u32) -> [f32; 3] {
+        self.positions[index as usize]
+    }
+
+    /// Adjacent tile indices, ordered counter-clockwise around the tile.
+    pub fn neighbors(&self, index: u32) -> &[u32] {
+        &self.neighbors[index as usize]
+    }
+
+    pub fn is_pentagon(&self, index: u32) -> bool {
+        self.neighbors[index as usize].len() == 5
+    }
+
+    pub fn pentagons(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.tile_count()).filter(|&index| self.is_pentagon(index))
+    }
+
+    /// Every tile at most `rings` steps away from one of `sources`, sources included.
+    pub fn tiles_within(&self, sources: impl IntoIterator<Item = u32>, rings: u32) -> HashSet<u32> {
+        let mut reached: HashSet<u32> = sources.into_iter().collect();
+        let mut frontier: Vec<u32> = reached.iter().copied().collect();
+
+        for _ in 0..rings {
+            let mut next = Vec::new();
+            for index in frontier {
+                for &neighbor in self.neighbors(index) {
+                    if reached.insert(neighbor) {
+                        next.push(neighbor);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        reached
+    }
+}
+
+fn lookup_slot(face: u8, u: u32, v: u32, subdivision: u32) -> usize {
+    let side = (subdivision + 1) as usize;
+    (face as usize * side + u as usize) * side + v as usize
+}
+
+/// Exact identity of a lattice point: its non-zero icosahedron vertex weights, sorted.
+fn lattice_key(face: u8, u: u32, v: u32, subdivision: u32) -> [(usize, u32); 3] {
+    let [a, b, c] = ICOSAHEDRON_FACES[face as usize];
+    let mut key = [(a, subdivision - u - v), (b, u), (c, v)];
+
+    for weight in key.iter_mut() {
+        if weight.1 == 0 {
+            *weight = (usize::MAX, 0);
+        }
+    }
+
+    key.sort_unstable();
+    key
+}
+
+fn lattice_position(face: u8, u: u32, v: u32, subdivision: u32) -> [f32; 3] {
+    let [a, b, c] = ICOSAHEDRON_FACES[face as usize];
+    let n = subdivision as f64;
+    let weights = [(n - u as f64 - v as f64) / n, u as f64 / n, v as f64 / n];
+
+    let mut point = [0.0f64; 3];
+    for (corner, weight) in [a, b, c].into_iter().zip(weights) {
+        for axis in 0..3 {
+            point[axis] += ICOSAHEDRON_VERTICES[corner][axis] * weight;
+        }
+    }
+
+    let len = (point[0] * point[0] + point[1] * point[1] + point[2] * point[2]).sqrt();
+    [
+        (point[0] / len) as f32,
+        (point[1] / len) as f32,
+        (point[2] / len) as f32,
+    ]
+}
+
+fn sort_ring_counter_clockwise(center: [f32; 3], positions: &[[f32; 3]], ring: &mut [u32]) {
+    let normal = center.map(|c| c as f64);
+    let offset = |index: u32| -> [f64; 3] {
+        let p = positions[index as usize];
+        [
+            p[0] as f64 - normal[0],
+            p[1] as f64 - normal[1],
+            p[2] as f64 - normal[2],
+        ]
+    };
+
+    let reference = offset(ring[0]);
+    let angle = |index: u32| -> f64 {
+        let d = offset(index);
+        let cross = [
+            reference[1] * d[2] - reference[2] * d[1],
+            reference[2] * d[0] - reference[0] * d[2],
+            reference[0] * d[1] - reference[1] * d[0],
+        ];
+        let sin = cross[0] * normal[0] + cross[1] * normal[1] + cross[2] * normal[2];
+        let cos = reference[0] * d[0] + reference[1] * d[1] + reference[2] * d[2];
+        sin.atan2(cos).rem_euclid(std::f64::consts::TAU)
+    };
+
+    ring.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::proc_gen::tile::get_hex_neighbors;
+
+    const SUBDIVISIONS: [u32; 6] = [1, 2, 3, 7, 16, 25];
+
+    #[test]
+    fn has_the_goldberg_tile_count_and_twelve_pentagons() {
+        for n in SUBDIVISIONS {
+            let topology = GoldbergTopology::for_subdivision(n);
+            assert_eq!(topology.tile_count(), tile_count_for_subdivision(n));
+            assert_eq!(topology.pentagons().count(), 12, "subdivision {n}");
+            assert!((0..topology.tile_count()).all(|i| {
+                let degree = topology.neighbors(i).len();
+                degree == 5 || degree == 6
+            }));
+        }
+    }
+
+    #[test]
+    fn neighbours_are_symmetric_distinct_and_never_self() {
+        for n in SUBDIVISIONS {
+            let topology = GoldbergTopology::for_subdivision(n);
+            for index in 0..topology.tile_count() {
+                let ring = topology.neighbors(index);
+                assert!(!ring.contains(&index), "tile {index} is its own neighbour");
+
+                let mut sorted = ring.to_vec();
+                sorted.sort_unstable();
+                sorted.dedup();
+                assert_eq!(sorted.len(), ring.len(), "duplicate neighbour of {index}");
+
+                for &neighbor in ring {
+                    assert!(
+                        topology.neighbors(neighbor).contains(&index),
+                        "subdivision {n}: {neighbor} is a neighbour of {index}, not the reverse"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn consecutive_neighbours_are_adjacent_to_each_other() {
+        for n in SUBDIVISIONS.into_iter().filter(|&n| n > 1) {
+            let topology = GoldbergTopology::for_subdivision(n);
+            for index in 0..topology.tile_count() {
+                let ring = topology.neighbors(index);
+                for (i, &a) in ring.iter().enumerate() {
+                    let b = ring[(i + 1) % ring.len()];
+                    assert!(
+                        topology.neighbors(a).contains(&b),
+                        "subdivision {n}: ring of {index} is not ordered around it"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_face_address_resolves_to_its_canonical_tile() {
+        for n in SUBDIVISIONS {
+            let topology = GoldbergTopology::for_subdivision(n);
+            for index in 0..topology.tile_count() {
+                let (face, u, v) = topology.tile_coords(index);
+                assert_eq!(topology.tile_index(face, u, v), Some(index));
+            }
+            for face in 0..ICOSAHEDRON_FACE_COUNT {
+                for u in 0..=n {
+                    for v in 0..=(n - u) {
+                        let index = topology.tile_index(face, u, v).unwrap();
+                        assert!(index < topology.tile_count());
+                    }
+                }
+            }
+            assert_eq!(topology.tile_index(0, n, 1), None);
+            assert_eq!(topology.tile_index(ICOSAHEDRON_FACE_COUNT, 0, 0), None);
+        }
+    }
+
+    #[test]
+    fn hex_neighbours_by_coordinates_are_symmetric() {
+        let n = 7;
+        let topology = GoldbergTopology::for_subdivision(n);
+        for index in 0..topology.tile_count() {
+            let tile = topology.tile_coords(index);
+            for neighbor in get_hex_neighbors(tile.0, tile.1, tile.2, n) {
+                assert!(get_hex_neighbors(neighbor.0, neighbor.1, neighbor.2, n).contains(&tile));
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::game::proc_gen::galaxy::{Galaxy, MIN_SPAWN_DENSITY};
+
+// Blocks are halved along every axis until they are this many cells wide, then scanned cell
+// by cell
+const LEAF_SIZE: i64 = 4;
+
+type Cell = (i32, i32, i32);
+
+/// A star system found around a search center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearbyStarSystem {
+    pub position: Cell,
+    pub distance: f32, // In grid cells
+}
+
+/// Star systems of `galaxy` within `radius` cells of `center`, nearest first and at most
+/// `limit` of them: `usize::MAX` lists them all, a small limit finds the nearest few.
+///
+/// Best-first walk over an octree of the search cube: blocks are visited from the nearest,
+/// those whose density bound rules out any star system are dropped whole, and the walk stops
+/// once no block left can beat the `limit`-th system found. Results are sorted by distance,
+/// then position, so ties resolve the same way whatever the visiting order.
+pub fn nearest_star_systems(
+    galaxy: &Galaxy,
+    center: Cell,
+    radius: f32,
+    limit: usize,
+) -> Vec<NearbyStarSystem> {
+    if limit == 0 || radius.is_nan() || radius < 0.0 {
+        return Vec::new();
+    }
+
+    // Distances are compared squared, exactly: a cell at integer offsets is within the radius
+    // when its squared distance is at most floor(radius²)
+    let radius_sq = (radius as f64 * radius as f64).floor() as i64;
+    let reach = (radius_sq as f64).sqrt().floor() as i64;
+    let center = [center.0 as i64, center.1 as i64, center.2 as i64];
+    let root = Block::new(
+        center.map(|c| (c - reach).max(i32::MIN as i64)),
+        center.map(|c| (c + reach).min(i32::MAX as i64)),
+        center,
+    );
+
+    let mut pending = BinaryHeap::from([Reverse(root)]);
+    // Max-heap of (distance², position) holding the best `limit` systems so far
+    let mut found: BinaryHeap<(i64, Cell)> = BinaryHeap::new();
+
+    while let Some(Reverse(block)) = pending.pop() {
+        if block.distance_sq > radius_sq {
+            break;
+        }
+        if found.len() == limit
+            && found
+                .peek()
+                .is_some_and(|&(worst, _)| block.distance_sq > worst)
+        {
+            break;
+        }
+
+        let max_density = galaxy.max_star_system_density(cell_of(block.min), cell_of(block.max));
+        if max_density <= MIN_SPAWN_DENSITY {
+            continue;
+        }
+
+        if block.width() > LEAF_SIZE {
+            pending.extend(
+                block
+                    .split(center)
+                    .into_iter()
+                    .filter(|child| child.distance_sq <= radius_sq)
+                    .map(Reverse),
+            );
+            continue;
+        }
+
+        for x in block.min[0]..=block.max[0] {
+            for y in block.min[1]..=block.max[1] {
+                for z in block.min[2]..=block.max[2] {
+                    let distance_sq = Block::new([x, y, z], [x, y, z], center).distance_sq;
+                    if distance_sq > radius_sq {
+                        continue;
+                    }
+
+                    let position = cell_of([x, y, z]);
+                    let candidate = (distance_sq, position);
+                    if found.len() == limit && found.peek().is_some_and(|&worst| candidate > worst)
+                    {
+                        continue;
+                    }
+                    if !galaxy.should_spawn_star_system_below(position, max_density) {
+                        continue;
+                    }
+
+                    found.push(candidate);
+                    if found.len() > limit {
+                        found.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    found
+        .into_sorted_vec()
+        .into_iter()
+        .map(|(distance_sq, position)| NearbyStarSystem {
+            position,
+            distance: (distance_sq as f64).sqrt() as f32,
+        })
+        .collect()
+}
+
+/// Box of cells `min..=max`, ordered by its squared distance to the search center.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Block {
+    distance_sq: i64,
+    min: [i64; 3],
+    max: [i64; 3],
+}
+
+impl Block {
+    fn new(min: [i64; 3], max: [i64; 3], center: [i64; 3]) -> Self {
+        let distance_sq = (0..3)
+            .map(|axis| {
+                let gap = (min[axis] - center[axis])
+                    .max(center[axis] - max[axis])
+                    .max(0);
+                gap * gap
+            })
+            .sum();
+        Self {
+            distance_sq,
+            min,
+            max,
+        }
+    }
+
+    fn width(&self) -> i64 {
+        (0..3)
+            .map(|axis| self.max[axis] - self.min[axis] + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Eight halves of the block, fewer once an axis is a single cell wide
+    fn split(&self, center: [i64; 3]) -> Vec<Block> {
+        let halves = |axis: usize| {
+            let (min, max) = (self.min[axis], self.max[axis]);
+            let mid = min + (max - min) / 2;
+            if min == max {
+                vec![(min, max)]
+            } else {
+                vec![(min, mid), (mid + 1, max)]
+            }
+        };
+
+        let mut blocks = Vec::with_capacity(8);
+        for &(x0, x1) in &halves(0) {
+            for &(y0, y1) in &halves(1) {
+                for &(z0, z1) in &halves(2) {
+                    blocks.push(Block::new([x0, y0, z0], [x1, y1, z1], center));
+                }
+            }
+        }
+        blocks
+    }
+}
+
+// Search cells stay on the i32 grid: the root block is clamped to it
+fn cell_of([x, y, z]: [i64; 3]) -> Cell {
+    (x as i32, y as i32, z as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::proc_gen::chunk::{ChunkCoord, galaxies_in_chunk};
+    use crate::game::proc_gen::seed::{CURRENT_GENERATION_VERSION, RootSeed};
+
+    // Every cell of the cube around `center`, nearest first, like the search must return
+    fn brute_force(galaxy: &Galaxy, center: Cell, radius: i32) -> Vec<NearbyStarSystem> {
+        let mut found = Vec::new();
+        for x in center.0 - radius..=center.0 + radius {
+            for y in center.1 - radius..=center.1 + radius {
+                for z in center.2 - radius..=center.2 + radius {
+                    let (dx, dy, dz) = (x - center.0, y - center.1, z - center.2);
+                    let distance_sq = (dx * dx + dy * dy + dz * dz) as i64;
+                    if distance_sq <= (radius * radius) as i64
+                        && galaxy.should_spawn_star_system((x, y, z))
+                    {
+                        found.push((distance_sq, (x, y, z)));
+                    }
+                }
+            }
+        }
+        found.sort();
+        found
+            .into_iter()
+            .map(|(distance_sq, position)| NearbyStarSystem {
+                position,
+                distance: (distance_sq as f64).sqrt() as f32,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_a_brute_force_scan_sorted_by_distance() {
+        const RADIUS: i32 = 10;
+        let mut systems_seen = 0;
+
+        for world_seed in [1, 42, 0x5EED] {
+            let root = RootSeed::new(world_seed, CURRENT_GENERATION_VERSION);
+            for galaxy in galaxies_in_chunk(root, ChunkCoord::new(0, 0, 0))
+                .iter()
+                .take(2)
+            {
+                for center in [(0, 0, 0), (7, -3, 5)] {
+                    let expected = brute_force(galaxy, center, RADIUS);
+
+                    let all = nearest_star_systems(galaxy, center, RADIUS as f32, usize::MAX);
+                    assert_eq!(
+                        all, expected,
+                        "seed {world_seed}, galaxy {:?}",
+                        galaxy.position
+                    );
+
+                    let nearest = nearest_star_systems(galaxy, center, RADIUS as f32, 5);
+                    assert_eq!(nearest, expected[..expected.len().min(5)]);
+                    systems_seen += expected.len();
+                }
+            }
+        }
+        assert!(
+            systems_seen > 0,
+            "no star system around the sampled centers"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+// Seeded 3D simplex noise and the fractal variants built on it.
+//
+// A `NoiseField` is fully described by its seed, which callers take from `derive_seed` with their
+// own tag, so every planet / galaxy / universe gets an independent field. Lattice gradients are
+// picked by hashing (seed, i, j, k) directly: no permutation table, nothing to build per field.
+
+// Skewing factors for 3D simplex grids
+const F3: f64 = 1.0 / 3.0;
+const G3: f64 = 1.0 / 6.0;
+
+// Edge midpoints of a cube: the classic 12 simplex gradients
+const GRADIENTS: [[f64; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+// Offsets applied to the warp lookups so the three warp axes are decorrelated
+const WARP_OFFSETS: [[f64; 3]; 3] = [[5.2, 1.3, 7.7], [1.7, 9.2, 3.4], [8.3, 2.8, 6.1]];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Fbm {
+    pub octaves: u32,
+    pub frequency: f64,
+    pub lacunarity: f64, // Frequency multiplier per octave
+    pub gain: f64,       // Amplitude multiplier per octave
+}
+
+impl Fbm {
+    pub const fn new(octaves: u32, frequency: f64) -> Self {
+        Self {
+            octaves,
+            frequency,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NoiseField {
+    seed: u64,
+}
+
+impl NoiseField {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Single octave of simplex noise, roughly in [-1.0, 1.0].
+    pub fn simplex(&self, x: f64, y: f64, z: f64) -> f64 {
+        simplex3(self.seed, x, y, z)
+    }
+
+    /// Fractal Brownian motion, normalised to [-1.0, 1.0].
+    pub fn fbm(&self, x: f64, y: f64, z: f64, params: Fbm) -> f64 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut total_amplitude = 0.0;
+        let mut frequency = params.frequency;
+
+        for octave in 0..params.octaves {
+            let octave_seed = mix(self.seed, octave as u64);
+            sum += amplitude * simplex3(octave_seed, x * frequency, y * frequency, z * frequency);
+            total_amplitude += amplitude;
+            amplitude *= params.gain;
+            frequency *= params.lacunarity;
+        }
+
+        if total_amplitude > 0.0 {
+            (sum / total_amplitude).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Ridged multifractal in [0.0, 1.0]: sharp crests where the base noise crosses zero.
+    /// Each octave is weighted by the previous one so ridges get detail and valleys stay smooth.
+    pub fn ridged(&self, x: f64, y: f64, z: f64, params: Fbm) -> f64 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut total_amplitude = 0.0;
+        let mut frequency = params.frequency;
+        let mut weight = 1.0;
+
+        for octave in 0..params.octaves {
+            let octave_seed = mix(self.seed, octave as u64);
+            let n = simplex3(octave_seed, x * frequency, y * frequency, z * frequency);
+
+            let ridge = (1.0 - n.abs()).powi(2) * weight;
+            weight = ridge.clamp(0.0, 1.0);
+
+            sum += amplitude * ridge;
+            total_amplitude += amplitude;
+            amplitude *= params.gain;
+            frequency *= params.lacunarity;
+        }
+
+        if total_amplitude > 0.0 {
+            (sum / total_amplitude).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// fBm sampled at a position displaced by another fBm, in [-1.0, 1.0].
+    /// `strength` is the displacement in input units; it turns round blobs into swirly,
+    /// continent-like shapes.
+    pub fn warped(&self, x: f64, y: f64, z: f64, params: Fbm, strength: f64) -> f64 {
+        let warp = Fbm::new(params.octaves.min(3), params.frequency);
+
+        let mut displaced = [x, y, z];
+        for (axis, offset) in WARP_OFFSETS.iter().enumerate() {
+            let warp_field = NoiseField::new(mix(self.seed, 0x5741_5250 + axis as u64)); // "WARP"
+            displaced[axis] +=
+                strength * warp_field.fbm(x + offset[0], y + offset[1], z + offset[2], warp);
+        }
+
+        self.fbm(displaced[0], displaced[1], displaced[2], params)
+    }
+}
+
+fn simplex3(seed: u64, x: f64, y: f64, z: f64) -> f64 {
+    // Skew input space to find the containing simplex cell
+    let s = (x + y + z) * F3;
+    let i = (x + s).floor();
+    let j = (y + s).floor();
+    let k = (z + s).floor();
+
+    let t = (i + j + k) * G3;
+    let x0 = x - (i - t);
+    let y0 = y - (j - t);
+    let z0 = z - (k - t);
+
+    // Which of the six tetrahedra of the cube we are in
+    let (i1, j1, k1, i2, j2, k2) = if x0 >= y0 {
+        if y0 >= z0 {
+            (1, 0, 0, 1, 1, 0)
+        } else if x0 >= z0 {
+            (1, 0, 0, 1, 0, 1)
+        } else {
+            (0, 0, 1, 1, 0, 1)
+        }
+    } else if y0 < z0 {
+        (0, 0, 1, 0, 1, 1)
+    } else if x0 < z0 {
+        (0, 1, 0, 0, 1, 1)
+    } else {
+        (0, 1, 0, 1, 1, 0)
+    };
+
+    // Corner n of the simplex sits n * G3 further along the unskewed diagonal
+    let corners = [(0, 0, 0), (i1, j1, k1), (i2, j2, k2), (1, 1, 1)];
+    let (ci, cj, ck) = (i as i64, j as i64, k as i64);
+    let mut total = 0.0;
+
+    for (n, (di, dj, dk)) in corners.into_iter().enumerate() {
+        let dx = x0 - di as f64 + n as f64 * G3;
+        let dy = y0 - dj as f64 + n as f64 * G3;
+        let dz = z0 - dk as f64 + n as f64 * G3;
+
+        let falloff = 0.6 - dx * dx - dy * dy - dz * dz;
+        if falloff <= 0.0 {
+            continue;
+        }
+
+        let gradient = GRADIENTS[(lattice_hash(seed, ci + di, cj + dj, ck + dk) % 12) as usize];
+        let dot = gradient[0] * dx + gradient[1] * dy + gradient[2] * dz;
+        let falloff_sq = falloff * falloff;
+        total += falloff_sq * falloff_sq * dot;
+    }
+
+    // Scale so the output spans roughly [-1.0, 1.0]
+    32.0 * total
+}
+
+fn lattice_hash(seed: u64, i: i64, j: i64, k: i64) -> u64 {
+    let h = seed
+        ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (j as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (k as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    finalize(h)
+}
+
+fn mix(seed: u64, value: u64) -> u64 {
+    finalize(seed ^ value.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+// SplitMix64 finalizer
+fn finalize(mut h: u64) -> u64 {
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: u64 = 0x5EED_0015;
+    const SAMPLES: u64 = 20_000;
+
+    /// Points spread over a cube of side 200 around the origin, the same on every run.
+    fn sample_points() -> impl Iterator<Item = (f64, f64, f64)> {
+        (0..SAMPLES).map(|n| {
+            let coordinate = |axis: u64| {
+                let unit = finalize(mix(n, axis)) as f64 / u64::MAX as f64;
+                200.0 * unit - 100.0
+            };
+            (coordinate(0), coordinate(1), coordinate(2))
+        })
+    }
+
+    #[test]
+    fn fields_are_deterministic_per_seed() {
+        let params = Fbm::new(5, 0.3);
+        let (field, same, other) = (
+            NoiseField::new(SEED),
+            NoiseField::new(SEED),
+            NoiseField::new(SEED + 1),
+        );
+
+        let mut differences = 0;
+        for (x, y, z) in sample_points().take(1000) {
+            assert_eq!(field.simplex(x, y, z), same.simplex(x, y, z));
+            assert_eq!(field.fbm(x, y, z, params), same.fbm(x, y, z, params));
+            assert_eq!(field.ridged(x, y, z, params), same.ridged(x, y, z, params));
+            assert_eq!(
+                field.warped(x, y, z, params, 2.0),
+                same.warped(x, y, z, params, 2.0)
+            );
+            differences += (field.fbm(x, y, z, params) != other.fbm(x, y, z, params)) as u32;
+        }
+        assert!(differences > 990, "only {differences} samples differ");
+    }
+
+    #[test]
+    fn fractals_stay_in_range() {
+        let field = NoiseField::new(SEED);
+        let (mut fbm_min, mut fbm_max) = (f64::MAX, f64::MIN);
+
+        for params in [Fbm::new(1, 1.0), Fbm::new(4, 0.05), Fbm::new(8, 2.5)] {
+            for (x, y, z) in sample_points() {
+                let fbm = field.fbm(x, y, z, params);
+                assert!((-1.0..=1.0).contains(&fbm), "fbm {fbm} at ({x}, {y}, {z})");
+                fbm_min = fbm_min.min(fbm);
+                fbm_max = fbm_max.max(fbm);
+
+                let ridged = field.ridged(x, y, z, params);
+                assert!(
+                    (0.0..=1.0).contains(&ridged),
+                    "ridged {ridged} at ({x}, {y}, {z})"
+                );
+
+                let warped = field.warped(x, y, z, params, 4.0);
+                assert!(
+                    (-1.0..=1.0).contains(&warped),
+                    "warped {warped} at ({x}, {y}, {z})"
+                );
+            }
+        }
+        // The range is used, not just respected
+        assert!(
+            fbm_min < -0.5 && fbm_max > 0.5,
+            "fbm spans {fbm_min} to {fbm_max}"
+        );
+
+        // No octave at all is flat
+        assert_eq!(field.fbm(1.0, 2.0, 3.0, Fbm::new(0, 1.0)), 0.0);
+        assert_eq!(field.ridged(1.0, 2.0, 3.0, Fbm::new(0, 1.0)), 0.0);
+    }
+}
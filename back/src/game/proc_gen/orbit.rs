@@ -0,0 +1,262 @@
+use std::f64::consts::TAU;
+
+use crate::game::proc_gen::seed::{ORBIT_TAG, derive_seed};
+
+// Game time compression: a 1 AU orbit around one solar mass takes a day instead of a year.
+// Inner planets of red dwarfs then lap their star within hours, giants within weeks.
+pub const SECONDS_PER_ORBIT_YEAR: f64 = 86_400.0;
+
+// Neighbouring orbits are at least 1.3× apart, so with e below 0.13 they never cross
+const MAX_ECCENTRICITY: f64 = 0.12;
+const MAX_INCLINATION_DEG: f64 = 4.0;
+
+// Orbits are derived with the body seed, each element gets its own component
+const ROLL_ECCENTRICITY: i64 = 0;
+const ROLL_INCLINATION: i64 = 1;
+const ROLL_ASCENDING_NODE: i64 = 2;
+const ROLL_PERIAPSIS: i64 = 3;
+const ROLL_PHASE: i64 = 4;
+
+// Intercept search: the target's path is sampled this many times per period before refining
+const INTERCEPT_STEPS_PER_PERIOD: f64 = 64.0;
+const INTERCEPT_BISECTIONS: u32 = 48;
+
+const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
+
+/// Keplerian orbit of a body around its host, in the system's frame: the reference plane is
+/// x-y, like the asteroid belts'. Angles are in radians, the epoch is game time 0 (the world's
+/// start).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orbit {
+    pub semi_major_axis_au: f32,
+    pub eccentricity: f32,
+    pub inclination_rad: f32,
+    pub ascending_node_rad: f32,
+    pub argument_of_periapsis_rad: f32,
+    pub mean_anomaly_at_epoch_rad: f32,
+    pub period_s: f64, // Game seconds
+}
+
+impl Orbit {
+    /// Seeded orbit of semi-major axis `semi_major_axis_au` around a host of `host_mass` solar
+    /// masses. Mostly near-circular and close to the reference plane, like the Solar System's.
+    pub fn new(
+        body_seed: u64,
+        generation_version: u32,
+        semi_major_axis_au: f32,
+        host_mass: f32,
+    ) -> Self {
+        let roll = |component: i64| {
+            (derive_seed(body_seed, generation_version, ORBIT_TAG, &[component]) as f64)
+                * U64_TO_UNIT_F64
+        };
+
+        Self {
+            semi_major_axis_au,
+            eccentricity: (MAX_ECCENTRICITY * roll(ROLL_ECCENTRICITY).powi(2)) as f32,
+            inclination_rad: (MAX_INCLINATION_DEG * roll(ROLL_INCLINATION)).to_radians() as f32,
+            ascending_node_rad: (TAU * roll(ROLL_ASCENDING_NODE)) as f32,
+            argument_of_periapsis_rad: (TAU * roll(ROLL_PERIAPSIS)) as f32,
+            mean_anomaly_at_epoch_rad: (TAU * roll(ROLL_PHASE)) as f32,
+            period_s: orbital_period_s(semi_major_axis_au, host_mass),
+        }
+    }
+
+    /// Mean anomaly at game time `time_s`, in [0, 2π).
+    pub fn mean_anomaly(&self, time_s: f64) -> f64 {
+        (self.mean_anomaly_at_epoch_rad as f64 + TAU * time_s / self.period_s).rem_euclid(TAU)
+    }
+
+    /// Position relative to the host at game time `time_s`, in AU.
+    pub fn position_au(&self, time_s: f64) -> [f32; 3] {
+        let a = self.semi_major_axis_au as f64;
+        let e = self.eccentricity as f64;
+        let eccentric_anomaly = eccentric_anomaly(self.mean_anomaly(time_s), e);
+
+        // In the orbital plane, periapsis along x
+        let (sin_e, cos_e) = eccentric_anomaly.sin_cos();
+        let x = a * (cos_e - e);
+        let y = a * (1.0 - e * e).sqrt() * sin_e;
+
+        // Periapsis argument, then inclination about the line of nodes, then the node's
+        // longitude
+        let (sin_w, cos_w) = (self.argument_of_periapsis_rad as f64).sin_cos();
+        let (x, y) = (x * cos_w - y * sin_w, x * sin_w + y * cos_w);
+        let (sin_i, cos_i) = (self.inclination_rad as f64).sin_cos();
+        let (y, z) = (y * cos_i, y * sin_i);
+        let (sin_o, cos_o) = (self.ascending_node_rad as f64).sin_cos();
+        let (x, y) = (x * cos_o - y * sin_o, x * sin_o + y * cos_o);
+
+        [x as f32, y as f32, z as f32]
+    }
+
+    /// Farthest distance from the host, in AU.
+    pub fn apoapsis_au(&self) -> f32 {
+        self.semi_major_axis_au * (1.0 + self.eccentricity)
+    }
+}
+
+/// Kepler's third law in game time: P² = a³ / M years, a in AU and M in solar masses.
+pub fn orbital_period_s(semi_major_axis_au: f32, host_mass: f32) -> f64 {
+    let a = semi_major_axis_au as f64;
+    let years = (a * a * a / (host_mass as f64).max(f64::MIN_POSITIVE)).sqrt();
+    years * SECONDS_PER_ORBIT_YEAR
+}
+
+/// Solves Kepler's equation M = E - e sin E for the eccentric anomaly E (Newton's method).
+fn eccentric_anomaly(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let mut anomaly = mean_anomaly;
+    for _ in 0..8 {
+        let step = (anomaly - eccentricity * anomaly.sin() - mean_anomaly)
+            / (1.0 - eccentricity * anomaly.cos());
+        anomaly -= step;
+        if step.abs() < 1e-12 {
+            break;
+        }
+    }
+    anomaly
+}
+
+/// Where and when a ship meets an orbiting body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intercept {
+    pub arrival_s: f64,        // Game seconds
+    pub position_au: [f32; 3], // In the frame of `from_au`
+}
+
+/// Earliest point where a ship leaving `from_au` at game time `departure_s` and flying straight
+/// at `speed_au_per_s` meets a body on `orbit` around a host at `host_au` (both positions in
+/// the same frame). The ship aims where the body will be, not where it is, so the trip is
+/// shorter when the body comes towards it. `None` if the ship cannot move.
+///
+/// Meeting windows shorter than a 64th of the orbital period can be missed, in which case a
+/// later intercept is returned.
+pub fn intercept(
+    orbit: &Orbit,
+    host_au: [f32; 3],
+    from_au: [f32; 3],
+    departure_s: f64,
+    speed_au_per_s: f64,
+) -> Option<Intercept> {
+    if speed_au_per_s.is_nan() || speed_au_per_s <= 0.0 {
+        return None;
+    }
+
+    let target_at = |time_s: f64| {
+        let relative = orbit.position_au(time_s);
+        [0, 1, 2].map(|axis| host_au[axis] + relative[axis])
+    };
+    // Positive once the ship can have reached the body's position by `time_s`
+    let lead = |time_s: f64| {
+        let target = target_at(time_s);
+        let distance = (0..3)
+            .map(|axis| (target[axis] as f64 - from_au[axis] as f64).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        speed_au_per_s * (time_s - departure_s) - distance
+    };
+
+    // The body never strays farther than this from the ship's start, so the lead is positive
+    // by the time the ship could have flown it
+    let host_distance = (0..3)
+        .map(|axis| (host_au[axis] as f64 - from_au[axis] as f64).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    let latest_s = departure_s + (host_distance + orbit.apoapsis_au() as f64) / speed_au_per_s;
+    let step_s = orbit.period_s / INTERCEPT_STEPS_PER_PERIOD;
+
+    let (mut before, mut after) = (departure_s, departure_s);
+    while lead(after) < 0.0 && after < latest_s {
+        before = after;
+        after = (after + step_s).min(latest_s);
+    }
+
+    for _ in 0..INTERCEPT_BISECTIONS {
+        let middle = 0.5 * (before + after);
+        if lead(middle) < 0.0 {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+
+    Some(Intercept {
+        arrival_s: after,
+        position_au: target_at(after),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::proc_gen::seed::CURRENT_GENERATION_VERSION;
+
+    fn distance(a: [f32; 3], b: [f32; 3]) -> f64 {
+        (0..3)
+            .map(|axis| (a[axis] as f64 - b[axis] as f64).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn intercepts_meet_the_body_on_its_orbit_once_the_ship_can_get_there() {
+        let host_au = [0.4, -1.5, 0.02];
+        let mut checked = 0;
+
+        for seed in 0..40u64 {
+            let orbit = Orbit::new(
+                seed,
+                CURRENT_GENERATION_VERSION,
+                0.2 + seed as f32 * 0.15,
+                0.3 + seed as f32 * 0.05,
+            );
+            for from_au in [[0.0, 0.0, 0.0], [-3.0, 2.5, 0.1], host_au] {
+                for departure_s in [0.0, 1_234.5, 3.0 * orbit.period_s] {
+                    for speed_au_per_s in [1e-5, 1e-3, 0.1] {
+                        let found =
+                            intercept(&orbit, host_au, from_au, departure_s, speed_au_per_s)
+                                .unwrap();
+                        assert!(found.arrival_s >= departure_s);
+
+                        let on_orbit = orbit.position_au(found.arrival_s);
+                        let expected = [0, 1, 2].map(|axis| host_au[axis] + on_orbit[axis]);
+                        assert_eq!(found.position_au, expected, "orbit {seed}");
+
+                        let flown = speed_au_per_s * (found.arrival_s - departure_s);
+                        assert!(
+                            flown >= distance(from_au, found.position_au),
+                            "orbit {seed}: the ship flies {flown} AU, short of the body"
+                        );
+                        checked += 1;
+                    }
+                }
+            }
+        }
+        assert_eq!(checked, 40 * 3 * 3 * 3);
+    }
+
+    #[test]
+    fn ships_that_cannot_move_never_intercept() {
+        let orbit = Orbit::new(7, CURRENT_GENERATION_VERSION, 1.0, 1.0);
+        for speed_au_per_s in [0.0, -1.0, f64::NAN] {
+            assert_eq!(
+                intercept(&orbit, [0.0; 3], [2.0, 0.0, 0.0], 0.0, speed_au_per_s),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn positions_stay_between_periapsis_and_apoapsis() {
+        for seed in 0..20u64 {
+            let orbit = Orbit::new(seed, CURRENT_GENERATION_VERSION, 1.0 + seed as f32, 1.0);
+            let periapsis = orbit.semi_major_axis_au * (1.0 - orbit.eccentricity);
+            for step in 0..50 {
+                let time_s = orbit.period_s * step as f64 / 50.0;
+                let radius = distance(orbit.position_au(time_s), [0.0; 3]) as f32;
+                assert!(radius >= periapsis * 0.9999 && radius <= orbit.apoapsis_au() * 1.0001);
+            }
+            assert_eq!(orbit.position_au(0.0), orbit.position_au(orbit.period_s));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use crate::game::proc_gen::planet::PlanetType;
+use crate::game::proc_gen::seed::{
+    PLANET_ATMOSPHERE_TAG, PLANET_AXIAL_TILT_TAG, PLANET_CHEMISTRY_TAG, PLANET_MASS_TAG,
+    PLANET_RADIUS_TAG, PLANET_WATER_TAG, SeedTag, derive_seed,
+};
+
+const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
+
+// Earth reference values: masses and radii below are in Earth units
+const EARTH_ESCAPE_VELOCITY_KM_S: f32 = 11.2;
+
+// Tile resolution: an Earth-sized world gets GP(20, 0) (4,002 tiles), the range is clamped to
+// GP(3, 0) (92 tiles) for small rocks and GP(32, 0) (10,242 tiles) for super-Earths and giants
+const EARTH_SUBDIVISION: f32 = 20.0;
+const MIN_SUBDIVISION: u32 = 3;
+const MAX_SUBDIVISION: u32 = 32;
+
+// Below this escape velocity (km/s) a body keeps no meaningful atmosphere
+const MIN_ATMOSPHERE_ESCAPE_VELOCITY_KM_S: f32 = 2.5;
+// Surface pressure (atm) separating trace, thin and full atmospheres
+const TRACE_PRESSURE_ATM: f32 = 0.006;
+const THIN_PRESSURE_ATM: f32 = 0.3;
+// Oceans cap at 80% of the surface so every wet world still has some land
+const MAX_WATER_FRACTION: f32 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtmosphereType {
+    None,
+    Thin,
+    Nitrogen,
+    Breathable,
+    CarbonDioxide,
+    Toxic,
+    HydrogenHelium,
+}
+
+impl AtmosphereType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AtmosphereType::None => "none",
+            AtmosphereType::Thin => "thin",
+            AtmosphereType::Nitrogen => "nitrogen",
+            AtmosphereType::Breathable => "breathable",
+            AtmosphereType::CarbonDioxide => "carbon_dioxide",
+            AtmosphereType::Toxic => "toxic",
+            AtmosphereType::HydrogenHelium => "hydrogen_helium",
+        }
+    }
+
+    /// Greenhouse warming per unit of ln(1 + pressure in atm).
+    /// Tuned so 1 atm of nitrogen gives Earth's 33 K and 90 atm of CO2 gives Venus-like heat.
+    fn greenhouse_strength_k(&self) -> f32 {
+        match self {
+            AtmosphereType::None | AtmosphereType::HydrogenHelium => 0.0,
+            AtmosphereType::Thin | AtmosphereType::Nitrogen | AtmosphereType::Breathable => 47.6,
+            AtmosphereType::Toxic => 60.0,
+            AtmosphereType::CarbonDioxide => 100.0,
+        }
+    }
+}
+
+/// Bulk physical profile of a planet, derived once from its seed, class and insolation.
+#[derive(Debug, Clone, Copy)]
+pub struct PlanetPhysics {
+    pub mass_earth: f32,
+    pub radius_earth: f32,
+    pub surface_gravity_g: f32,
+    pub atmosphere: AtmosphereType,
+    pub surface_pressure_atm: f32,
+    // Share of the surface covered by oceans or sea ice, 0.0 (dry) to MAX_WATER_FRACTION
+    pub water_fraction: f32,
+    pub axial_tilt_deg: f32,
+}
+
+impl PlanetPhysics {
+    /// `equilibrium_temp_k` is the airless blackbody temperature of the orbit (see climate.rs).
+    pub fn new(
+        planet_seed: u64,
+        generation_version: u32,
+        class: PlanetType,
+        equilibrium_temp_k: f32,
+    ) -> Self {
+        let mass_roll = roll(planet_seed, generation_version, PLANET_MASS_TAG);
+        let radius_roll = roll(planet_seed, generation_version, PLANET_RADIUS_TAG);
+
+        // Masses are log-uniform within each class
+        let (mass_earth, radius_earth) = match class {
+            PlanetType::Terrestrial => {
+                let mass = log_lerp(0.05, 8.0, mass_roll);
+                // Rocky mass-radius relation, with ±5% for composition
+                (mass, mass.powf(0.27) * (0.95 + 0.1 * radius_roll))
+            }
+            PlanetType::IceGiant => (log_lerp(10.0, 25.0, mass_roll), 3.5 + 0.7 * radius_roll),
+            // Degenerate cores keep gas giants near Jupiter's radius whatever their mass
+            PlanetType::GasGiant => (log_lerp(30.0, 3000.0, mass_roll), 9.0 + 3.0 * radius_roll),
+            PlanetType::AsteroidField => {
+                let mass = log_lerp(0.0001, 0.001, mass_roll);
+                (mass, mass.powf(0.3))
+            }
+            // From Deimos-like rubble up to Ganymede: around 100 to 500 tiles
+            PlanetType::Moon => {
+                let mass = log_lerp(0.0003, 0.025, mass_roll);
+                (mass, mass.powf(0.3) * (0.9 + 0.2 * radius_roll))
+            }
+        };
+
+        let surface_gravity_g = mass_earth / (radius_earth * radius_earth);
+
+        // Most planets lean a little, a few are tipped over (Uranus-style)
+        let tilt_roll = roll(planet_seed, generation_version, PLANET_AXIAL_TILT_TAG);
+        let axial_tilt_deg = tilt_roll * tilt_roll * 90.0;
+
+        let mut physics = Self {
+            mass_earth,
+            radius_earth,
+            surface_gravity_g,
+            atmosphere: AtmosphereType::None,
+            surface_pressure_atm: 0.0,
+            water_fraction: 0.0,
+            axial_tilt_deg,
+        };
+
+        match class {
+            PlanetType::GasGiant | PlanetType::IceGiant => {
+                physics.atmosphere = AtmosphereType::HydrogenHelium;
+                // No surface: pressure is quoted at the 1 bar cloud deck
+                physics.surface_pressure_atm = 1.0;
+            }
+            PlanetType::Terrestrial | PlanetType::Moon => {
+                physics.generate_atmosphere(planet_seed, generation_version, equilibrium_temp_k)
+            }
+            PlanetType::AsteroidField => {}
+        }
+
+        physics
+    }
+
+    fn generate_atmosphere(
+        &mut self,
+        planet_seed: u64,
+        generation_version: u32,
+        equilibrium_temp_k: f32,
+    ) {
+        // Hot, light worlds lose their gas to thermal escape
+        let retention = self.escape_velocity_km_s() / (equilibrium_temp_k / 255.0).max(0.2).sqrt();
+        if retention < MIN_ATMOSPHERE_ESCAPE_VELOCITY_KM_S {
+            return;
+        }
+
+        // Heavier worlds hold thicker air: 1/20x to 5x of the mass-scaled baseline
+        let pressure_roll = roll(planet_seed, generation_version, PLANET_ATMOSPHERE_TAG);
+        let pressure_atm = self.mass_earth.powf(1.2) * 10f32.powf(pressure_roll * 2.0 - 1.3);
+        if pressure_atm < TRACE_PRESSURE_ATM {
+            return;
+        }
+        self.surface_pressure_atm = pressure_atm;
+
+        let has_liquid_water_band = (230.0..340.0).contains(&equilibrium_temp_k);
+        let chemistry_roll = roll(planet_seed, generation_version, PLANET_CHEMISTRY_TAG);
+
+        self.atmosphere = if pressure_atm < THIN_PRESSURE_ATM {
+            AtmosphereType::Thin
+        } else if equilibrium_temp_k > 300.0 && pressure_atm > 5.0 {
+            // Runaway greenhouse: oceans boiled off, carbonate rocks baked out (Venus)
+            AtmosphereType::CarbonDioxide
+        } else if has_liquid_water_band && pressure_atm < 5.0 && chemistry_roll < 0.35 {
+            // Photosynthesis needs stable surface water
+            AtmosphereType::Breathable
+        } else if chemistry_roll < 0.6 {
+            AtmosphereType::Nitrogen
+        } else if chemistry_roll < 0.85 {
+            AtmosphereType::CarbonDioxide
+        } else {
+            AtmosphereType::Toxic
+        };
+
+        // Open water needs air pressure comfortably above water's triple point
+        if pressure_atm > 0.05 {
+            let water_roll = roll(planet_seed, generation_version, PLANET_WATER_TAG);
+            self.water_fraction = water_roll * MAX_WATER_FRACTION;
+        }
+    }
+
+    pub fn escape_velocity_km_s(&self) -> f32 {
+        EARTH_ESCAPE_VELOCITY_KM_S * (self.mass_earth / self.radius_earth.max(0.0001)).sqrt()
+    }
+
+    /// Extra surface heating trapped by the atmosphere.
+    pub fn greenhouse_warming_k(&self) -> f32 {
+        self.atmosphere.greenhouse_strength_k() * self.surface_pressure_atm.ln_1p()
+    }
+
+    pub fn is_breathable(&self) -> bool {
+        self.atmosphere == AtmosphereType::Breathable
+    }
+
+    /// Goldberg subdivision for this body: tile count grows with surface area, damped so small
+    /// moons stay playable and giants do not explode the tile budget.
+    pub fn subdivision(&self) -> u32 {
+        let n = (EARTH_SUBDIVISION * self.radius_earth.powf(0.8)).round() as u32;
+        n.clamp(MIN_SUBDIVISION, MAX_SUBDIVISION)
+    }
+}
+
+fn roll(planet_seed: u64, generation_version: u32, tag: SeedTag) -> f32 {
+    ((derive_seed(planet_seed, generation_version, tag, &[]) as f64) * U64_TO_UNIT_F64) as f32
+}
+
+fn log_lerp(min: f32, max: f32, t: f32) -> f32 {
+    min * (max / min).powf(t)
+}
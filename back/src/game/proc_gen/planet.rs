@@ -1,7 +1,12 @@
+use std::sync::Arc;
+
+use crate::game::proc_gen::climate::{PlanetClimate, equilibrium_temperature_k};
+use crate::game::proc_gen::deposit::PlanetGeology;
+use crate::game::proc_gen::goldberg::GoldbergTopology;
+use crate::game::proc_gen::physics::PlanetPhysics;
 use crate::game::proc_gen::seed::{PLANET_TAG, derive_seed};
-use crate::game::proc_gen::tile::{
-    DynamicTileProperties, calculate_tile_properties, get_hex_neighbors,
-};
+use crate::game::proc_gen::star_system::{BodyType, Moon, OrbitalBody, Star, StarSystem};
+use crate::game::proc_gen::tile::{DynamicTileProperties, calculate_tile_properties};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlanetType {
@@ -9,6 +14,19 @@ pub enum PlanetType {
     GasGiant,
     IceGiant,
     AsteroidField,
+    Moon,
+}
+
+impl PlanetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanetType::Terrestrial => "terrestrial",
+            PlanetType::GasGiant => "gas_giant",
+            PlanetType::IceGiant => "ice_giant",
+            PlanetType::AsteroidField => "asteroid_field",
+            PlanetType::Moon => "moon",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -16,66 +34,163 @@ pub struct Planet {
     pub id: Option<i64>,
     pub star_system_id: i64,
     pub seed: u64,
+    pub generation_version: u32,
     pub planet_pos: (i32, i32, i32),
+    // Address inside the system: the orbital slot, plus the moon index for satellites
+    pub orbit_index: u32,
+    pub moon_index: Option<u32>,
     pub class: PlanetType,
     pub semi_major_axis_au: f32,
     pub is_in_habitable_zone: bool,
     pub subdivision: u32,
+    pub physics: PlanetPhysics,
+    pub climate: PlanetClimate,
+    pub geology: PlanetGeology,
 }
 
 impl Planet {
-    pub fn new(
-        star_system_id: i64,
-        system_seed: u64,
-        planet_pos: (i32, i32, i32),
-        index: u32,
-        is_in_habitable_zone: bool,
-        semi_major_axis_au: f32,
-    ) -> Self {
+    pub fn new(star_system_id: i64, system: &StarSystem, body: &OrbitalBody, star: &Star) -> Self {
+        let (x, y, z) = system.position;
         let seed = derive_seed(
-            system_seed,
+            system.seed,
+            system.generation_version,
             PLANET_TAG,
-            &[
-                planet_pos.0 as i64,
-                planet_pos.1 as i64,
-                planet_pos.2 as i64,
-                index as i64,
-            ],
+            &[x as i64, y as i64, z as i64, body.index as i64],
         );
 
-        // Resolution default for Goldberg Sphere: 32x32 tiles per face
-        let subdivision = 32;
-
-        let class = if semi_major_axis_au > 5.0 {
-            PlanetType::GasGiant
-        } else if semi_major_axis_au > 3.0 {
-            PlanetType::IceGiant
-        } else {
-            PlanetType::Terrestrial
+        let class = match body.body_type {
+            BodyType::Terrestrial => PlanetType::Terrestrial,
+            BodyType::GasGiant => PlanetType::GasGiant,
+            BodyType::IceGiant => PlanetType::IceGiant,
+            BodyType::AsteroidBelt => PlanetType::AsteroidField,
         };
 
+        Self::build(star_system_id, system, seed, body, None, class, star)
+    }
+
+    /// A moon of `body`, with the same surface and tile API as a planet.
+    pub fn new_moon(
+        star_system_id: i64,
+        system: &StarSystem,
+        body: &OrbitalBody,
+        moon: &Moon,
+        star: &Star,
+    ) -> Self {
+        Self::build(
+            star_system_id,
+            system,
+            moon.seed,
+            body,
+            Some(moon.index),
+            PlanetType::Moon,
+            star,
+        )
+    }
+
+    /// All moons of `body` as planets.
+    pub fn moons_of(
+        star_system_id: i64,
+        system: &StarSystem,
+        body: &OrbitalBody,
+        star: &Star,
+    ) -> Vec<Planet> {
+        body.moons
+            .iter()
+            .map(|moon| Self::new_moon(star_system_id, system, body, moon, star))
+            .collect()
+    }
+
+    /// Regenerates the planet or moon stored at (`orbit_index`, `moon_index`) of `system`.
+    /// Returns `None` if the system has no such body.
+    pub fn from_address(
+        star_system_id: i64,
+        system: &StarSystem,
+        orbit_index: u32,
+        moon_index: Option<u32>,
+    ) -> Option<Self> {
+        let body = system
+            .bodies
+            .iter()
+            .find(|body| body.index == orbit_index)?;
+        let star = system.host_star(body.host);
+
+        match moon_index {
+            None => Some(Self::new(star_system_id, system, body, &star)),
+            Some(index) => {
+                let moon = body.moons.iter().find(|moon| moon.index == index)?;
+                Some(Self::new_moon(star_system_id, system, body, moon, &star))
+            }
+        }
+    }
+
+    fn build(
+        star_system_id: i64,
+        system: &StarSystem,
+        seed: u64,
+        body: &OrbitalBody,
+        moon_index: Option<u32>,
+        class: PlanetType,
+        star: &Star,
+    ) -> Self {
+        // Moons share their parent's orbit around the star, and so its insolation
+        let semi_major_axis_au = body.semi_major_axis_au;
+        let generation_version = system.generation_version;
+        let physics = PlanetPhysics::new(
+            seed,
+            generation_version,
+            class,
+            equilibrium_temperature_k(star, semi_major_axis_au),
+        );
+        let climate = PlanetClimate::new(star, semi_major_axis_au, &physics);
+        let geology = PlanetGeology::new(class, star, &physics);
+
+        // Goldberg sphere resolution scales with the planet's size
+        let subdivision = physics.subdivision();
+
         Self {
             id: None,
             star_system_id,
             seed,
-            planet_pos,
+            generation_version,
+            planet_pos: system.position,
+            orbit_index: body.index,
+            moon_index,
             class,
             semi_major_axis_au,
-            is_in_habitable_zone,
+            is_in_habitable_zone: body.is_in_habitable_zone,
             subdivision,
+            physics,
+            climate,
+            geology,
         }
     }
 
+    /// Tile graph shared by every planet with the same subdivision.
+    pub fn topology(&self) -> Arc<GoldbergTopology> {
+        GoldbergTopology::for_subdivision(self.subdivision)
+    }
+
     /// Query exact physical state for a single tile on demand.
-    pub fn query_tile(&self, face: u8, u: u32, v: u32) -> DynamicTileProperties {
+    /// Returns `None` if (face, u, v) is not a tile of this planet.
+    pub fn query_tile(&self, face: u8, u: u32, v: u32) -> Option<DynamicTileProperties> {
+        let topology = self.topology();
+        let index = topology.tile_index(face, u, v)?;
+        Some(self.query_tile_in(&topology, index))
+    }
+
+    /// Query a tile by its stable index (see `GoldbergTopology`).
+    pub fn query_tile_at(&self, index: u32) -> DynamicTileProperties {
+        self.query_tile_in(&self.topology(), index)
+    }
+
+    fn query_tile_in(&self, topology: &GoldbergTopology, index: u32) -> DynamicTileProperties {
         calculate_tile_properties(
             self.seed,
-            self.is_in_habitable_zone,
-            self.semi_major_axis_au,
-            self.subdivision,
-            face,
-            u,
-            v,
+            self.generation_version,
+            &self.climate,
+            &self.geology,
+            topology,
+            index,
         )
     }
 
@@ -85,15 +200,17 @@ impl Planet {
         face: u8,
         u: u32,
         v: u32,
-    ) -> (DynamicTileProperties, Vec<DynamicTileProperties>) {
-        let center = self.query_tile(face, u, v);
-        let neighbor_coords = get_hex_neighbors(face, u, v, self.subdivision);
+    ) -> Option<(DynamicTileProperties, Vec<DynamicTileProperties>)> {
+        let topology = self.topology();
+        let index = topology.tile_index(face, u, v)?;
 
-        let neighbors = neighbor_coords
-            .into_iter()
-            .map(|(nf, nu, nv)| self.query_tile(nf, nu, nv))
+        let center = self.query_tile_in(&topology, index);
+        let neighbors = topology
+            .neighbors(index)
+            .iter()
+            .map(|&neighbor| self.query_tile_in(&topology, neighbor))
             .collect();
 
-        (center, neighbors)
+        Some((center, neighbors))
     }
 }
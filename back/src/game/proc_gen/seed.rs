@@ -1,3 +1,4 @@
+use anyhow::{Result, bail};
 use xxhash_rust::xxh3::xxh3_64_with_seed;
 
 // # Golden Rules
@@ -17,51 +18,183 @@ use xxhash_rust::xxh3::xxh3_64_with_seed;
 // Store only player-created state.
 // Everything else is regenerated from:
 // ```text
-// world_seed + location + tag
-
-pub const WORLD_SEED: u64 = 42;
+// world_seed + generation_version + location + tag
+// ```
 
 // If generation changes later:
-// GENERATION_VERSION = 1;
+// CURRENT_GENERATION_VERSION = 3;
 // New worlds use new generation.
-// Old saves keep version 1.
-pub const GENERATION_VERSION: u64 = 1;
+// Old worlds keep the version stored in their `worlds` row.
+//
+// Version 1 (the original, colliding tags with generators rewritten since) is not
+// regenerated anymore: such worlds are refused and their database must be reset.
+// Version 2: the registry numbering below, with the version mixed into the root.
+pub const MIN_GENERATION_VERSION: u32 = 2;
+pub const CURRENT_GENERATION_VERSION: u32 = 2;
+
+/// A `derive_seed` tag. Every tag is declared once in the registry below, which refuses to
+/// compile if two tags share a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedTag {
+    value: u64,
+    // First generation version whose generators use the tag
+    since: u32,
+}
+
+impl SeedTag {
+    /// First generation version whose generators use the tag.
+    pub const fn since(self) -> u32 {
+        self.since
+    }
+}
 
-pub const GALAXY_TAG: u64 = 2;
-pub const GALAXY_SPAWN_TAG: u64 = 3;
-pub const GALAXY_TYPE_TAG: u64 = 4;
-pub const GALAXY_IRREGULAR_NOISE_TAG: u64 = 5;
-pub const GALAXY_FAST_3D_NOISE_TAG: u64 = 6;
+macro_rules! seed_tags {
+    ($($name:ident = $value:literal $((since $since:literal))?,)*) => {
+        $(pub const $name: SeedTag = SeedTag {
+            value: $value,
+            since: seed_tags!(@since $($since)?),
+        };)*
 
-pub const STAR_SYSTEM_TAG: u64 = 100;
-pub const STAR_SYSTEM_SPAWN_TAG: u64 = 101;
-pub const STAR_SYSTEM_ATTR_TAG: u64 = 200;
-pub const STAR_SYSTEM_BODY_TYPE_TAG: u64 = 200;
-pub const STAR_SYSTEM_ORBIT_SPACING_TAG: u64 = 200;
-pub const STAR_SYSTEM_STAR_MASS_TAG: u64 = 200;
-pub const STAR_SYSTEM_SMBH_SPAWN_TAG: u64 = 200;
+        pub const ALL_SEED_TAGS: &[(&str, SeedTag)] = &[$((stringify!($name), $name)),*];
+    };
+    (@since) => { MIN_GENERATION_VERSION };
+    (@since $since:literal) => { $since };
+}
+
+// One block of 100 per hierarchy level. A tag added for a later version says since which one,
+// e.g. `NEW_TAG = 317 (since 3),`
+seed_tags! {
+    // Universe (parent: world root seed)
+    UNIVERSE_DENSITY_NOISE_TAG = 100,
 
-pub const PLANET_TAG: u64 = 3;
-pub const TILE_TAG: u64 = 4;
+    // Galaxy
+    GALAXY_TAG = 200,
+    GALAXY_SPAWN_TAG = 201,
+    GALAXY_TYPE_TAG = 202,
+    GALAXY_IRREGULAR_NOISE_TAG = 203,
+    GALAXY_STRUCTURE_TAG = 204,
 
-pub const PLANET_SUBDIVISION_TAG: u64 = 12;
+    // Star system
+    STAR_SYSTEM_TAG = 300,
+    STAR_SYSTEM_SPAWN_TAG = 301,
+    STAR_SYSTEM_ATTR_TAG = 302,
+    STAR_SYSTEM_BODY_TYPE_TAG = 303,
+    STAR_SYSTEM_ORBIT_SPACING_TAG = 304,
+    STAR_SYSTEM_STAR_MASS_TAG = 305,
+    STAR_SYSTEM_SMBH_SPAWN_TAG = 306,
+    STAR_SYSTEM_MULTIPLICITY_TAG = 307,
+    STAR_SYSTEM_COMPANION_TAG = 308,
+    STAR_SYSTEM_HOST_GROUP_TAG = 309,
+    PLANET_SPAWN_TAG = 310,
+    STAR_SYSTEM_METALLICITY_TAG = 311,
+    ASTEROID_SPAWN_TAG = 312,
+    ASTEROID_TAG = 313,
+    ANOMALY_SPAWN_TAG = 314,
+    ANOMALY_TAG = 315,
+    ORBIT_TAG = 316,
 
-pub const PLANET_SPAWN_TAG: u64 = 325;
+    // Planet
+    PLANET_TAG = 400,
+    PLANET_MASS_TAG = 401,
+    PLANET_RADIUS_TAG = 402,
+    PLANET_AXIAL_TILT_TAG = 403,
+    PLANET_ATMOSPHERE_TAG = 404,
+    PLANET_CHEMISTRY_TAG = 405,
+    PLANET_WATER_TAG = 406,
+
+    // Moons and rings
+    MOON_TAG = 500,
+    MOON_SPAWN_TAG = 501,
+    MOON_ORBIT_SPACING_TAG = 502,
+    RING_SPAWN_TAG = 510,
+    RING_ATTR_TAG = 511,
+
+    // Tile
+    TILE_TAG = 600,
+    TILE_NOISE_OFFSET_TAG = 601,
+    TILE_DEPOSIT_TAG = 602,
+    TILE_ELEVATION_NOISE_TAG = 603,
+    TILE_MOISTURE_NOISE_TAG = 604,
+    TILE_VEIN_NOISE_TAG = 605,
+}
+
+const _: () = assert!(
+    seed_tags_are_unique(ALL_SEED_TAGS),
+    "two seed tags share the same value"
+);
+
+const fn seed_tags_are_unique(tags: &[(&str, SeedTag)]) -> bool {
+    let mut i = 0;
+    while i < tags.len() {
+        let mut j = i + 1;
+        while j < tags.len() {
+            if tags[i].1.value == tags[j].1.value {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
 
 // # Adding Features Later
 // Do not change hierarchy.
-// Add new tags.
-// const STAR_TYPE_TAG: u64 = 10;
-// const PLANET_COUNT_TAG: u64 = 11;
-// const TILE_HEIGHT_TAG: u64 = 100;
-// const TILE_BIOME_TAG: u64 = 101;
-// const ASTEROID_COUNT_TAG: u64 = 200;
-// const STATION_COUNT_TAG: u64 = 201;
-
-pub fn derive_seed(base_seed: u64, tag: u64, components: &[i64]) -> u64 {
-    // Start with a mix of our global generation version, base seed, and specific feature tag
-    let mut current_hash = xxh3_64_with_seed(&tag.to_le_bytes(), base_seed);
-    current_hash = xxh3_64_with_seed(&GENERATION_VERSION.to_le_bytes(), current_hash);
+// Add new tags to the registry, in the block of their level, and only use them in generators
+// for the versions that have them.
+// Never renumber a tag without bumping CURRENT_GENERATION_VERSION: that regenerates every world.
+
+/// Fails with a reset hint if this server cannot regenerate worlds of `generation_version`.
+pub fn check_generation_version(generation_version: u32) -> Result<()> {
+    if generation_version < MIN_GENERATION_VERSION {
+        bail!(
+            "generation version {generation_version} is no longer supported (oldest is \
+             {MIN_GENERATION_VERSION}): its universe cannot be regenerated, reset the database"
+        );
+    }
+    if generation_version > CURRENT_GENERATION_VERSION {
+        bail!(
+            "generation version {generation_version} is newer than this server \
+             ({CURRENT_GENERATION_VERSION})"
+        );
+    }
+    Ok(())
+}
+
+/// Root of a world's hierarchy, with the generation version every seed below it is derived
+/// with. Galaxies take it, and pass the version on to what they generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootSeed {
+    pub seed: u64,
+    pub generation_version: u32,
+}
+
+impl RootSeed {
+    /// The generation version is mixed in here, once: every other seed descends from this one
+    /// (Rule 1), so a world keeps its exact universe for as long as its stored version is
+    /// passed in, whatever later versions change.
+    pub fn new(world_seed: u64, generation_version: u32) -> Self {
+        Self {
+            seed: xxh3_64_with_seed(&(generation_version as u64).to_le_bytes(), world_seed),
+            generation_version,
+        }
+    }
+}
+
+pub fn derive_seed(
+    base_seed: u64,
+    generation_version: u32,
+    tag: SeedTag,
+    components: &[i64],
+) -> u64 {
+    debug_assert!(
+        tag.since <= generation_version,
+        "seed tag {} is used by a version {generation_version} generator",
+        tag.value
+    );
+
+    // Start with a mix of the parent seed and the specific feature tag
+    let mut current_hash = xxh3_64_with_seed(&tag.value.to_le_bytes(), base_seed);
 
     // Append all location/coordinate constraints sequentially
     for &comp in components {
@@ -0,0 +1,331 @@
+// Golden snapshot of the generator. A fixed sample of the universe (seeds, galaxies, star
+// systems, planets and their tile maps) is dumped as text and compared with the dump checked in
+// for every supported generation version. Any difference means existing worlds would
+// regenerate differently: either undo the drift, or bump CURRENT_GENERATION_VERSION and commit
+// the new dump next to the old one.
+//
+// Regenerate the dump of the current version (older ones are frozen) with:
+//     UPDATE_GOLDEN=1 cargo test golden_universe
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::game::proc_gen::chunk::{ChunkCoord, STAR_SYSTEM_CHUNK_SIZE, galaxies_in_chunk};
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::nearby::nearest_star_systems;
+use crate::game::proc_gen::planet::Planet;
+use crate::game::proc_gen::seed::{
+    ALL_SEED_TAGS, CURRENT_GENERATION_VERSION, MIN_GENERATION_VERSION, RootSeed, derive_seed,
+};
+use crate::game::proc_gen::space_object::SpaceObjects;
+use crate::game::proc_gen::star_system::{StarSystem, StarType};
+use crate::game::proc_gen::tile::TileType;
+use crate::game::resource::Resource;
+
+const SNAPSHOT_WORLD_SEED: u64 = 0x5EED;
+// Galaxies of cosmic chunk (0, 0, 0) whose systems and planets are dumped in full
+const DETAILED_GALAXIES: usize = 3;
+// Systems nearest to each detailed galaxy's core
+const SYSTEMS_PER_GALAXY: usize = 3;
+const CORE_SEARCH_RADIUS: f32 = 32.0;
+// Characters per line of a tile map
+const TILE_MAP_WIDTH: usize = 96;
+
+fn golden_path(generation_version: u32) -> String {
+    format!(
+        "{}/tests/golden/universe_v{generation_version}.txt",
+        env!("CARGO_MANIFEST_DIR"),
+    )
+}
+
+fn dump_universe(generation_version: u32) -> String {
+    let mut out = String::new();
+    let root = RootSeed::new(SNAPSHOT_WORLD_SEED, generation_version);
+
+    writeln!(out, "# generation v{generation_version}").unwrap();
+    writeln!(out, "world_seed {SNAPSHOT_WORLD_SEED} root {}", root.seed).unwrap();
+
+    // Renumbering a tag, or changing the hash, moves every seed below it
+    writeln!(out, "\n## seeds").unwrap();
+    for (name, tag) in ALL_SEED_TAGS
+        .iter()
+        .filter(|(_, tag)| tag.since() <= generation_version)
+    {
+        let seed = derive_seed(root.seed, generation_version, *tag, &[1, -2, 3]);
+        writeln!(out, "{name} {seed}").unwrap();
+    }
+
+    writeln!(out, "\n## galaxies of cosmic chunk (0, 0, 0)").unwrap();
+    let galaxies = galaxies_in_chunk(root, ChunkCoord::new(0, 0, 0));
+    for galaxy in &galaxies {
+        writeln!(
+            out,
+            "{:?} {} seed {} {:?}",
+            galaxy.position,
+            galaxy.galaxy_type.as_str(),
+            galaxy.seed,
+            galaxy.structure
+        )
+        .unwrap();
+    }
+
+    let mut planets = Vec::new();
+    for galaxy in galaxies.iter().take(DETAILED_GALAXIES) {
+        dump_galaxy(&mut out, galaxy, &mut planets);
+    }
+
+    writeln!(out, "\n## planets").unwrap();
+    for planet in &planets {
+        dump_planet(&mut out, planet);
+    }
+
+    // Whole tile maps are slow to generate: only the first planet of each class gets one
+    let mut mapped = Vec::new();
+    for planet in &planets {
+        if !mapped.contains(&planet.class) {
+            mapped.push(planet.class);
+            dump_tile_map(&mut out, planet);
+        }
+    }
+
+    out
+}
+
+fn dump_galaxy(out: &mut String, galaxy: &Galaxy, planets: &mut Vec<Planet>) {
+    writeln!(out, "\n## galaxy {:?}", galaxy.position).unwrap();
+
+    // Spawn mask of one chunk off the core: pins the density function cell by cell
+    let chunk = ChunkCoord::new(1, 0, 0);
+    let (min, max) = chunk.bounds(STAR_SYSTEM_CHUNK_SIZE).unwrap();
+    let mut mask = Vec::new();
+    for x in min.0..=max.0 {
+        for y in min.1..=max.1 {
+            for z in min.2..=max.2 {
+                mask.push(galaxy.should_spawn_star_system((x, y, z)) as u8);
+            }
+        }
+    }
+    writeln!(
+        out,
+        "chunk {:?}: {} systems, mask hash {:016x}",
+        chunk,
+        mask.iter().filter(|&&spawned| spawned == 1).count(),
+        xxh3_64(&mask)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "metallicity at core {:?}, at chunk {:?}",
+        galaxy.mean_metallicity((0, 0, 0)),
+        galaxy.mean_metallicity(max)
+    )
+    .unwrap();
+
+    for nearby in nearest_star_systems(galaxy, (0, 0, 0), CORE_SEARCH_RADIUS, SYSTEMS_PER_GALAXY) {
+        let system = StarSystem::new(galaxy, nearby.position);
+        dump_star_system(out, &system);
+
+        for body in &system.bodies {
+            let star = system.host_star(body.host);
+            planets.push(Planet::new(0, &system, body, &star));
+            planets.extend(Planet::moons_of(0, &system, body, &star));
+        }
+    }
+}
+
+fn dump_star_system(out: &mut String, system: &StarSystem) {
+    writeln!(
+        out,
+        "system {:?} seed {} {:?}",
+        system.position, system.seed, system.configuration
+    )
+    .unwrap();
+
+    for star in &system.stars {
+        let class = match star.star_type {
+            StarType::Spectral(spectral) => spectral.as_str(),
+            _ => star.star_type.as_str(),
+        };
+        writeln!(
+            out,
+            "  star {class} mass {:?} radius {:?} luminosity {:?} temp {} [Fe/H] {:?}",
+            star.mass, star.radius, star.luminosity, star.surface_temp, star.metallicity
+        )
+        .unwrap();
+    }
+
+    for body in &system.bodies {
+        writeln!(
+            out,
+            "  body {} {} around {:?} at {:?} AU habitable {} seed {} moons {:?} rings {:?}",
+            body.index,
+            body.body_type.as_str(),
+            body.host,
+            body.semi_major_axis_au,
+            body.is_in_habitable_zone,
+            body.seed,
+            body.moons
+                .iter()
+                .map(|moon| (moon.index, moon.orbit_radius_planet_radii))
+                .collect::<Vec<_>>(),
+            body.rings
+                .as_ref()
+                .map(|rings| (rings.composition, rings.richness))
+        )
+        .unwrap();
+        writeln!(out, "    {:?}", body.orbit).unwrap();
+    }
+
+    let objects = SpaceObjects::generate(system);
+    for asteroid in &objects.asteroids {
+        writeln!(
+            out,
+            "  asteroid {} belt {} {} {} at {:?} yield {:?}",
+            asteroid.index,
+            asteroid.belt_index,
+            asteroid.size.as_str(),
+            asteroid.composition.as_str(),
+            asteroid.position_au(),
+            asteroid.total_yield
+        )
+        .unwrap();
+    }
+    for anomaly in &objects.anomalies {
+        writeln!(
+            out,
+            "  anomaly {} {} at {:?}",
+            anomaly.index,
+            anomaly.kind.as_str(),
+            anomaly.position_au()
+        )
+        .unwrap();
+    }
+}
+
+fn dump_planet(out: &mut String, planet: &Planet) {
+    writeln!(
+        out,
+        "planet {:?} orbit {} moon {:?} {} seed {} subdivision {}",
+        planet.planet_pos,
+        planet.orbit_index,
+        planet.moon_index,
+        planet.class.as_str(),
+        planet.seed,
+        planet.subdivision
+    )
+    .unwrap();
+    writeln!(out, "  {:?}", planet.physics).unwrap();
+    writeln!(out, "  {:?}", planet.climate).unwrap();
+}
+
+/// Biome letter per tile in index order (uppercase when the tile holds a deposit), then the
+/// deposit counts. Every other tile property only shows through the hash.
+fn dump_tile_map(out: &mut String, planet: &Planet) {
+    writeln!(
+        out,
+        "\n## tile map of planet {:?} orbit {} moon {:?}",
+        planet.planet_pos, planet.orbit_index, planet.moon_index
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "legend {}",
+        TileType::ALL
+            .iter()
+            .map(|tile_type| format!("{}={}", tile_letter(*tile_type), tile_type.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    )
+    .unwrap();
+
+    let topology = planet.topology();
+    let tiles: Vec<_> = (0..topology.tile_count())
+        .map(|index| planet.query_tile_at(index))
+        .collect();
+
+    let properties: String = tiles.iter().map(|tile| format!("{tile:?}\n")).collect();
+    writeln!(out, "tiles hash {:016x}", xxh3_64(properties.as_bytes())).unwrap();
+
+    let letters: Vec<char> = tiles
+        .iter()
+        .map(|tile| {
+            let letter = tile_letter(tile.tile_type);
+            if tile.rare_deposit.is_some() {
+                letter.to_ascii_uppercase()
+            } else {
+                letter
+            }
+        })
+        .collect();
+    for line in letters.chunks(TILE_MAP_WIDTH) {
+        writeln!(out, "{}", line.iter().collect::<String>()).unwrap();
+    }
+
+    let mut deposits: BTreeMap<(&str, u8), usize> = BTreeMap::new();
+    for deposit in tiles.iter().filter_map(|tile| tile.rare_deposit) {
+        *deposits
+            .entry((deposit.resource.as_str(), deposit.richness.level()))
+            .or_default() += 1;
+    }
+    writeln!(out, "deposits (resource, richness): {deposits:?}").unwrap();
+}
+
+fn tile_letter(tile_type: TileType) -> char {
+    (b'a' + tile_type as u8) as char
+}
+
+#[test]
+fn golden_universe() {
+    for generation_version in MIN_GENERATION_VERSION..=CURRENT_GENERATION_VERSION {
+        check_golden(generation_version);
+    }
+}
+
+fn check_golden(generation_version: u32) {
+    let dump = dump_universe(generation_version);
+    let path = golden_path(generation_version);
+
+    if generation_version == CURRENT_GENERATION_VERSION
+        && std::env::var_os("UPDATE_GOLDEN").is_some()
+    {
+        std::fs::create_dir_all(std::path::Path::new(&path).parent().unwrap()).unwrap();
+        std::fs::write(&path, &dump).unwrap();
+        return;
+    }
+
+    let golden = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "no golden dump at {path}, create it with UPDATE_GOLDEN=1 cargo test golden_universe"
+        )
+    });
+
+    if let Some((line, (expected, actual))) = golden
+        .lines()
+        .zip(dump.lines())
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+    {
+        panic!(
+            "generation drifted from {path} at line {}:\n  expected: {expected}\n  actual:   {actual}",
+            line + 1
+        );
+    }
+    assert_eq!(
+        golden.lines().count(),
+        dump.lines().count(),
+        "generation drifted from {path}: different line count"
+    );
+}
+
+#[test]
+fn resources_are_listed_in_declaration_order() {
+    // Tile DTOs and the database rely on `resource as u8` indexing `Resource::ALL`
+    for (index, resource) in Resource::ALL.iter().enumerate() {
+        assert_eq!(*resource as usize, index);
+    }
+    for (index, tile_type) in TileType::ALL.iter().enumerate() {
+        assert_eq!(*tile_type as usize, index);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::game::proc_gen::seed::{
+    ANOMALY_SPAWN_TAG, ANOMALY_TAG, ASTEROID_SPAWN_TAG, ASTEROID_TAG, SeedTag, derive_seed,
+};
+use crate::game::proc_gen::star_system::{BodyType, OrbitHost, OrbitalBody, StarSystem, StarType};
+use crate::game::resource::Resource;
+
+// Bodies per belt. Large asteroids are few but hold most of a belt's yield.
+const MAX_LARGE_ASTEROIDS: u32 = 4;
+const MIN_SMALL_ASTEROIDS: u32 = 8;
+const MAX_SMALL_ASTEROIDS: u32 = 24;
+// Belt half-width and half-thickness, as fractions of its semi-major axis
+const BELT_HALF_WIDTH: f32 = 0.15;
+const BELT_HALF_THICKNESS: f32 = 0.03;
+
+// Anomalies drift anywhere inside this margin past the outermost orbit
+const ANOMALY_REACH: f32 = 1.2;
+const ANOMALY_REACH_EMPTY_SYSTEM_AU: f32 = 20.0;
+const MAX_ANOMALIES: u32 = 3;
+
+// Asteroid seeds are derived with [asteroid index] and anomaly seeds with [anomaly index],
+// each attribute of an object then gets its own component below
+const ROLL_SIZE: i64 = 0;
+const ROLL_COMPOSITION: i64 = 1;
+const ROLL_RADIUS: i64 = 2;
+const ROLL_PHASE: i64 = 3;
+const ROLL_HEIGHT: i64 = 4;
+const ROLL_KIND: i64 = 5;
+
+const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsteroidSize {
+    Small,
+    Large,
+}
+
+impl AsteroidSize {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AsteroidSize::Small => "small",
+            AsteroidSize::Large => "large",
+        }
+    }
+
+    /// (min radius km, max radius km, min yield, max yield). Radius and yield grow together.
+    fn ranges(&self) -> (f32, f32, f64, f64) {
+        match self {
+            AsteroidSize::Small => (0.2, 5.0, 200.0, 2_000.0),
+            AsteroidSize::Large => (20.0, 500.0, 20_000.0, 200_000.0),
+        }
+    }
+}
+
+/// A minable rock of an asteroid belt. Its yield is the untouched amount: mining only
+/// persists what was extracted (see the space_objects table).
+#[derive(Debug, Clone)]
+pub struct Asteroid {
+    pub index: u32, // Unique across the asteroids of the system
+    pub belt_index: u32,
+    pub host: OrbitHost,
+    // Position around the host at the generation epoch: orbit radius, angle, height above the
+    // orbital plane
+    pub orbit_radius_au: f32,
+    pub phase_rad: f32,
+    pub height_au: f32,
+    pub size: AsteroidSize,
+    pub radius_km: f32,
+    pub composition: Resource,
+    pub total_yield: f64,
+}
+
+impl Asteroid {
+    /// Position relative to the host, in AU.
+    pub fn position_au(&self) -> [f32; 3] {
+        polar_position(self.orbit_radius_au, self.phase_rad, self.height_au)
+    }
+
+    pub fn remaining_yield(&self, extracted: f64) -> f64 {
+        (self.total_yield - extracted).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    // Hulk of a long-dead ship, worth salvaging
+    Derelict,
+    // Unexplained transmission to investigate
+    Signal,
+    // Tear in space-time, drawn to black holes
+    Rift,
+}
+
+impl AnomalyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::Derelict => "derelict",
+            AnomalyKind::Signal => "signal",
+            AnomalyKind::Rift => "rift",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub index: u32,
+    pub kind: AnomalyKind,
+    // Position relative to the system's primary star, in AU
+    pub distance_au: f32,
+    pub phase_rad: f32,
+    pub height_au: f32,
+    pub seed: u64,
+}
+
+impl Anomaly {
+    pub fn position_au(&self) -> [f32; 3] {
+        polar_position(self.distance_au, self.phase_rad, self.height_au)
+    }
+}
+
+/// The small bodies of a system, regenerated from its seed like its planets.
+#[derive(Debug, Clone, Default)]
+pub struct SpaceObjects {
+    pub asteroids: Vec<Asteroid>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl SpaceObjects {
+    pub fn generate(system: &StarSystem) -> Self {
+        let metallicity = system.stars[0].metallicity;
+
+        let mut asteroids = Vec::new();
+        for belt in system
+            .bodies
+            .iter()
+            .filter(|body| body.body_type == BodyType::AsteroidBelt)
+        {
+            generate_belt(belt, system.generation_version, metallicity, &mut asteroids);
+        }
+
+        Self {
+            asteroids,
+            anomalies: generate_anomalies(system),
+        }
+    }
+}
+
+fn generate_belt(
+    belt: &OrbitalBody,
+    generation_version: u32,
+    metallicity: f32,
+    asteroids: &mut Vec<Asteroid>,
+) {
+    let count_roll = roll(belt.seed, generation_version, ASTEROID_SPAWN_TAG, 0);
+    let large_count = (count_roll * (MAX_LARGE_ASTEROIDS + 1) as f64) as u32;
+    let small_roll = roll(belt.seed, generation_version, ASTEROID_SPAWN_TAG, 1);
+    let small_count = MIN_SMALL_ASTEROIDS
+        + (small_roll * (MAX_SMALL_ASTEROIDS - MIN_SMALL_ASTEROIDS + 1) as f64) as u32;
+
+    for slot in 0..large_count + small_count {
+        let index = asteroids.len() as u32;
+        let seed = derive_seed(belt.seed, generation_version, ASTEROID_TAG, &[slot as i64]);
+        let size = if slot < large_count {
+            AsteroidSize::Large
+        } else {
+            AsteroidSize::Small
+        };
+
+        let (min_radius, max_radius, min_yield, max_yield) = size.ranges();
+        let size_roll = roll(seed, generation_version, ASTEROID_TAG, ROLL_SIZE);
+        let radius_roll = roll(seed, generation_version, ASTEROID_TAG, ROLL_RADIUS);
+
+        let a = belt.semi_major_axis_au;
+        asteroids.push(Asteroid {
+            index,
+            belt_index: belt.index,
+            host: belt.host,
+            orbit_radius_au: a * (1.0 + BELT_HALF_WIDTH * (2.0 * radius_roll as f32 - 1.0)),
+            phase_rad: std::f32::consts::TAU
+                * roll(seed, generation_version, ASTEROID_TAG, ROLL_PHASE) as f32,
+            height_au: a
+                * BELT_HALF_THICKNESS
+                * (2.0 * roll(seed, generation_version, ASTEROID_TAG, ROLL_HEIGHT) as f32 - 1.0),
+            size,
+            radius_km: log_lerp(min_radius as f64, max_radius as f64, size_roll) as f32,
+            composition: asteroid_composition(
+                roll(seed, generation_version, ASTEROID_TAG, ROLL_COMPOSITION),
+                metallicity,
+            ),
+            total_yield: log_lerp(min_yield, max_yield, size_roll),
+        });
+    }
+}
+
+/// Stony and carbonaceous rocks dominate; metal-rich systems form more metallic ones.
+fn asteroid_composition(composition_roll: f64, metallicity: f32) -> Resource {
+    let metal_factor = 10f32.powf(0.8 * metallicity);
+    let weights = [
+        (Resource::Silicon, 0.35),
+        (Resource::Deuterium, 0.20),
+        (Resource::Iron, 0.25 * metal_factor),
+        (Resource::Titanium, 0.10 * metal_factor),
+        (Resource::Gold, 0.04 * metal_factor),
+        (Resource::RareEarths, 0.04 * metal_factor),
+        (Resource::Uranium, 0.02 * metal_factor),
+    ];
+
+    let total: f32 = weights.iter().map(|(_, weight)| weight).sum();
+    let mut remaining = composition_roll as f32 * total;
+    for (resource, weight) in weights {
+        if remaining < weight {
+            return resource;
+        }
+        remaining -= weight;
+    }
+    Resource::Silicon
+}
+
+fn generate_anomalies(system: &StarSystem) -> Vec<Anomaly> {
+    let generation_version = system.generation_version;
+    let has_black_hole = system
+        .stars
+        .iter()
+        .any(|star| !matches!(star.star_type, StarType::Spectral(_)));
+
+    // Mostly none, sometimes one, rarely a few
+    let count_roll = roll(system.seed, generation_version, ANOMALY_SPAWN_TAG, 0);
+    let count = ((count_roll * count_roll * (MAX_ANOMALIES + 1) as f64) as u32).min(MAX_ANOMALIES);
+
+    let reach_au = system
+        .bodies
+        .iter()
+        .map(|body| body.semi_major_axis_au)
+        .reduce(f32::max)
+        .map_or(ANOMALY_REACH_EMPTY_SYSTEM_AU, |outermost| {
+            outermost * ANOMALY_REACH
+        });
+
+    (0..count)
+        .map(|index| {
+            let seed = derive_seed(
+                system.seed,
+                generation_version,
+                ANOMALY_TAG,
+                &[index as i64],
+            );
+            let kind_roll = roll(seed, generation_version, ANOMALY_TAG, ROLL_KIND);
+            let rift_chance = if has_black_hole { 0.6 } else { 0.1 };
+
+            let kind = if kind_roll < rift_chance {
+                AnomalyKind::Rift
+            } else if kind_roll < rift_chance + (1.0 - rift_chance) * 0.6 {
+                AnomalyKind::Derelict
+            } else {
+                AnomalyKind::Signal
+            };
+
+            // Uniform over the disc's area rather than its radius
+            let distance_au =
+                reach_au * (roll(seed, generation_version, ANOMALY_TAG, ROLL_RADIUS) as f32).sqrt();
+
+            Anomaly {
+                index,
+                kind,
+                distance_au,
+                phase_rad: std::f32::consts::TAU
+                    * roll(seed, generation_version, ANOMALY_TAG, ROLL_PHASE) as f32,
+                height_au: distance_au
+                    * BELT_HALF_THICKNESS
+                    * (2.0 * roll(seed, generation_version, ANOMALY_TAG, ROLL_HEIGHT) as f32 - 1.0),
+                seed,
+            }
+        })
+        .collect()
+}
+
+fn polar_position(radius: f32, phase_rad: f32, height: f32) -> [f32; 3] {
+    let (sin, cos) = phase_rad.sin_cos();
+    [radius * cos, radius * sin, height]
+}
+
+fn roll(seed: u64, generation_version: u32, tag: SeedTag, component: i64) -> f64 {
+    (derive_seed(seed, generation_version, tag, &[component]) as f64) * U64_TO_UNIT_F64
+}
+
+fn log_lerp(min: f64, max: f64, t: f64) -> f64 {
+    min * (max / min).powf(t)
+}
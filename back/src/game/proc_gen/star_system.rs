@@ -1,11 +1,15 @@
 use crate::game::proc_gen::{
-    galaxy::GalaxyType,
+    galaxy::{Galaxy, GalaxyType},
+    orbit::Orbit,
     seed::{
-        PLANET_SPAWN_TAG, STAR_SYSTEM_ATTR_TAG, STAR_SYSTEM_BODY_TYPE_TAG,
+        MOON_ORBIT_SPACING_TAG, MOON_SPAWN_TAG, MOON_TAG, PLANET_SPAWN_TAG, RING_ATTR_TAG,
+        RING_SPAWN_TAG, STAR_SYSTEM_ATTR_TAG, STAR_SYSTEM_BODY_TYPE_TAG, STAR_SYSTEM_COMPANION_TAG,
+        STAR_SYSTEM_HOST_GROUP_TAG, STAR_SYSTEM_METALLICITY_TAG, STAR_SYSTEM_MULTIPLICITY_TAG,
         STAR_SYSTEM_ORBIT_SPACING_TAG, STAR_SYSTEM_SMBH_SPAWN_TAG, STAR_SYSTEM_STAR_MASS_TAG,
         STAR_SYSTEM_TAG, derive_seed,
     },
 };
+use crate::game::resource::Resource;
 
 const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
 
@@ -27,6 +31,57 @@ pub enum SpectralType {
     M,
 }
 
+impl SpectralType {
+    /// (min mass, max mass, min surface temp, max surface temp) of the class.
+    fn ranges(&self) -> (f32, f32, u32, u32) {
+        match self {
+            SpectralType::O => (16.0, 50.0, 30000, 45000),
+            SpectralType::B => (2.1, 16.0, 10000, 30000),
+            SpectralType::A => (1.4, 2.1, 7500, 10000),
+            SpectralType::F => (1.04, 1.4, 6000, 7500),
+            SpectralType::G => (0.8, 1.04, 5200, 6000),
+            SpectralType::K => (0.45, 0.8, 3700, 5200),
+            SpectralType::M => (0.08, 0.45, 2400, 3700),
+        }
+    }
+
+    fn for_mass(mass: f32) -> Self {
+        [
+            SpectralType::M,
+            SpectralType::K,
+            SpectralType::G,
+            SpectralType::F,
+            SpectralType::A,
+            SpectralType::B,
+        ]
+        .into_iter()
+        .find(|spectral_type| mass < spectral_type.ranges().1)
+        .unwrap_or(SpectralType::O)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpectralType::O => "O",
+            SpectralType::B => "B",
+            SpectralType::A => "A",
+            SpectralType::F => "F",
+            SpectralType::G => "G",
+            SpectralType::K => "K",
+            SpectralType::M => "M",
+        }
+    }
+}
+
+impl StarType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StarType::Spectral(_) => "main_sequence",
+            StarType::StellarBlackHole => "stellar_black_hole",
+            StarType::SupermassiveBlackHole => "supermassive_black_hole",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Star {
     pub star_type: StarType,
@@ -34,6 +89,67 @@ pub struct Star {
     pub radius: f32,       // Solar Radii (R_sun)
     pub luminosity: f32,   // Relative to Sun (L_sun)
     pub surface_temp: u32, // Kelvin
+    pub metallicity: f32,  // [Fe/H] in dex: 0.0 is solar, -1.0 a tenth of the Sun's metals
+}
+
+impl Star {
+    fn main_sequence(spectral_type: SpectralType, mass: f32, metallicity: f32) -> Self {
+        let (min_m, max_m, min_temp, max_temp) = spectral_type.ranges();
+
+        let luminosity = mass.powf(3.5);
+        let radius = if mass < 1.0 {
+            mass.powf(0.8)
+        } else {
+            mass.powf(0.57)
+        };
+        let mass_factor = ((mass - min_m) / (max_m - min_m)).clamp(0.0, 1.0);
+        let surface_temp = (min_temp as f32 + (max_temp - min_temp) as f32 * mass_factor) as u32;
+
+        Star {
+            star_type: StarType::Spectral(spectral_type),
+            mass,
+            radius,
+            luminosity,
+            surface_temp,
+            metallicity,
+        }
+    }
+}
+
+/// How the stars of a system are arranged. Stars are listed primary first; in a trinary the
+/// first two form the close pair and the third orbits them from afar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StarConfiguration {
+    Single,
+    CloseBinary {
+        separation_au: f32,
+    },
+    WideBinary {
+        separation_au: f32,
+    },
+    Trinary {
+        inner_separation_au: f32,
+        outer_separation_au: f32,
+    },
+}
+
+impl StarConfiguration {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StarConfiguration::Single => "single",
+            StarConfiguration::CloseBinary { .. } => "close_binary",
+            StarConfiguration::WideBinary { .. } => "wide_binary",
+            StarConfiguration::Trinary { .. } => "trinary",
+        }
+    }
+}
+
+/// What an orbital body circles: a single star, or the barycenter of a close pair
+/// (circumbinary orbit). Values are indices into `StarSystem::stars`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitHost {
+    Star(usize),
+    Pair(usize, usize),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,45 +160,121 @@ pub enum BodyType {
     AsteroidBelt,
 }
 
+impl BodyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BodyType::Terrestrial => "terrestrial",
+            BodyType::GasGiant => "gas_giant",
+            BodyType::IceGiant => "ice_giant",
+            BodyType::AsteroidBelt => "asteroid_belt",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OrbitalBody {
-    pub index: u32,
+    pub index: u32, // Unique across all hosts of the system
+    pub host: OrbitHost,
     pub body_type: BodyType,
     pub semi_major_axis_au: f32,
+    pub orbit: Orbit,
     pub is_in_habitable_zone: bool,
     pub seed: u64,
+    pub moons: Vec<Moon>,
+    pub rings: Option<RingSystem>,
+}
+
+/// A natural satellite. Its seed is used directly as the seed of the `Planet` built from it
+/// (see `Planet::new_moon`).
+#[derive(Debug, Clone)]
+pub struct Moon {
+    pub index: u32,
+    pub orbit_radius_planet_radii: f32, // Distance from the parent's center
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RingComposition {
+    Ice,
+    Rock,
+}
+
+impl RingComposition {
+    /// Resource harvested by mining the ring.
+    pub fn resource(&self) -> Resource {
+        match self {
+            RingComposition::Ice => Resource::Deuterium,
+            RingComposition::Rock => Resource::Iron,
+        }
+    }
+}
+
+/// Planetary ring, mined like an asteroid field.
+#[derive(Debug, Clone)]
+pub struct RingSystem {
+    pub inner_radius_planet_radii: f32,
+    pub outer_radius_planet_radii: f32,
+    pub composition: RingComposition,
+    pub richness: f32, // 0.0 (sparse dust) to 1.0 (dense, Saturn-like)
+    pub seed: u64,
 }
 
 #[derive(Debug, Clone)]
 pub struct StarSystem {
     pub seed: u64,
+    pub generation_version: u32,
     pub position: (i32, i32, i32),
-    pub star: Star,
+    pub stars: Vec<Star>,
+    pub configuration: StarConfiguration,
     pub bodies: Vec<OrbitalBody>,
 }
 
 impl StarSystem {
-    pub fn new(galaxy_seed: u64, galaxy_type: GalaxyType, position: (i32, i32, i32)) -> Self {
+    pub fn new(galaxy: &Galaxy, position: (i32, i32, i32)) -> Self {
+        let generation_version = galaxy.generation_version;
         let system_seed = derive_seed(
-            galaxy_seed,
+            galaxy.seed,
+            generation_version,
             STAR_SYSTEM_TAG,
             &[position.0 as i64, position.1 as i64, position.2 as i64],
         );
 
-        let star = Self::generate_star(system_seed, galaxy_type, position);
-        let bodies = Self::generate_orbital_bodies(system_seed, &star);
+        let primary = Self::generate_star(system_seed, galaxy, position);
+        let (stars, configuration) =
+            Self::generate_companions(system_seed, generation_version, primary);
+        let bodies =
+            Self::generate_orbital_bodies(system_seed, generation_version, &stars, configuration);
 
         Self {
             seed: system_seed,
+            generation_version,
             position,
-            star,
+            stars,
+            configuration,
             bodies,
         }
     }
 
-    fn generate_star(system_seed: u64, galaxy_type: GalaxyType, position: (i32, i32, i32)) -> Star {
+    fn generate_star(system_seed: u64, galaxy: &Galaxy, position: (i32, i32, i32)) -> Star {
+        let generation_version = galaxy.generation_version;
+        let galaxy_type = galaxy.galaxy_type;
         let is_center = position == (0, 0, 0);
 
+        // Birth metallicity: the galaxy's radial trend plus a triangular ±0.3 dex spread
+        let scatter = (0..2)
+            .map(|component| {
+                (derive_seed(
+                    system_seed,
+                    generation_version,
+                    STAR_SYSTEM_METALLICITY_TAG,
+                    &[component],
+                ) as f64)
+                    * U64_TO_UNIT_F64
+            })
+            .sum::<f64>()
+            - 1.0;
+        let metallicity = galaxy.mean_metallicity(position) + 0.3 * scatter as f32;
+
         // 1. Central Supermassive Black Hole Logic
         if is_center {
             let has_smbh = match galaxy_type {
@@ -90,8 +282,12 @@ impl StarSystem {
                 GalaxyType::Elliptical => true,
                 // Irregular galaxies only have a 20% chance of a central SMBH
                 GalaxyType::Irregular => {
-                    let center_roll = (derive_seed(system_seed, STAR_SYSTEM_SMBH_SPAWN_TAG, &[])
-                        as f64)
+                    let center_roll = (derive_seed(
+                        system_seed,
+                        generation_version,
+                        STAR_SYSTEM_SMBH_SPAWN_TAG,
+                        &[],
+                    ) as f64)
                         * U64_TO_UNIT_F64;
                     center_roll < 0.20
                 }
@@ -109,11 +305,13 @@ impl StarSystem {
                     radius: 0.05, // Event horizon footprint scale
                     luminosity: 0.0,
                     surface_temp: 0,
+                    metallicity,
                 };
             }
+        }
 
         // 2. Galaxy-Specific Star & Stellar Black Hole Distributions
-        let star_seed = derive_seed(system_seed, STAR_SYSTEM_ATTR_TAG, &[]);
+        let star_seed = derive_seed(system_seed, generation_version, STAR_SYSTEM_ATTR_TAG, &[]);
         let roll = (star_seed as f64) * U64_TO_UNIT_F64;
 
         let derived_type = match galaxy_type {
@@ -178,7 +376,12 @@ impl StarSystem {
         // 3. Attribute Resolution
         match derived_type {
             StarType::StellarBlackHole => {
-                let mass_seed = derive_seed(star_seed, STAR_SYSTEM_STAR_MASS_TAG, &[]);
+                let mass_seed = derive_seed(
+                    star_seed,
+                    generation_version,
+                    STAR_SYSTEM_STAR_MASS_TAG,
+                    &[],
+                );
                 let mass_roll = (mass_seed as f64) * U64_TO_UNIT_F64;
                 let mass = (10.0 + mass_roll * 40.0) as f32; // 10 to 50 Solar Masses
 
@@ -188,50 +391,215 @@ impl StarSystem {
                     radius: 0.0001,
                     luminosity: 0.0,
                     surface_temp: 0,
+                    metallicity,
                 }
             }
 
             StarType::Spectral(spectral_type) => {
-                let (min_m, max_m, min_temp, max_temp) = match spectral_type {
-                    SpectralType::O => (16.0, 50.0, 30000, 45000),
-                    SpectralType::B => (2.1, 16.0, 10000, 30000),
-                    SpectralType::A => (1.4, 2.1, 7500, 10000),
-                    SpectralType::F => (1.04, 1.4, 6000, 7500),
-                    SpectralType::G => (0.8, 1.04, 5200, 6000),
-                    SpectralType::K => (0.45, 0.8, 3700, 5200),
-                    SpectralType::M => (0.08, 0.45, 2400, 3700),
-                };
-
-                let mass_seed = derive_seed(star_seed, STAR_SYSTEM_STAR_MASS_TAG, &[]);
+                let (min_m, max_m, _, _) = spectral_type.ranges();
+
+                let mass_seed = derive_seed(
+                    star_seed,
+                    generation_version,
+                    STAR_SYSTEM_STAR_MASS_TAG,
+                    &[],
+                );
                 let mass_roll = (mass_seed as f64) * U64_TO_UNIT_F64;
                 let mass = min_m + (max_m - min_m) * (mass_roll as f32);
 
-                let luminosity = mass.powf(3.5);
-                let radius = if mass < 1.0 {
-                    mass.powf(0.8)
-                } else {
-                    mass.powf(0.57)
-                };
-                let mass_factor = (mass - min_m) / (max_m - min_m);
-                let surface_temp =
-                    (min_temp as f32 + (max_temp - min_temp) as f32 * mass_factor) as u32;
-
-                Star {
-                    star_type: StarType::Spectral(spectral_type),
-                    mass,
-                    radius,
-                    luminosity,
-                    surface_temp,
-                }
+                Star::main_sequence(spectral_type, mass, metallicity)
             }
 
             _ => unreachable!(),
         }
     }
 
-    fn generate_orbital_bodies(system_seed: u64, star: &Star) -> Vec<OrbitalBody> {
+    fn generate_companions(
+        system_seed: u64,
+        generation_version: u32,
+        primary: Star,
+    ) -> (Vec<Star>, StarConfiguration) {
+        // Only main sequence primaries get companions; black holes stay alone
+        let StarType::Spectral(primary_type) = primary.star_type else {
+            return (vec![primary], StarConfiguration::Single);
+        };
+
+        // Massive stars are almost always in multiples, red dwarfs mostly single
+        let multiplicity = match primary_type {
+            SpectralType::O => 0.70,
+            SpectralType::B => 0.60,
+            SpectralType::A => 0.50,
+            SpectralType::F | SpectralType::G => 0.45,
+            SpectralType::K => 0.35,
+            SpectralType::M => 0.25,
+        };
+
+        let roll = |component: i64| {
+            (derive_seed(
+                system_seed,
+                generation_version,
+                STAR_SYSTEM_MULTIPLICITY_TAG,
+                &[component],
+            ) as f64)
+                * U64_TO_UNIT_F64
+        };
+
+        if roll(0) >= multiplicity {
+            return (vec![primary], StarConfiguration::Single);
+        }
+
+        // Companion masses are a fraction of the primary's; the metallicity is shared
+        let primary_mass = primary.mass;
+        let metallicity = primary.metallicity;
+        let companion = |index: i64| {
+            let ratio_roll = (derive_seed(
+                system_seed,
+                generation_version,
+                STAR_SYSTEM_COMPANION_TAG,
+                &[index],
+            ) as f64)
+                * U64_TO_UNIT_F64;
+            let mass = (primary_mass * (0.1 + 0.9 * ratio_roll as f32)).max(0.08);
+            Star::main_sequence(SpectralType::for_mass(mass), mass, metallicity)
+        };
+
+        let close_separation_au = log_lerp(0.05, 0.5, roll(2));
+        let wide_separation_au = log_lerp(50.0, 1000.0, roll(3));
+
+        let arrangement_roll = roll(1);
+        if arrangement_roll < 0.2 {
+            // Hierarchical triple: a close pair with a distant third star (Alpha Centauri)
+            (
+                vec![primary, companion(1), companion(2)],
+                StarConfiguration::Trinary {
+                    inner_separation_au: close_separation_au,
+                    outer_separation_au: wide_separation_au * 2.0,
+                },
+            )
+        } else if arrangement_roll < 0.5 {
+            (
+                vec![primary, companion(1)],
+                StarConfiguration::CloseBinary {
+                    separation_au: close_separation_au,
+                },
+            )
+        } else {
+            (
+                vec![primary, companion(1)],
+                StarConfiguration::WideBinary {
+                    separation_au: wide_separation_au,
+                },
+            )
+        }
+    }
+
+    /// Planet-hosting groups of the configuration, with the orbit range each one keeps stable.
+    /// Close pairs host circumbinary planets beyond ~3x their separation; in wide systems each
+    /// host is truncated at a third of the distance to the other.
+    fn orbit_hosts(configuration: StarConfiguration) -> Vec<(OrbitHost, f32, f32)> {
+        match configuration {
+            StarConfiguration::Single => vec![(OrbitHost::Star(0), 0.0, f32::INFINITY)],
+            StarConfiguration::CloseBinary { separation_au } => {
+                vec![(OrbitHost::Pair(0, 1), 3.0 * separation_au, f32::INFINITY)]
+            }
+            StarConfiguration::WideBinary { separation_au } => vec![
+                (OrbitHost::Star(0), 0.0, separation_au / 3.0),
+                (OrbitHost::Star(1), 0.0, separation_au / 3.0),
+            ],
+            StarConfiguration::Trinary {
+                inner_separation_au,
+                outer_separation_au,
+            } => vec![
+                (
+                    OrbitHost::Pair(0, 1),
+                    3.0 * inner_separation_au,
+                    outer_separation_au / 3.0,
+                ),
+                (OrbitHost::Star(2), 0.0, outer_separation_au / 3.0),
+            ],
+        }
+    }
+
+    /// Where `host` sits in the system, in AU from the primary star (or from the close pair's
+    /// barycenter). Distant companions stay parked at their separation along x: their own
+    /// orbits take millennia.
+    pub fn host_position_au(&self, host: OrbitHost) -> [f32; 3] {
+        match (self.configuration, host) {
+            (StarConfiguration::WideBinary { separation_au }, OrbitHost::Star(1)) => {
+                [separation_au, 0.0, 0.0]
+            }
+            (
+                StarConfiguration::Trinary {
+                    outer_separation_au,
+                    ..
+                },
+                OrbitHost::Star(2),
+            ) => [outer_separation_au, 0.0, 0.0],
+            _ => [0.0; 3],
+        }
+    }
+
+    /// Position of `body` in the system at game time `time_s`, in AU (see `host_position_au`).
+    pub fn body_position_au(&self, body: &OrbitalBody, time_s: f64) -> [f32; 3] {
+        let host = self.host_position_au(body.host);
+        let relative = body.orbit.position_au(time_s);
+        [0, 1, 2].map(|axis| host[axis] + relative[axis])
+    }
+
+    /// Effective star seen by bodies orbiting `host`: a close pair shines with its combined
+    /// luminosity and pulls with its combined mass.
+    pub fn host_star(&self, host: OrbitHost) -> Star {
+        host_star_of(&self.stars, host)
+    }
+
+    fn generate_orbital_bodies(
+        system_seed: u64,
+        generation_version: u32,
+        stars: &[Star],
+        configuration: StarConfiguration,
+    ) -> Vec<OrbitalBody> {
         let mut bodies = Vec::new();
 
+        for (group, (host, inner_limit_au, outer_limit_au)) in
+            Self::orbit_hosts(configuration).into_iter().enumerate()
+        {
+            // The first group keeps the system seed so single-star systems are unaffected
+            let group_seed = if group == 0 {
+                system_seed
+            } else {
+                derive_seed(
+                    system_seed,
+                    generation_version,
+                    STAR_SYSTEM_HOST_GROUP_TAG,
+                    &[group as i64],
+                )
+            };
+
+            Self::generate_host_bodies(
+                group_seed,
+                generation_version,
+                &host_star_of(stars, host),
+                host,
+                inner_limit_au,
+                outer_limit_au,
+                &mut bodies,
+            );
+        }
+
+        bodies
+    }
+
+    fn generate_host_bodies(
+        system_seed: u64,
+        generation_version: u32,
+        star: &Star,
+        host: OrbitHost,
+        inner_limit_au: f32,
+        outer_limit_au: f32,
+        bodies: &mut Vec<OrbitalBody>,
+    ) {
+        let first_index = bodies.len() as u32;
+
         // Non-luminous systems (Black Holes) produce no habitable zones
         let hz_center = star.luminosity.sqrt();
         let hz_inner = hz_center * 0.75;
@@ -244,24 +612,44 @@ impl StarSystem {
             2.0 * star.mass.sqrt()
         };
 
-        let body_count_seed = derive_seed(system_seed, PLANET_SPAWN_TAG, &[0]);
+        let body_count_seed = derive_seed(system_seed, generation_version, PLANET_SPAWN_TAG, &[0]);
         let body_count_roll = (body_count_seed as f64) * U64_TO_UNIT_F64;
         let max_bodies = 3 + (body_count_roll * 10.0) as u32;
 
-        let mut current_au = 0.15 * star.mass.min(10.0); // Cap inner distance multiplier for extreme SMBH mass
+        let mut current_au = (0.15 * star.mass.min(10.0)).max(inner_limit_au); // Cap inner distance multiplier for extreme SMBH mass
 
         for idx in 0..max_bodies {
-            let slot_seed = derive_seed(system_seed, PLANET_SPAWN_TAG, &[idx as i64 + 1]);
-
-            let spacing_seed = derive_seed(slot_seed, STAR_SYSTEM_ORBIT_SPACING_TAG, &[]);
+            let slot_seed = derive_seed(
+                system_seed,
+                generation_version,
+                PLANET_SPAWN_TAG,
+                &[idx as i64 + 1],
+            );
+
+            let spacing_seed = derive_seed(
+                slot_seed,
+                generation_version,
+                STAR_SYSTEM_ORBIT_SPACING_TAG,
+                &[],
+            );
             let spacing_roll = (spacing_seed as f64) * U64_TO_UNIT_F64;
 
-            let body_type_seed = derive_seed(slot_seed, STAR_SYSTEM_BODY_TYPE_TAG, &[]);
+            let body_type_seed = derive_seed(
+                slot_seed,
+                generation_version,
+                STAR_SYSTEM_BODY_TYPE_TAG,
+                &[],
+            );
             let type_roll = (body_type_seed as f64) * U64_TO_UNIT_F64;
 
             let spacing_factor = 1.3 + (spacing_roll as f32 * 0.5);
             current_au *= spacing_factor;
 
+            // Orbits past the limit are torn apart by the other stars
+            if current_au > outer_limit_au {
+                break;
+            }
+
             let body_type = if type_roll < 0.12 && current_au > 1.0 && current_au < frost_line {
                 BodyType::AsteroidBelt
             } else if current_au < frost_line {
@@ -279,15 +667,131 @@ impl StarSystem {
             let is_in_habitable_zone =
                 star.luminosity > 0.0 && current_au >= hz_inner && current_au <= hz_outer;
 
+            let is_beyond_frost_line = current_au >= frost_line;
+
             bodies.push(OrbitalBody {
-                index: idx,
+                index: first_index + idx,
+                host,
                 body_type,
                 semi_major_axis_au: current_au,
+                orbit: Orbit::new(slot_seed, generation_version, current_au, star.mass),
                 is_in_habitable_zone,
                 seed: slot_seed,
+                moons: Self::generate_moons(slot_seed, generation_version, body_type),
+                rings: Self::generate_rings(
+                    slot_seed,
+                    generation_version,
+                    body_type,
+                    is_beyond_frost_line,
+                ),
             });
         }
+    }
 
-        bodies
+    fn generate_moons(body_seed: u64, generation_version: u32, body_type: BodyType) -> Vec<Moon> {
+        let max_moons = match body_type {
+            BodyType::GasGiant => 6,
+            BodyType::IceGiant => 4,
+            BodyType::Terrestrial => 2,
+            BodyType::AsteroidBelt => return Vec::new(),
+        };
+
+        // Skewed towards few moons: most rocky planets have none, most giants a handful
+        let count_roll = (derive_seed(body_seed, generation_version, MOON_SPAWN_TAG, &[]) as f64)
+            * U64_TO_UNIT_F64;
+        let moon_count = match body_type {
+            BodyType::Terrestrial => (count_roll * count_roll * 3.0) as u32,
+            _ => 1 + (count_roll * max_moons as f64) as u32,
+        }
+        .min(max_moons);
+
+        // First moon just outside the Roche limit and any ring, each next one further out
+        let mut orbit_radius = 3.0;
+
+        (0..moon_count)
+            .map(|moon_idx| {
+                let spacing_roll = (derive_seed(
+                    body_seed,
+                    generation_version,
+                    MOON_ORBIT_SPACING_TAG,
+                    &[moon_idx as i64],
+                ) as f64)
+                    * U64_TO_UNIT_F64;
+                orbit_radius *= 1.4 + spacing_roll as f32;
+
+                Moon {
+                    index: moon_idx,
+                    orbit_radius_planet_radii: orbit_radius,
+                    seed: derive_seed(body_seed, generation_version, MOON_TAG, &[moon_idx as i64]),
+                }
+            })
+            .collect()
     }
+
+    fn generate_rings(
+        body_seed: u64,
+        generation_version: u32,
+        body_type: BodyType,
+        is_beyond_frost_line: bool,
+    ) -> Option<RingSystem> {
+        let ring_chance = match body_type {
+            BodyType::GasGiant => 0.40,
+            BodyType::IceGiant => 0.30,
+            BodyType::Terrestrial => 0.02,
+            BodyType::AsteroidBelt => return None,
+        };
+
+        let spawn_roll = (derive_seed(body_seed, generation_version, RING_SPAWN_TAG, &[]) as f64)
+            * U64_TO_UNIT_F64;
+        if spawn_roll >= ring_chance {
+            return None;
+        }
+
+        let ring_seed = derive_seed(body_seed, generation_version, RING_ATTR_TAG, &[]);
+        let width_roll = (derive_seed(ring_seed, generation_version, RING_ATTR_TAG, &[0]) as f64)
+            * U64_TO_UNIT_F64;
+        let richness_roll = (derive_seed(ring_seed, generation_version, RING_ATTR_TAG, &[1])
+            as f64)
+            * U64_TO_UNIT_F64;
+
+        // Ice survives only beyond the frost line; inner rings are rubble
+        let composition = if is_beyond_frost_line {
+            RingComposition::Ice
+        } else {
+            RingComposition::Rock
+        };
+
+        let inner_radius_planet_radii = 1.2 + 0.3 * width_roll as f32;
+
+        Some(RingSystem {
+            inner_radius_planet_radii,
+            outer_radius_planet_radii: inner_radius_planet_radii + 0.3 + 0.9 * width_roll as f32,
+            composition,
+            richness: richness_roll as f32,
+            seed: ring_seed,
+        })
+    }
+}
+
+fn host_star_of(stars: &[Star], host: OrbitHost) -> Star {
+    match host {
+        OrbitHost::Star(index) => stars[index].clone(),
+        OrbitHost::Pair(a, b) => {
+            let (a, b) = (&stars[a], &stars[b]);
+            let brighter = if a.luminosity >= b.luminosity { a } else { b };
+
+            Star {
+                star_type: brighter.star_type,
+                mass: a.mass + b.mass,
+                radius: brighter.radius,
+                luminosity: a.luminosity + b.luminosity,
+                surface_temp: brighter.surface_temp,
+                metallicity: brighter.metallicity,
+            }
+        }
+    }
+}
+
+fn log_lerp(min: f32, max: f32, t: f64) -> f32 {
+    min * (max / min).powf(t as f32)
 }
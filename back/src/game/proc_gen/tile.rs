@@ -1,8 +1,23 @@
-use crate::game::proc_gen::seed::{TILE_TAG, derive_seed};
+use crate::game::proc_gen::climate::{
+    MOUNTAIN_LEVEL, PlanetClimate, WATER_BOILING_K, WATER_FREEZING_K, upwind_direction,
+};
+use crate::game::proc_gen::deposit::{PlanetGeology, deposit_at};
+use crate::game::proc_gen::goldberg::GoldbergTopology;
+use crate::game::proc_gen::noise::{Fbm, NoiseField};
+use crate::game::proc_gen::seed::{
+    TILE_ELEVATION_NOISE_TAG, TILE_MOISTURE_NOISE_TAG, TILE_NOISE_OFFSET_TAG, TILE_TAG, derive_seed,
+};
+use crate::game::resource::Deposit;
 
-// Tags for distinct feature derivations
-const TILE_NOISE_OFFSET_TAG: u64 = 0x5449_4C45_4E4F; // "TILENO"
-const TILE_DEPOSIT_TAG: u64 = 0x4445_504F_5349_5400; // "DEPOSIT"
+// Frequencies are in unit-sphere space: 1.5 gives a handful of continents per planet
+const CONTINENT_FBM: Fbm = Fbm::new(5, 1.5);
+const CONTINENT_WARP_STRENGTH: f64 = 0.4;
+const MOUNTAIN_RIDGE_FBM: Fbm = Fbm::new(4, 3.0);
+const MOISTURE_FBM: Fbm = Fbm::new(4, 2.0);
+
+// Longitude offsets (radians at the equator) of the upwind samples used for sea breezes and
+// rain shadows: roughly 3 and 6 tiles away on a 10k-tile planet
+const UPWIND_STEPS: [f64; 2] = [0.06, 0.12];
 
 const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
 
@@ -16,9 +31,29 @@ pub enum TileType {
     Lava,
     Water,
     Ocean,
+    Tundra,
+    Jungle,
+    Swamp,
+    IceSheet,
 }
 
 impl TileType {
+    /// Every tile type, in declaration order: `tile_type as u8` indexes this array.
+    pub const ALL: [TileType; 12] = [
+        Self::Plains,
+        Self::Forest,
+        Self::Mountain,
+        Self::Desert,
+        Self::Snow,
+        Self::Lava,
+        Self::Water,
+        Self::Ocean,
+        Self::Tundra,
+        Self::Jungle,
+        Self::Swamp,
+        Self::IceSheet,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Plains => "plains",
@@ -29,6 +64,10 @@ impl TileType {
             Self::Lava => "lava",
             Self::Water => "water",
             Self::Ocean => "ocean",
+            Self::Tundra => "tundra",
+            Self::Jungle => "jungle",
+            Self::Swamp => "swamp",
+            Self::IceSheet => "ice_sheet",
         }
     }
 }
@@ -40,102 +79,93 @@ pub struct DynamicTileProperties {
     pub v: u32,
     pub tile_type: TileType,
     pub elevation: f32,     // 0.0 to 1.0
+    pub moisture: f32,      // 0.0 (arid) to 1.0 (wet)
+    pub temperature_k: f32, // Annual mean surface temperature
     pub yield_quality: f32, // 0.0 to 1.0 multiplier
-    pub rare_deposit: Option<&'static str>,
-}
-
-/// Converts Goldberg (face, u, v) to a 3D point on a unit sphere to prevent edge seam artifacts.
-fn goldberg_to_unit_sphere(face: u8, u: u32, v: u32, subdivision: u32) -> (f32, f32, f32) {
-    let side_len = subdivision as f32;
-    let fu = (u as f32) / side_len - 0.5;
-    let fv = (v as f32) / side_len - 0.5;
-
-    // Project faces onto cube / icosahedral axes
-    let (x, y, z) = match face % 6 {
-        0 => (1.0, fu, fv),
-        1 => (-1.0, fu, fv),
-        2 => (fu, 1.0, fv),
-        3 => (fu, -1.0, fv),
-        4 => (fu, fv, 1.0),
-        _ => (fu, fv, -1.0),
-    };
-
-    let len = (x * x + y * y + z * z).sqrt();
-    (x / len, y / len, z / len)
+    pub rare_deposit: Option<Deposit>,
 }
 
 /// Deterministic calculation of tile state based on planet parameters.
 pub fn calculate_tile_properties(
     planet_seed: u64,
-    is_in_habitable_zone: bool,
-    distance_from_star_au: f32,
-    subdivision: u32,
-    face: u8,
-    u: u32,
-    v: u32,
+    generation_version: u32,
+    climate: &PlanetClimate,
+    geology: &PlanetGeology,
+    topology: &GoldbergTopology,
+    tile_index: u32,
 ) -> DynamicTileProperties {
-    let tile_seed = derive_seed(planet_seed, TILE_TAG, &[face as i64, u as i64, v as i64]);
-    let (sx, sy, sz) = goldberg_to_unit_sphere(face, u, v, subdivision);
+    let (face, u, v) = topology.tile_coords(tile_index);
+    let tile_seed = derive_seed(
+        planet_seed,
+        generation_version,
+        TILE_TAG,
+        &[face as i64, u as i64, v as i64],
+    );
+    let [sx, sy, sz] = topology.position(tile_index);
+    let position = [sx as f64, sy as f64, sz as f64];
 
-    // 1. Calculate Elevation via multi-frequency pseudo-noise
-    let elevation_raw = (sx * 3.0).sin() * (sy * 3.0).cos() + (sz * 3.0).sin();
-    let elevation = ((elevation_raw + 2.0) / 4.0).clamp(0.0, 1.0);
+    // 1. Elevation
+    let elevation_field = NoiseField::new(derive_seed(
+        planet_seed,
+        generation_version,
+        TILE_ELEVATION_NOISE_TAG,
+        &[],
+    ));
+    let elevation = elevation_at(&elevation_field, position);
 
-    // 2. Latitude-based temperature calculation (sz represents polar axis offset)
-    let latitude = sz.abs(); // 0.0 at equator, 1.0 at poles
-    let base_temp = 1.0 - latitude; // 1.0 hot, 0.0 cold
+    // 2. Temperature from the planet climate (sz represents polar axis offset)
+    let temperature_k = climate.surface_temperature_k(sz, elevation);
 
-    // 3. Determine Biome (TileType)
-    let tile_type = if distance_from_star_au < 0.4 {
-        if elevation > 0.75 {
-            TileType::Lava
-        } else {
-            TileType::Desert
-        }
-    } else if distance_from_star_au > 3.0 || base_temp < 0.2 {
-        if elevation < 0.3 {
-            TileType::Ocean
-        } else {
-            TileType::Snow
-        }
-    } else if is_in_habitable_zone {
-        if elevation < 0.35 {
-            TileType::Ocean
-        } else if elevation < 0.45 {
-            TileType::Water
-        } else if elevation > 0.80 {
-            TileType::Mountain
-        } else if base_temp > 0.6 {
-            TileType::Plains
-        } else {
-            TileType::Forest
-        }
-    } else {
-        if elevation > 0.7 {
-            TileType::Mountain
-        } else {
-            TileType::Desert
+    // 3. Moisture: noise base, latitude rain belts, then sea breezes and rain shadows
+    //    from the terrain upwind
+    let moisture_field = NoiseField::new(derive_seed(
+        planet_seed,
+        generation_version,
+        TILE_MOISTURE_NOISE_TAG,
+        &[],
+    ));
+    let base_moisture =
+        0.5 + 0.8 * moisture_field.fbm(position[0], position[1], position[2], MOISTURE_FBM);
+
+    // Wet equator, dry subtropics around 30°, wet again around 60°, dry poles
+    let latitude = (sz as f64).clamp(-1.0, 1.0).asin();
+    let rain_belts = 0.15 * (6.0 * latitude).cos();
+
+    let mut moisture = 0.2 + 0.6 * base_moisture.clamp(0.0, 1.0) + rain_belts;
+
+    let longitude_scale = 1.0 / latitude.cos().max(0.2);
+    for step in UPWIND_STEPS {
+        let angle = upwind_direction(sz) as f64 * step * longitude_scale;
+        let upwind_elevation = elevation_at(&elevation_field, rotate_about_pole(position, angle));
+
+        if upwind_elevation < climate.sea_level {
+            moisture += 0.1; // Onshore wind carries sea moisture
+        } else if upwind_elevation > MOUNTAIN_LEVEL && upwind_elevation > elevation {
+            moisture -= 0.25; // Rain shadow behind a range
         }
-    };
+    }
 
-    // 4. Determine Yield Quality (0.0 .. 1.0)
-    let yield_roll = (derive_seed(tile_seed, TILE_NOISE_OFFSET_TAG, &[]) as f64) * U64_TO_UNIT_F64;
+    let moisture = moisture.clamp(0.0, 1.0) as f32;
+
+    // 4. Determine Biome (TileType)
+    let tile_type = classify_biome(climate, elevation, temperature_k, moisture);
+
+    // 5. Determine Yield Quality (0.0 .. 1.0)
+    let yield_roll = (derive_seed(tile_seed, generation_version, TILE_NOISE_OFFSET_TAG, &[])
+        as f64)
+        * U64_TO_UNIT_F64;
     let yield_quality = (0.3 + (yield_roll as f32 * 0.7)).clamp(0.0, 1.0);
 
-    // 5. Determine Rare Deposit Spawn
-    let deposit_roll = (derive_seed(tile_seed, TILE_DEPOSIT_TAG, &[]) as f64) * U64_TO_UNIT_F64;
-    let rare_deposit = if deposit_roll < 0.05 {
-        match (deposit_roll * 100.0) as u32 % 6 {
-            0 => Some("iron"),
-            1 => Some("titanium"),
-            2 => Some("uranium"),
-            3 => Some("deuterium"),
-            4 => Some("rare_earths"),
-            _ => Some("dark_matter"),
-        }
-    } else {
-        None
-    };
+    // 6. Rare deposits: clustered veins shaped by biome, relief, planet class and metallicity
+    let rare_deposit = deposit_at(
+        planet_seed,
+        generation_version,
+        geology,
+        tile_seed,
+        position,
+        tile_type,
+        elevation,
+    );
 
     DynamicTileProperties {
         face,
@@ -143,37 +173,113 @@ pub fn calculate_tile_properties(
         v,
         tile_type,
         elevation,
+        moisture,
+        temperature_k,
         yield_quality,
         rare_deposit,
     }
 }
 
-/// Returns coordinates of adjacent tiles on a hexagonal grid plane face.
-pub fn get_hex_neighbors(face: u8, u: u32, v: u32, max_subdivision: u32) -> Vec<(u8, u32, u32)> {
-    let mut neighbors = Vec::with_capacity(6);
-    let du = u as i32;
-    let dv = v as i32;
+/// Domain-warped continents, with ridged mountain ranges raised on land only.
+fn elevation_at(field: &NoiseField, position: [f64; 3]) -> f32 {
+    let [px, py, pz] = position;
+    let continents = field.warped(px, py, pz, CONTINENT_FBM, CONTINENT_WARP_STRENGTH);
+    let ridges = field.ridged(px, py, pz, MOUNTAIN_RIDGE_FBM);
+    let elevation_raw = 0.5 + 0.9 * continents + 0.35 * ridges * continents.max(0.0);
+    elevation_raw.clamp(0.0, 1.0) as f32
+}
+
+fn rotate_about_pole(position: [f64; 3], angle: f64) -> [f64; 3] {
+    let (sin, cos) = angle.sin_cos();
+    [
+        position[0] * cos - position[1] * sin,
+        position[0] * sin + position[1] * cos,
+        position[2],
+    ]
+}
+
+fn classify_biome(
+    climate: &PlanetClimate,
+    elevation: f32,
+    temperature_k: f32,
+    moisture: f32,
+) -> TileType {
+    // Molten or boiled-dry surfaces
+    if temperature_k > 800.0 || (temperature_k > 500.0 && elevation > 0.7) {
+        return TileType::Lava;
+    }
+    if temperature_k > WATER_BOILING_K {
+        return TileType::Desert;
+    }
+
+    // Seas, frozen over on cold planets and at the poles
+    if elevation < climate.sea_level {
+        return if temperature_k < WATER_FREEZING_K - 10.0 {
+            TileType::IceSheet
+        } else if elevation < climate.deep_ocean_level {
+            TileType::Ocean
+        } else {
+            TileType::Water
+        };
+    }
+
+    if elevation > MOUNTAIN_LEVEL {
+        return if temperature_k < 255.0 {
+            TileType::Snow
+        } else {
+            TileType::Mountain
+        };
+    }
 
-    // Hex axial directions
-    let offsets = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+    // Cold land bands
+    if temperature_k < 235.0 {
+        return TileType::IceSheet;
+    }
+    if temperature_k < 258.0 {
+        return TileType::Snow;
+    }
+    if temperature_k < WATER_FREEZING_K {
+        return TileType::Tundra;
+    }
 
-    for (ox, oy) in offsets {
-        let nu = du + ox;
-        let nv = dv + oy;
+    // Vegetation needs both water and usable starlight
+    let lowland = elevation < climate.sea_level + 0.05;
+    let growth = moisture * climate.vegetation_factor;
 
-        // In-bounds on current face grid
-        if nu >= 0 && nu < max_subdivision as i32 && nv >= 0 && nv < max_subdivision as i32 {
-            neighbors.push((face, nu as u32, nv as u32));
+    if moisture < 0.25 {
+        TileType::Desert
+    } else if temperature_k > 297.0 {
+        if growth > 0.75 && lowland {
+            TileType::Swamp
+        } else if growth > 0.6 {
+            TileType::Jungle
+        } else if growth > 0.3 {
+            TileType::Plains
         } else {
-            // Edge-wrapping transitions across polyhedral faces (simplification boundary logic)
-            let next_face = (face + 1) % 6;
-            let wrapped_u = (nu.rem_euclid(max_subdivision as i32)) as u32;
-            let wrapped_v = (nv.rem_euclid(max_subdivision as i32)) as u32;
-            neighbors.push((next_face, wrapped_u, wrapped_v));
+            TileType::Desert
         }
+    } else if growth > 0.8 && lowland {
+        TileType::Swamp
+    } else if growth > 0.5 {
+        TileType::Forest
+    } else {
+        TileType::Plains
     }
+}
+
+/// Returns the canonical coordinates of the tiles adjacent to (face, u, v) on the Goldberg sphere.
+/// Five entries for the 12 pentagons, six otherwise, none if the coordinates are off the lattice.
+pub fn get_hex_neighbors(face: u8, u: u32, v: u32, subdivision: u32) -> Vec<(u8, u32, u32)> {
+    let topology = GoldbergTopology::for_subdivision(subdivision);
 
-    neighbors
+    match topology.tile_index(face, u, v) {
+        Some(index) => topology
+            .neighbors(index)
+            .iter()
+            .map(|&neighbor| topology.tile_coords(neighbor))
+            .collect(),
+        None => Vec::new(),
+    }
 }
 
 // use serde::{Deserialize, Serialize};
@@ -1,13 +1,21 @@
-use crate::game::proc_gen::seed::{GALAXY_SPAWN_TAG, derive_seed};
+use crate::game::proc_gen::noise::{Fbm, NoiseField};
+use crate::game::proc_gen::seed::{
+    GALAXY_SPAWN_TAG, RootSeed, UNIVERSE_DENSITY_NOISE_TAG, derive_seed,
+};
 
 // Pre-computed constant for maximum precision and zero division overhead
 const U64_TO_UNIT_F64: f64 = 1.0 / (u64::MAX as f64);
 
-pub fn should_spawn_galaxy(world_seed: u64, galaxy_position: (i32, i32, i32)) -> bool {
-    let density = compute_cosmic_density(galaxy_position);
+// Cosmic web: low-frequency filaments with sub-cluster detail on top
+const COSMIC_FILAMENT_FBM: Fbm = Fbm::new(4, 0.08);
+const COSMIC_WARP_STRENGTH: f64 = 6.0;
+
+pub fn should_spawn_galaxy(root: RootSeed, galaxy_position: (i32, i32, i32)) -> bool {
+    let density = compute_cosmic_density(root, galaxy_position);
 
     let galaxy_spawn_seed = derive_seed(
-        world_seed,
+        root.seed,
+        root.generation_version,
         GALAXY_SPAWN_TAG,
         &[
             galaxy_position.0 as i64,
@@ -23,20 +31,24 @@ pub fn should_spawn_galaxy(world_seed: u64, galaxy_position: (i32, i32, i32)) ->
     roll < threshold
 }
 
-fn compute_cosmic_density(position: (i32, i32, i32)) -> f64 {
-    let x = position.0 as f64 * 0.08;
-    let y = position.1 as f64 * 0.08;
-    let z = position.2 as f64 * 0.08;
-
-    // Coupled primary waves (cosmic filaments)
-    let base_structure = (x.sin() * y.cos()) + (y.sin() * z.cos()) + (z.sin() * x.cos());
-
-    // Higher frequency octave (sub-clusters & fine void detail)
-    let detail = (x * 2.3).cos() * (z * 2.3).sin() * 0.5;
-
-    let raw_density = base_structure + detail; // Range: [-3.5 .. 3.5]
-
-    // Correctly normalize [-3.5, 3.5] -> [0.0, 1.0]
-    let normalized = (raw_density + 3.5) / 7.0;
+fn compute_cosmic_density(root: RootSeed, position: (i32, i32, i32)) -> f64 {
+    let field = NoiseField::new(derive_seed(
+        root.seed,
+        root.generation_version,
+        UNIVERSE_DENSITY_NOISE_TAG,
+        &[],
+    ));
+
+    // Ridges of the warped field become filaments, troughs become voids
+    let raw_density = field.warped(
+        position.0 as f64,
+        position.1 as f64,
+        position.2 as f64,
+        COSMIC_FILAMENT_FBM,
+        COSMIC_WARP_STRENGTH,
+    ); // Range: [-1.0 .. 1.0]
+
+    // Normalize [-1.0, 1.0] -> [0.0, 1.0]
+    let normalized = 0.5 + 0.5 * raw_density;
     normalized.clamp(0.0, 1.0)
 }
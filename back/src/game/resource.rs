@@ -0,0 +1,77 @@
+/// Every mineable resource of the game. Tile deposits, ring mining and the `rare_deposit`
+/// column of planet_tiles all use this list, stored by `as_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Coal,
+    Iron,
+    Gold,
+    Gems,
+    Petrol,
+    Uranium,
+    RareEarths,
+    Silicon,
+    Titanium,
+    Deuterium,
+    DarkMatter,
+}
+
+impl Resource {
+    /// Every resource, in declaration order: `resource as u8` indexes this array.
+    pub const ALL: [Resource; 11] = [
+        Self::Coal,
+        Self::Iron,
+        Self::Gold,
+        Self::Gems,
+        Self::Petrol,
+        Self::Uranium,
+        Self::RareEarths,
+        Self::Silicon,
+        Self::Titanium,
+        Self::Deuterium,
+        Self::DarkMatter,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Coal => "coal",
+            Self::Iron => "iron",
+            Self::Gold => "gold",
+            Self::Gems => "gems",
+            Self::Petrol => "petrol",
+            Self::Uranium => "uranium",
+            Self::RareEarths => "rare_earths",
+            Self::Silicon => "silicon",
+            Self::Titanium => "titanium",
+            Self::Deuterium => "deuterium",
+            Self::DarkMatter => "dark_matter",
+        }
+    }
+
+    /// Metals concentrate with the metallicity of the star the system formed around.
+    pub fn is_metal(&self) -> bool {
+        matches!(
+            self,
+            Self::Iron | Self::Gold | Self::Uranium | Self::RareEarths | Self::Titanium
+        )
+    }
+}
+
+/// Quality tier of a deposit, stored as 1..=3 in planet_tiles.deposit_richness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DepositRichness {
+    Poor = 1,
+    Standard = 2,
+    Rich = 3,
+}
+
+impl DepositRichness {
+    pub fn level(&self) -> u8 {
+        *self as u8
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deposit {
+    pub resource: Resource,
+    pub richness: DepositRichness,
+}
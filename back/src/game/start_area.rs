@@ -0,0 +1,249 @@
+use crate::game::proc_gen::tile::{DynamicTileProperties, TileType};
+
+/// How far around a home tile a new player's early economy reaches.
+pub const START_AREA_RINGS: u32 = 3;
+
+// Score weights: each resource stops counting once a start has "enough" of it, so a huge
+// forest cannot make up for having no stone at all
+const FOOD_CAP: f32 = 10.0;
+const WOOD_CAP: f32 = 6.0;
+const STONE_CAP: f32 = 4.0;
+const STONE_WEIGHT: f32 = 1.5;
+const DEPOSIT_CAP: u32 = 3;
+const DEPOSIT_WEIGHT: f32 = 2.0;
+const COAST_BONUS: f32 = 3.0;
+const ISOLATION_BONUS: f32 = 3.0;
+// Beyond this many star system cells the nearest rival no longer matters
+const FULL_ISOLATION_DISTANCE: f32 = 12.0;
+
+/// Best score any start can reach: every resource at its cap, coastal and alone.
+pub const MAX_START_SCORE: f32 = FOOD_CAP
+    + WOOD_CAP
+    + STONE_WEIGHT * STONE_CAP
+    + DEPOSIT_WEIGHT * DEPOSIT_CAP as f32
+    + COAST_BONUS
+    + ISOLATION_BONUS;
+
+/// Scores a start must land between. Below `min` a player would be starved of something;
+/// above `max` they would snowball ahead of everyone else.
+#[derive(Debug, Clone, Copy)]
+pub struct FairnessBand {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for FairnessBand {
+    fn default() -> Self {
+        Self {
+            min: 18.0,
+            max: 30.0,
+        }
+    }
+}
+
+impl FairnessBand {
+    pub fn contains(&self, score: f32) -> bool {
+        (self.min..=self.max).contains(&score)
+    }
+}
+
+/// What a player can reach from a candidate home tile.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StartAreaSurvey {
+    // Yield-weighted tile counts (each tile adds its 0.0 .. 1.0 yield quality)
+    pub food: f32,
+    pub wood: f32,
+    pub stone: f32,
+    pub rare_deposits: u32,
+    pub water_tiles: u32,
+    // Star system grid distance to the closest other home in the galaxy, None when alone
+    pub nearest_player_distance: Option<f32>,
+}
+
+impl StartAreaSurvey {
+    /// `area` is every tile within `START_AREA_RINGS` of the home tile, the home tile included.
+    pub fn new(area: &[DynamicTileProperties], nearest_player_distance: Option<f32>) -> Self {
+        let mut survey = Self {
+            nearest_player_distance,
+            ..Self::default()
+        };
+
+        for tile in area {
+            let quality = tile.yield_quality;
+            match tile.tile_type {
+                TileType::Plains | TileType::Swamp => survey.food += quality,
+                TileType::Forest => survey.wood += quality,
+                // Jungle feeds and builds, at half the rate of dedicated land
+                TileType::Jungle => {
+                    survey.food += 0.5 * quality;
+                    survey.wood += 0.5 * quality;
+                }
+                TileType::Mountain => survey.stone += quality,
+                TileType::Water | TileType::Ocean => survey.water_tiles += 1,
+                _ => {}
+            }
+            if tile.rare_deposit.is_some() {
+                survey.rare_deposits += 1;
+            }
+        }
+
+        survey
+    }
+
+    /// Food, wood and stone are all reachable: the floor under every other consideration.
+    pub fn has_essentials(&self) -> bool {
+        self.food > 0.0 && self.wood > 0.0 && self.stone > 0.0
+    }
+
+    pub fn score(&self) -> f32 {
+        let coast = if self.water_tiles > 0 {
+            COAST_BONUS
+        } else {
+            0.0
+        };
+        let isolation = match self.nearest_player_distance {
+            None => ISOLATION_BONUS,
+            Some(distance) => ISOLATION_BONUS * (distance / FULL_ISOLATION_DISTANCE).min(1.0),
+        };
+
+        self.food.min(FOOD_CAP)
+            + self.wood.min(WOOD_CAP)
+            + STONE_WEIGHT * self.stone.min(STONE_CAP)
+            + DEPOSIT_WEIGHT * self.rare_deposits.min(DEPOSIT_CAP) as f32
+            + coast
+            + isolation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::resource::{Deposit, DepositRichness, Resource};
+
+    fn tile(tile_type: TileType, yield_quality: f32) -> DynamicTileProperties {
+        DynamicTileProperties {
+            face: 0,
+            u: 0,
+            v: 0,
+            tile_type,
+            elevation: 0.5,
+            moisture: 0.5,
+            temperature_k: 288.0,
+            yield_quality,
+            rare_deposit: None,
+        }
+    }
+
+    fn tiles(tile_type: TileType, count: usize) -> Vec<DynamicTileProperties> {
+        vec![tile(tile_type, 1.0); count]
+    }
+
+    fn score_of(area: &[DynamicTileProperties]) -> f32 {
+        // Alone: the isolation bonus is the same for every area compared
+        StartAreaSurvey::new(area, None).score()
+    }
+
+    #[test]
+    fn each_resource_stops_counting_at_its_cap() {
+        let alone = ISOLATION_BONUS;
+
+        assert_eq!(score_of(&tiles(TileType::Plains, 100)), FOOD_CAP + alone);
+        assert_eq!(score_of(&tiles(TileType::Forest, 100)), WOOD_CAP + alone);
+        assert_eq!(
+            score_of(&tiles(TileType::Mountain, 100)),
+            STONE_WEIGHT * STONE_CAP + alone
+        );
+
+        let mut deposits = tiles(TileType::Tundra, 10);
+        for tile in &mut deposits {
+            tile.rare_deposit = Some(Deposit {
+                resource: Resource::Iron,
+                richness: DepositRichness::Standard,
+            });
+        }
+        assert_eq!(
+            score_of(&deposits),
+            DEPOSIT_WEIGHT * DEPOSIT_CAP as f32 + alone
+        );
+    }
+
+    #[test]
+    fn huge_forest_cannot_offset_missing_stone() {
+        let mut forest = tiles(TileType::Forest, 200);
+        forest.extend(tiles(TileType::Plains, 10));
+
+        let mut balanced = tiles(TileType::Forest, 6);
+        balanced.extend(tiles(TileType::Plains, 10));
+        balanced.extend(tiles(TileType::Mountain, 4));
+
+        assert!(score_of(&forest) < score_of(&balanced));
+        assert!(!StartAreaSurvey::new(&forest, None).has_essentials());
+        assert!(StartAreaSurvey::new(&balanced, None).has_essentials());
+    }
+
+    #[test]
+    fn essentials_need_food_wood_and_stone() {
+        let essentials = [TileType::Plains, TileType::Forest, TileType::Mountain];
+        for missing in essentials {
+            let area: Vec<_> = essentials
+                .into_iter()
+                .filter(|&tile_type| tile_type != missing)
+                .map(|tile_type| tile(tile_type, 1.0))
+                .collect();
+            assert!(
+                !StartAreaSurvey::new(&area, None).has_essentials(),
+                "without {missing:?}"
+            );
+        }
+
+        // Jungle feeds and builds
+        let jungle = [tile(TileType::Jungle, 1.0), tile(TileType::Mountain, 1.0)];
+        assert!(StartAreaSurvey::new(&jungle, None).has_essentials());
+        // Barren tiles count for nothing
+        let barren = [
+            tile(TileType::Plains, 0.0),
+            tile(TileType::Forest, 1.0),
+            tile(TileType::Mountain, 1.0),
+        ];
+        assert!(!StartAreaSurvey::new(&barren, None).has_essentials());
+    }
+
+    #[test]
+    fn isolation_grows_until_full_distance() {
+        let isolation = |nearest_player_distance| {
+            StartAreaSurvey {
+                nearest_player_distance,
+                ..StartAreaSurvey::default()
+            }
+            .score()
+        };
+
+        assert_eq!(isolation(None), ISOLATION_BONUS);
+        assert_eq!(isolation(Some(0.0)), 0.0);
+        assert_eq!(
+            isolation(Some(FULL_ISOLATION_DISTANCE / 2.0)),
+            ISOLATION_BONUS / 2.0
+        );
+        assert_eq!(isolation(Some(FULL_ISOLATION_DISTANCE)), ISOLATION_BONUS);
+        assert_eq!(
+            isolation(Some(FULL_ISOLATION_DISTANCE * 4.0)),
+            ISOLATION_BONUS
+        );
+    }
+
+    #[test]
+    fn saturated_coastal_start_reaches_max_score() {
+        let mut area = tiles(TileType::Plains, 20);
+        area.extend(tiles(TileType::Forest, 20));
+        area.extend(tiles(TileType::Mountain, 20));
+        area.extend(tiles(TileType::Ocean, 1));
+        for tile in area.iter_mut().take(DEPOSIT_CAP as usize) {
+            tile.rare_deposit = Some(Deposit {
+                resource: Resource::Coal,
+                richness: DepositRichness::Rich,
+            });
+        }
+
+        assert_eq!(score_of(&area), MAX_START_SCORE);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::game::unit_stats::UnitStats;
+
+// Beyond this distance from its primary star a point is between the stars: reach another
+// system with a jump. Wide triple systems keep their outer star within about 2000 AU.
+pub const MAX_SYSTEM_RADIUS_AU: f64 = 5_000.0;
+// A jump drops out of interstellar flight this far from the target's primary star, on the side
+// it came from
+pub const JUMP_ARRIVAL_DISTANCE_AU: f64 = 100.0;
+
+const SECONDS_PER_HOUR: f64 = 3_600.0;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// A point of a star system, in AU from its primary star. `grid` is the system's cell in its
+/// galaxy, `star_systems.x/y/z`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemPoint {
+    pub star_system_id: i64,
+    pub grid: (i32, i32, i32),
+    pub position_au: [f64; 3],
+}
+
+/// Where a ship is at some instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpacePosition {
+    InSystem {
+        star_system_id: i64,
+        position_au: [f64; 3],
+    },
+    // Jumping: a fractional cell of the galaxy grid, on the line between both systems
+    Interstellar {
+        grid_position: [f64; 3],
+    },
+}
+
+/// A straight flight at constant speed: a hop within one system, or a jump to another one.
+/// Times are Unix epoch milliseconds, as in move_orders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trajectory {
+    pub from: SystemPoint,
+    pub to: SystemPoint,
+    pub start_time: i64,
+    pub arrival_time: i64,
+}
+
+impl Trajectory {
+    pub fn is_jump(&self) -> bool {
+        self.from.star_system_id != self.to.star_system_id
+    }
+
+    /// Where the ship is at `at_ms`, holding at either end outside the flight. A jump leaves its
+    /// system as it starts and enters the other one as it arrives.
+    pub fn position_at(&self, at_ms: i64) -> SpacePosition {
+        let progress = if self.arrival_time > self.start_time {
+            ((at_ms - self.start_time) as f64 / (self.arrival_time - self.start_time) as f64)
+                .clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let in_system = |point: &SystemPoint, position_au| SpacePosition::InSystem {
+            star_system_id: point.star_system_id,
+            position_au,
+        };
+        if !self.is_jump() {
+            let position_au = lerp(self.from.position_au, self.to.position_au, progress);
+            return in_system(&self.from, position_au);
+        }
+        if progress <= 0.0 {
+            in_system(&self.from, self.from.position_au)
+        } else if progress >= 1.0 {
+            in_system(&self.to, self.to.position_au)
+        } else {
+            SpacePosition::Interstellar {
+                grid_position: lerp(cell(self.from.grid), cell(self.to.grid), progress),
+            }
+        }
+    }
+}
+
+/// Sub-light cruise speed, `None` for units that cannot fly in space.
+pub fn hop_speed_au_per_s(stats: &UnitStats) -> Option<f64> {
+    (stats.cruise_au_per_h > 0.0).then(|| stats.cruise_au_per_h / SECONDS_PER_HOUR)
+}
+
+/// Game seconds a jump across `distance_cells` of the galaxy grid takes, `None` for units
+/// without an interstellar drive. Days for neighbouring systems, weeks across an arm.
+pub fn jump_duration_s(stats: &UnitStats, distance_cells: f64) -> Option<f64> {
+    (stats.jump_cells_per_day > 0.0)
+        .then(|| distance_cells / stats.jump_cells_per_day * SECONDS_PER_DAY)
+}
+
+/// Deuterium burnt by `count` ships of a type flying `distance_au` within a system.
+pub fn hop_fuel(stats: &UnitStats, count: i32, distance_au: f64) -> f64 {
+    stats.deuterium_per_au * distance_au * count as f64
+}
+
+/// Deuterium burnt by `count` ships of a type jumping across `distance_cells`.
+pub fn jump_fuel(stats: &UnitStats, count: i32, distance_cells: f64) -> f64 {
+    stats.deuterium_per_cell * distance_cells * count as f64
+}
+
+/// Where a jump from `from` enters the system at `to`: `JUMP_ARRIVAL_DISTANCE_AU` out from
+/// its primary star, towards the system it left.
+pub fn jump_arrival_au(from: (i32, i32, i32), to: (i32, i32, i32)) -> [f64; 3] {
+    let back = [0, 1, 2].map(|axis| cell(from)[axis] - cell(to)[axis]);
+    let length = distance(back, [0.0; 3]);
+    if length == 0.0 {
+        return [JUMP_ARRIVAL_DISTANCE_AU, 0.0, 0.0];
+    }
+    back.map(|c| c / length * JUMP_ARRIVAL_DISTANCE_AU)
+}
+
+/// Cells of the galaxy grid between two star systems.
+pub fn grid_distance(a: (i32, i32, i32), b: (i32, i32, i32)) -> f64 {
+    distance(cell(a), cell(b))
+}
+
+pub fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (0..3)
+        .map(|axis| (a[axis] - b[axis]).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn cell((x, y, z): (i32, i32, i32)) -> [f64; 3] {
+    [x as f64, y as f64, z as f64]
+}
+
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    [0, 1, 2].map(|axis| a[axis] + (b[axis] - a[axis]) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(star_system_id: i64, grid: (i32, i32, i32), position_au: [f64; 3]) -> SystemPoint {
+        SystemPoint {
+            star_system_id,
+            grid,
+            position_au,
+        }
+    }
+
+    fn flight(from: SystemPoint, to: SystemPoint) -> Trajectory {
+        Trajectory {
+            from,
+            to,
+            start_time: 1_000,
+            arrival_time: 5_000,
+        }
+    }
+
+    #[test]
+    fn hops_hold_at_their_ends_outside_the_flight() {
+        let hop = flight(
+            point(1, (0, 0, 0), [1.0, 0.0, 0.0]),
+            point(1, (0, 0, 0), [3.0, 2.0, -4.0]),
+        );
+        let at = |position_au| SpacePosition::InSystem {
+            star_system_id: 1,
+            position_au,
+        };
+
+        assert!(!hop.is_jump());
+        assert_eq!(hop.position_at(0), at([1.0, 0.0, 0.0]));
+        assert_eq!(hop.position_at(1_000), at([1.0, 0.0, 0.0]));
+        assert_eq!(hop.position_at(3_000), at([2.0, 1.0, -2.0]));
+        assert_eq!(hop.position_at(5_000), at([3.0, 2.0, -4.0]));
+        assert_eq!(hop.position_at(i64::MAX), at([3.0, 2.0, -4.0]));
+    }
+
+    #[test]
+    fn jumps_are_interstellar_strictly_between_their_ends() {
+        let from = point(1, (0, 0, 0), [1.0, 0.0, 0.0]);
+        let to = point(2, (4, -2, 8), [50.0, 0.0, 0.0]);
+        let jump = flight(from, to);
+        let at = |point: SystemPoint| SpacePosition::InSystem {
+            star_system_id: point.star_system_id,
+            position_au: point.position_au,
+        };
+
+        assert!(jump.is_jump());
+        assert_eq!(jump.position_at(0), at(from));
+        assert_eq!(jump.position_at(1_000), at(from));
+        assert_eq!(
+            jump.position_at(1_001),
+            SpacePosition::Interstellar {
+                grid_position: [0.001, -0.0005, 0.002]
+            }
+        );
+        assert_eq!(
+            jump.position_at(3_000),
+            SpacePosition::Interstellar {
+                grid_position: [2.0, -1.0, 4.0]
+            }
+        );
+        assert!(matches!(
+            jump.position_at(4_999),
+            SpacePosition::Interstellar { .. }
+        ));
+        assert_eq!(jump.position_at(5_000), at(to));
+        assert_eq!(jump.position_at(i64::MAX), at(to));
+    }
+
+    #[test]
+    fn instant_flights_are_already_there() {
+        let from = point(1, (0, 0, 0), [1.0, 0.0, 0.0]);
+        let to = point(2, (1, 0, 0), [-100.0, 0.0, 0.0]);
+        let jump = Trajectory {
+            arrival_time: 1_000,
+            ..flight(from, to)
+        };
+        assert_eq!(
+            jump.position_at(0),
+            SpacePosition::InSystem {
+                star_system_id: 2,
+                position_au: to.position_au
+            }
+        );
+    }
+
+    #[test]
+    fn jumps_arrive_on_the_side_they_came_from() {
+        for (from, to) in [
+            ((0, 0, 0), (1, 0, 0)),
+            ((5, 5, 5), (2, 1, 5)),
+            ((-7, 3, 12), (9, -4, 0)),
+        ] {
+            let arrival = jump_arrival_au(from, to);
+            assert!((distance(arrival, [0.0; 3]) - JUMP_ARRIVAL_DISTANCE_AU).abs() < 1e-9);
+
+            // Along the line back to the origin system
+            let back = [0, 1, 2].map(|axis| cell(from)[axis] - cell(to)[axis]);
+            let scale = JUMP_ARRIVAL_DISTANCE_AU / grid_distance(from, to);
+            for axis in 0..3 {
+                assert!((arrival[axis] - back[axis] * scale).abs() < 1e-9);
+            }
+        }
+
+        assert_eq!(
+            jump_arrival_au((3, 3, 3), (3, 3, 3)),
+            [JUMP_ARRIVAL_DISTANCE_AU, 0.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+/// What a unit type can do, looked up by the `units.unit_type` it is stored as.
+/// Only movement and cargo are modelled so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitStats {
+    // Can leave the surface and fly (design doc §6.2 "Orbit capable")
+    pub orbit_capable: bool,
+    // Engine acceleration in g: lifting off needs more than the planet's surface gravity
+    pub thrust_g: f64,
+    // Sub-light speed between points of a star system
+    pub cruise_au_per_h: f64,
+    // Interstellar drive speed, in cells of the galaxy grid (0.0 if it has none)
+    pub jump_cells_per_day: f64,
+    // Fuel burnt per ship of the type
+    pub deuterium_per_au: f64,
+    pub deuterium_per_cell: f64,
+    // Resources one ship of the type hauls per extraction, e.g. from an asteroid
+    pub cargo_capacity: f64,
+}
+
+impl UnitStats {
+    // Walks, never flies
+    const GROUND: Self = Self {
+        orbit_capable: false,
+        thrust_g: 0.0,
+        cruise_au_per_h: 0.0,
+        jump_cells_per_day: 0.0,
+        deuterium_per_au: 0.0,
+        deuterium_per_cell: 0.0,
+        cargo_capacity: 0.0,
+    };
+
+    pub fn for_type(unit_type: &str) -> Self {
+        match unit_type {
+            // The starting scout doubles as a light lander
+            "scout" => Self {
+                orbit_capable: true,
+                thrust_g: 3.0,
+                cruise_au_per_h: 2.0,
+                jump_cells_per_day: 4.0,
+                deuterium_per_au: 0.5,
+                deuterium_per_cell: 25.0,
+                cargo_capacity: 50.0,
+            },
+            _ => Self::GROUND,
+        }
+    }
+}
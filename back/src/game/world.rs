@@ -0,0 +1,88 @@
+use crate::db::world::WorldRow;
+use crate::game::proc_gen::seed::{RootSeed, check_generation_version};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+
+/// Game speed preset of a world, in the spirit of OGame's speed universes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ruleset {
+    Standard,
+    Speed,
+    // Developer world: everything resolves in seconds
+    Test,
+}
+
+impl Ruleset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ruleset::Standard => "standard",
+            Ruleset::Speed => "speed",
+            Ruleset::Test => "test",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "standard" => Ok(Ruleset::Standard),
+            "speed" => Ok(Ruleset::Speed),
+            "test" => Ok(Ruleset::Test),
+            other => Err(anyhow!(
+                "unknown ruleset '{other}' (expected standard, speed or test)"
+            )),
+        }
+    }
+
+    /// Travel times (surface moves, launches, space flights) are divided by it.
+    pub fn fleet_speed(&self) -> f64 {
+        match self {
+            Ruleset::Standard => 1.0,
+            Ruleset::Speed => 3.0,
+            Ruleset::Test => 20.0,
+        }
+    }
+}
+
+/// The universe this server process hosts, loaded once at startup.
+#[derive(Debug, Clone)]
+pub struct World {
+    pub id: i64,
+    pub name: String,
+    pub seed: u64,
+    pub generation_version: u32,
+    pub ruleset: Ruleset,
+    pub started_at: DateTime<Utc>,
+}
+
+impl World {
+    /// Seed every galaxy of this world derives from, with its generation version.
+    pub fn root_seed(&self) -> RootSeed {
+        RootSeed::new(self.seed, self.generation_version)
+    }
+
+    /// Game seconds elapsed at `at` since the world started: the clock orbits run on.
+    pub fn game_time_s(&self, at: DateTime<Utc>) -> f64 {
+        (at - self.started_at)
+            .to_std()
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64())
+    }
+}
+
+impl TryFrom<WorldRow> for World {
+    type Error = anyhow::Error;
+
+    fn try_from(row: WorldRow) -> Result<Self> {
+        check_generation_version(row.generation_version as u32)
+            .with_context(|| format!("cannot serve world '{}'", row.name))?;
+
+        Ok(Self {
+            id: row.id,
+            ruleset: Ruleset::parse(&row.ruleset)?,
+            seed: row.seed as u64, // Stored bit-for-bit in SQLite's signed INTEGER
+            generation_version: row.generation_version as u32,
+            name: row.name,
+            started_at: DateTime::parse_from_rfc3339(&row.started_at)
+                .with_context(|| format!("invalid world start time '{}'", row.started_at))?
+                .with_timezone(&Utc),
+        })
+    }
+}
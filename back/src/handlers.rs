@@ -1,4 +1,7 @@
+pub mod events;
+pub mod planet;
 pub mod state;
-// pub mod events;
+pub mod unit;
+pub mod universe;
 // pub mod move_unit;
 // pub mod state;
@@ -0,0 +1,86 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::services::diplomacy;
+use crate::services::events::PublishedEvent;
+use crate::{app::AppState, auth::middleware::AuthPlayer};
+
+/// GET /api/events
+///
+/// Server-sent events of the player: each carries the id to resume from in `Last-Event-ID`
+/// and a `GameEventDto` as JSON data. A `resync` event means some events were lost: reload
+/// the state.
+pub async fn stream_events(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let player_id = auth.0;
+    let last_event_id = headers
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let subscription = state.events.subscribe(last_event_id);
+
+    let stream = async_stream::stream! {
+        if subscription.gap {
+            yield Ok(resync_event());
+        }
+        for event in subscription.replay.iter().filter(|event| event.is_for(player_id)) {
+            yield Ok(sse_event(event));
+        }
+
+        let mut receiver = subscription.receiver;
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.is_for(player_id) => yield Ok(sse_event(&event)),
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => yield Ok(resync_event()),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessageRequest {
+    pub text: String,
+}
+
+/// POST /api/players/{id}/messages
+pub async fn send_message(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(to_player_id): Path<i64>,
+    Json(request): Json<MessageRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    diplomacy::send_message(
+        &state.db,
+        &state.world,
+        &state.events,
+        auth.0,
+        to_player_id,
+        &request.text,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn sse_event(event: &PublishedEvent) -> Event {
+    Event::default().id(event.id.to_string()).data(&event.json)
+}
+
+fn resync_event() -> Event {
+    Event::default().event("resync").data("{}")
+}
@@ -1,9 +1,24 @@
-// // handlers/planet.rs
-// pub async fn get_planet(
-//     State(state): State<Arc<AppState>>,
-//     auth: AuthPlayer,
-//     Path(planet_id): Path<i64>,
-// ) -> Result<Json<PlanetResponse>, AppError> {
-//     let response = planet_service::get_planet(&state.db, auth.0, planet_id).await?;
-//     Ok(Json(response))
-// }
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use std::sync::Arc;
+
+use crate::dto::planet::PlanetSurfaceDto;
+use crate::services::planets;
+use crate::{app::AppState, auth::middleware::AuthPlayer};
+
+/// GET /api/planets/{id}
+pub async fn get_planet(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(planet_id): Path<i64>,
+) -> Result<Json<PlanetSurfaceDto>, (StatusCode, String)> {
+    let planet = planets::load_planet_surface(&state.db, &state.world, auth.0, planet_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Planet not found".to_string()))?;
+
+    Ok(Json(planet))
+}
@@ -1,10 +1,8 @@
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::State,
     http::StatusCode,
 };
-use serde::{Deserialize, Serialize};
-use sqlx::prelude::FromRow;
 use std::sync::Arc;
 
 // use crate::models::GameStateDto;
@@ -13,9 +11,6 @@ use crate::{
     app::AppState,
     // game::{init_new_player, reveal_fog},
     auth::middleware::AuthPlayer,
-    game::proc_gen::seed::{
-        GALAXY_TAG, PLANET_SUBDIVISION_TAG, PLANET_TAG, SYSTEM_TAG, WORLD_SEED, derive_seed,
-    },
     // dto::state::GameStateDto,
 };
 
@@ -33,9 +28,10 @@ pub async fn get_game_state(
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let player_id = auth.0;
 
-    let gs = spawn::load_or_initialize_player(&state.db, player_id)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let gs =
+        spawn::load_or_initialize_player(&state.db, &state.world, &state.spawn_band, player_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     Ok(Json(gs))
 }
@@ -0,0 +1,224 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::dto::flight::FlightOrderDto;
+use crate::dto::move_route::MoveRouteDto;
+use crate::dto::space_object::AsteroidExtractionDto;
+use crate::dto::travel::{SpaceTravelDto, UnitPositionDto};
+use crate::game::flight::FlightState;
+use crate::services::events;
+use crate::services::flight::{self, FlightOrder};
+use crate::services::movement;
+use crate::services::travel::{self, TravelOrder};
+use crate::services::universe::space_objects;
+use crate::{app::AppState, auth::middleware::AuthPlayer};
+
+/// Destination tile, on the planet the unit stands on.
+#[derive(Debug, Deserialize)]
+pub struct MoveUnitRequest {
+    pub face: u8,
+    pub u: u32,
+    pub v: u32,
+}
+
+/// POST /api/units/{id}/move
+pub async fn move_unit(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(unit_id): Path<i64>,
+    Json(request): Json<MoveUnitRequest>,
+) -> Result<Json<MoveRouteDto>, (StatusCode, String)> {
+    let route = movement::order_surface_move(
+        &state.db,
+        &state.world,
+        auth.0,
+        unit_id,
+        (request.face, request.u, request.v),
+    )
+    .await?;
+
+    state.scheduler.wake();
+    Ok(Json(route))
+}
+
+/// DELETE /api/units/{id}/move: cancels the walk and returns the way back to its start.
+pub async fn cancel_move(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(unit_id): Path<i64>,
+) -> Result<Json<MoveRouteDto>, (StatusCode, String)> {
+    let route = movement::cancel_surface_move(&state.db, &state.world, auth.0, unit_id).await?;
+
+    state.scheduler.wake();
+    Ok(Json(route))
+}
+
+/// Layer to reach: `surface`, `low_orbit`, `high_orbit` or `space`. Entering an orbit from
+/// space names the planet, landing names the tile.
+#[derive(Debug, Deserialize)]
+pub struct FlightRequest {
+    pub to: String,
+    pub planet_id: Option<i64>,
+    pub face: Option<u8>,
+    pub u: Option<u32>,
+    pub v: Option<u32>,
+}
+
+/// POST /api/units/{id}/flight
+pub async fn order_flight(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(unit_id): Path<i64>,
+    Json(request): Json<FlightRequest>,
+) -> Result<Json<FlightOrderDto>, (StatusCode, String)> {
+    let to = FlightState::parse(&request.to)
+        .filter(|to| *to != FlightState::Embarked)
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                "to must be surface, low_orbit, high_orbit or space".to_string(),
+            )
+        })?;
+    let landing_tile = match (request.face, request.u, request.v) {
+        (Some(face), Some(u), Some(v)) => Some((face, u, v)),
+        _ => None,
+    };
+
+    let order = FlightOrder {
+        to,
+        planet_id: request.planet_id,
+        landing_tile,
+    };
+    let flight = flight::order_flight(&state.db, &state.world, auth.0, unit_id, &order).await?;
+
+    state.scheduler.wake();
+    // Coming down on a planet: its holders get to see it coming
+    if matches!(flight.maneuver, "enter_orbit" | "lower_orbit" | "land") {
+        announce_incoming(
+            &state,
+            auth.0,
+            unit_id,
+            flight.planet_id,
+            flight.maneuver,
+            flight.arrival_time,
+        )
+        .await;
+    }
+    Ok(Json(flight))
+}
+
+/// Destination in space: a planet of the unit's system, or a point (AU from the primary
+/// star) of its system or of another one of the galaxy.
+#[derive(Debug, Deserialize)]
+pub struct TravelRequest {
+    pub planet_id: Option<i64>,
+    pub star_system_id: Option<i64>,
+    pub position: Option<[f64; 3]>,
+}
+
+/// POST /api/units/{id}/travel
+pub async fn order_travel(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(unit_id): Path<i64>,
+    Json(request): Json<TravelRequest>,
+) -> Result<Json<SpaceTravelDto>, (StatusCode, String)> {
+    let order = TravelOrder {
+        planet_id: request.planet_id,
+        star_system_id: request.star_system_id,
+        position_au: request.position,
+    };
+    let flight =
+        travel::order_space_travel(&state.db, &state.world, auth.0, unit_id, &order).await?;
+
+    state.scheduler.wake();
+    if let Some(planet_id) = flight.planet_id {
+        announce_incoming(
+            &state,
+            auth.0,
+            unit_id,
+            planet_id,
+            "space_travel",
+            flight.arrival_time,
+        )
+        .await;
+    }
+    Ok(Json(flight))
+}
+
+/// Instant to locate the unit at, in Unix epoch milliseconds. Defaults to now.
+#[derive(Debug, Deserialize)]
+pub struct PositionQuery {
+    pub at: Option<i64>,
+}
+
+/// GET /api/units/{id}/position
+pub async fn get_position(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(unit_id): Path<i64>,
+    Query(query): Query<PositionQuery>,
+) -> Result<Json<UnitPositionDto>, (StatusCode, String)> {
+    let at = match query.at {
+        Some(ms) => DateTime::from_timestamp_millis(ms)
+            .ok_or((StatusCode::BAD_REQUEST, "at is out of range".to_string()))?,
+        None => Utc::now(),
+    };
+    let position =
+        travel::unit_space_position(&state.db, &state.world, auth.0, unit_id, at).await?;
+    Ok(Json(position))
+}
+
+/// Asteroid of the unit's star system to mine, by its index in the system.
+#[derive(Debug, Deserialize)]
+pub struct ExtractRequest {
+    pub asteroid_index: u32,
+}
+
+/// POST /api/units/{id}/extract
+pub async fn extract_asteroid(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path(unit_id): Path<i64>,
+    Json(request): Json<ExtractRequest>,
+) -> Result<Json<AsteroidExtractionDto>, (StatusCode, String)> {
+    let extraction = space_objects::extract_asteroid(
+        &state.db,
+        &state.world,
+        auth.0,
+        unit_id,
+        request.asteroid_index,
+    )
+    .await?;
+    Ok(Json(extraction))
+}
+
+// The order stands even if the warning fails
+async fn announce_incoming(
+    state: &AppState,
+    player_id: i64,
+    unit_id: i64,
+    planet_id: i64,
+    move_type: &str,
+    arrival_time: i64,
+) {
+    if let Err(e) = events::announce_incoming_unit(
+        &state.db,
+        &state.events,
+        player_id,
+        unit_id,
+        planet_id,
+        move_type,
+        arrival_time,
+    )
+    .await
+    {
+        tracing::warn!("incoming unit {unit_id} not announced: {:#}", e);
+    }
+}
@@ -0,0 +1,156 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::dto::space_object::SystemSpaceObjectsDto;
+use crate::dto::star_system::StarSystemDto;
+use crate::dto::universe::{GalaxyChunkDto, NearbyStarSystemsDto, StarSystemChunkDto};
+use crate::game::proc_gen::chunk::ChunkCoord;
+use crate::services::universe::nearby::{
+    DEFAULT_NEARBY_LIMIT, MAX_NEARBY_LIMIT, MAX_NEARBY_RADIUS,
+};
+use crate::services::universe::{chunks, nearby, space_objects, systems};
+use crate::{app::AppState, auth::middleware::AuthPlayer};
+
+/// GET /api/universe/chunks/{cx}/{cy}/{cz}
+pub async fn get_galaxy_chunk(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path((cx, cy, cz)): Path<(i32, i32, i32)>,
+) -> Result<Json<GalaxyChunkDto>, (StatusCode, String)> {
+    let chunk = chunks::load_galaxy_chunk(
+        &state.db,
+        &state.universe_cache,
+        &state.world,
+        auth.0,
+        ChunkCoord::new(cx, cy, cz),
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| (StatusCode::NOT_FOUND, "Chunk out of range".to_string()))?;
+
+    Ok(Json(chunk))
+}
+
+/// GET /api/universe/galaxies/{gx}/{gy}/{gz}/chunks/{cx}/{cy}/{cz}
+pub async fn get_star_system_chunk(
+    State(state): State<Arc<AppState>>,
+    auth: AuthPlayer,
+    Path((gx, gy, gz, cx, cy, cz)): Path<(i32, i32, i32, i32, i32, i32)>,
+) -> Result<Json<StarSystemChunkDto>, (StatusCode, String)> {
+    let chunk = chunks::load_star_system_chunk(
+        &state.db,
+        &state.universe_cache,
+        &state.world,
+        auth.0,
+        (gx, gy, gz),
+        ChunkCoord::new(cx, cy, cz),
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            "No galaxy at these coordinates, or chunk out of range".to_string(),
+        )
+    })?;
+
+    Ok(Json(chunk))
+}
+
+/// GET /api/universe/galaxies/{gx}/{gy}/{gz}/systems/{sx}/{sy}/{sz}
+pub async fn get_star_system(
+    State(state): State<Arc<AppState>>,
+    _auth: AuthPlayer,
+    Path((gx, gy, gz, sx, sy, sz)): Path<(i32, i32, i32, i32, i32, i32)>,
+) -> Result<Json<StarSystemDto>, (StatusCode, String)> {
+    let system = systems::load_star_system(&state.db, &state.world, (gx, gy, gz), (sx, sy, sz))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                "No star system at these coordinates".to_string(),
+            )
+        })?;
+
+    Ok(Json(system))
+}
+
+/// GET /api/universe/galaxies/{gx}/{gy}/{gz}/systems/{sx}/{sy}/{sz}/objects
+pub async fn get_system_space_objects(
+    State(state): State<Arc<AppState>>,
+    _auth: AuthPlayer,
+    Path((gx, gy, gz, sx, sy, sz)): Path<(i32, i32, i32, i32, i32, i32)>,
+) -> Result<Json<SystemSpaceObjectsDto>, (StatusCode, String)> {
+    let objects = space_objects::load_system_space_objects(
+        &state.db,
+        &state.world,
+        (gx, gy, gz),
+        (sx, sy, sz),
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            "No star system at these coordinates".to_string(),
+        )
+    })?;
+
+    Ok(Json(objects))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NearbyQuery {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub radius: Option<f32>,
+    pub limit: Option<usize>,
+}
+
+/// GET /api/universe/galaxies/{gx}/{gy}/{gz}/systems/nearby?x=&y=&z=&radius=&limit=
+pub async fn get_nearby_star_systems(
+    State(state): State<Arc<AppState>>,
+    _auth: AuthPlayer,
+    Path((gx, gy, gz)): Path<(i32, i32, i32)>,
+    Query(query): Query<NearbyQuery>,
+) -> Result<Json<NearbyStarSystemsDto>, (StatusCode, String)> {
+    let radius = query.radius.unwrap_or(MAX_NEARBY_RADIUS);
+    if !(0.0..=MAX_NEARBY_RADIUS).contains(&radius) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("radius must be between 0 and {MAX_NEARBY_RADIUS}"),
+        ));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_NEARBY_LIMIT);
+    if !(1..=MAX_NEARBY_LIMIT).contains(&limit) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("limit must be between 1 and {MAX_NEARBY_LIMIT}"),
+        ));
+    }
+
+    let systems = nearby::load_nearby_star_systems(
+        &state.world,
+        (gx, gy, gz),
+        (query.x, query.y, query.z),
+        radius,
+        limit,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            "No galaxy at these coordinates".to_string(),
+        )
+    })?;
+
+    Ok(Json(systems))
+}
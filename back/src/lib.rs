@@ -0,0 +1,15 @@
+// The game server as a library: the `axosm` binary serves it, the tools under src/bin/ reuse
+// the generator without going through HTTP.
+
+pub mod app;
+mod auth;
+pub mod config;
+mod db;
+mod dto;
+mod error;
+pub mod game;
+mod handlers;
+pub mod maths;
+mod repositories;
+mod services;
+mod worker;
@@ -39,28 +39,21 @@ use tokio::net::TcpListener;
 // use std::convert::Infallible;
 // use tokio_stream::wrappers::ReceiverStream;
 
-mod app;
-mod auth;
-mod dto;
-mod handlers;
-// mod api;
-mod db;
-mod game;
-mod maths;
-mod repositories;
-mod services;
+use axosm::{app, config};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // logging
     app::init_tracing()?;
 
-    let state: Arc<app::AppState> = app::init_state().await?;
-    // app::spawn_worker(state.clone());
+    let config = config::Config::load()?;
+
+    let state: Arc<app::AppState> = app::init_state(&config).await?;
+    app::spawn_worker(state.clone());
 
     let app = app::router(state);
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    let addr = SocketAddr::from(([127, 0, 0, 1], config.port));
     let listener = TcpListener::bind(addr).await?;
     tracing::info!("listening on {}", addr);
 
@@ -1,3 +1,5 @@
+/// Walks every cell of the grid outward from a start cell, in growing cubic shells: shell `L`
+/// holds the (2L + 1)³ - (2L - 1)³ cells at Chebyshev distance `L`, visited in x, y, z order.
 pub struct Spiral3D {
     start_x: i32,
     start_y: i32,
@@ -42,39 +44,26 @@ impl Iterator for Spiral3D {
             self.start_z + self.z,
         );
 
-        // If we are at the origin layer, pop out to the first layer
-        if self.layer == 0 {
-            self.layer = 1;
-            self.x = 1;
-            self.y = -1;
-            self.z = -1;
-            return Some(ret);
-        }
-
-        // State Machine: Systematically walk the 6 outer faces of the current cube layer
-        if self.x == self.layer && self.y == -self.layer && self.z == -self.layer {
-            // Whole 3D layer shell is complete! Jump outward to the next cube layer.
+        let layer = self.layer;
+        if self.x.abs() < layer && self.y.abs() < layer && self.z == -layer {
+            // Inside the shell's x-y footprint only the bottom and top faces belong to it:
+            // jump over the inner cube
+            self.z = layer;
+        } else if self.z < layer {
+            self.z += 1;
+        } else if self.y < layer {
+            self.y += 1;
+            self.z = -layer;
+        } else if self.x < layer {
+            self.x += 1;
+            self.y = -layer;
+            self.z = -layer;
+        } else {
+            // Whole shell is complete! Jump outward to the first corner of the next one.
             self.layer += 1;
-            self.x = self.layer;
+            self.x = -self.layer;
             self.y = -self.layer;
             self.z = -self.layer;
-        } else if self.x == self.layer && self.y < self.layer {
-            self.y += 1; // Face 1: Move +Y along the front-right edge
-        } else if self.y == self.layer && self.x > -self.layer {
-            self.x -= 1; // Face 2: Move -X along the front-top edge
-        } else if self.x == -self.layer && self.y > -self.layer {
-            self.y -= 1; // Face 3: Move -Y along the back-left edge
-        } else if self.y == -self.layer && self.x < self.layer - 1 {
-            // Note the -1 constraint: we stop just short of completing the bottom ring
-            // so we can start stepping upward into the Z dimension.
-            self.x += 1;
-        } else if self.z < self.layer {
-            // Face 5 & 6: We have processed the base ring; now we increment Z
-            // and spiral inwards/outwards for the inner columns.
-            self.z += 1;
-            // Reset XY to the start of the ring for this specific Z height layer
-            self.x = self.layer;
-            self.y = -self.layer;
         }
 
         Some(ret)
@@ -90,3 +79,65 @@ impl Iterator for Spiral3D {
 //         println!("{} {} {}", x, y, z);
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn shell_size(layer: i32) -> usize {
+        if layer == 0 {
+            1
+        } else {
+            ((2 * layer + 1).pow(3) - (2 * layer - 1).pow(3)) as usize
+        }
+    }
+
+    fn chebyshev(a: (i32, i32, i32), b: (i32, i32, i32)) -> i32 {
+        (a.0 - b.0)
+            .abs()
+            .max((a.1 - b.1).abs())
+            .max((a.2 - b.2).abs())
+    }
+
+    #[test]
+    fn starts_at_the_start_cell() {
+        assert_eq!(Spiral3D::new(10, -20, 30).next(), Some((10, -20, 30)));
+        assert_eq!(Spiral3D::default().next(), Some((0, 0, 0)));
+    }
+
+    #[test]
+    fn visits_each_shell_completely_and_once_before_the_next() {
+        for start in [(0, 0, 0), (10, -20, 30), (-7, 3, 0)] {
+            let mut spiral = Spiral3D::new(start.0, start.1, start.2);
+
+            for layer in 0..=6 {
+                let shell: Vec<_> = spiral.by_ref().take(shell_size(layer)).collect();
+                let unique: HashSet<_> = shell.iter().copied().collect();
+
+                assert_eq!(unique.len(), shell.len(), "duplicate cell in shell {layer}");
+                assert!(
+                    shell.iter().all(|&cell| chebyshev(cell, start) == layer),
+                    "cell outside shell {layer}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn never_repeats_a_cell() {
+        let cells: Vec<_> = Spiral3D::new(1, 2, 3).take(20_000).collect();
+        let unique: HashSet<_> = cells.iter().copied().collect();
+        assert_eq!(unique.len(), cells.len());
+    }
+
+    #[test]
+    fn distance_to_start_never_decreases() {
+        let start = (-4, 9, 2);
+        let distances: Vec<_> = Spiral3D::new(start.0, start.1, start.2)
+            .take(10_000)
+            .map(|cell| chebyshev(cell, start))
+            .collect();
+        assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
@@ -1,7 +1,12 @@
+pub mod battles_repo;
 pub mod buildings_repo;
 pub mod galaxies_repo;
+pub mod move_orders_repo;
 pub mod planets_repo;
 pub mod player_state_repo;
 pub mod players_repo;
+pub mod resources_repo;
+pub mod space_objects_repo;
 pub mod star_systems_repo;
 pub mod units_repo;
+pub mod worlds_repo;
@@ -0,0 +1,151 @@
+use crate::db::battle::BattleTickRow;
+use anyhow::Result;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+// Rounds are `?1` (an SQLite modifier such as '+60 seconds') apart, the first one that long
+// after the battle started
+const BATTLE_TICKS: &str = "
+    WITH ticks AS (
+        SELECT b.id, b.attacker_id, b.defender_id,
+               COALESCE(b.last_tick_at, b.started_at) AS previous_tick_at,
+               strftime('%Y-%m-%dT%H:%M:%fZ', COALESCE(b.last_tick_at, b.started_at), ?1)
+                   AS tick_at
+        FROM battles b
+        JOIN players p ON p.id = b.attacker_id
+        WHERE p.world_id = ?2
+    )";
+
+/// Battles of `world_id` with a round due by `now` (a stored timestamp), earliest first.
+pub async fn fetch_due_battle_ticks(
+    pool: &SqlitePool,
+    world_id: i64,
+    tick_interval: &str,
+    now: &str,
+    limit: i64,
+) -> Result<Vec<BattleTickRow>> {
+    let rows = sqlx::query_as::<_, BattleTickRow>(&format!(
+        "{BATTLE_TICKS}
+         SELECT * FROM ticks WHERE tick_at <= ?3 ORDER BY tick_at, id LIMIT ?4"
+    ))
+    .bind(tick_interval)
+    .bind(world_id)
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// When the next round of any battle of `world_id` is due.
+pub async fn fetch_next_battle_tick_at(
+    pool: &SqlitePool,
+    world_id: i64,
+    tick_interval: &str,
+) -> Result<Option<String>> {
+    let tick_at: Option<String> =
+        sqlx::query_scalar(&format!("{BATTLE_TICKS} SELECT MIN(tick_at) FROM ticks"))
+            .bind(tick_interval)
+            .bind(world_id)
+            .fetch_one(pool)
+            .await?;
+    Ok(tick_at)
+}
+
+/// Moves the battle's clock from `previous_tick_at` to `tick_at`, once: false if that round
+/// was already played (or the battle ended).
+pub async fn claim_battle_tick(
+    tx: &mut Transaction<'_, Sqlite>,
+    battle: &BattleTickRow,
+) -> Result<bool> {
+    let res = sqlx::query(
+        "UPDATE battles SET last_tick_at = ?
+         WHERE id = ? AND COALESCE(last_tick_at, started_at) = ?",
+    )
+    .bind(&battle.tick_at)
+    .bind(battle.id)
+    .bind(&battle.previous_tick_at)
+    .execute(&mut **tx)
+    .await?;
+    Ok(res.rows_affected() == 1)
+}
+
+/// Skips a round that cannot be played: the next one is then due an interval after `until`.
+pub async fn postpone_battle_tick(
+    pool: &SqlitePool,
+    battle: &BattleTickRow,
+    until: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE battles SET last_tick_at = ?
+         WHERE id = ? AND COALESCE(last_tick_at, started_at) = ?",
+    )
+    .bind(until)
+    .bind(battle.id)
+    .bind(&battle.previous_tick_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// Units of `b`'s two sides still fighting where it takes place, a tile or a point in space
+const BATTLE_UNITS: &str = "
+    u.in_battle = 1
+    AND u.player_id IN (b.attacker_id, b.defender_id)
+    AND (
+        (u.location_mode = 'planet_surface' AND EXISTS (
+            SELECT 1 FROM planet_tiles t
+            WHERE t.id = b.tile_id AND t.planet_id = u.planet_id
+              AND t.face = u.planet_face AND t.u = u.planet_u AND t.v = u.planet_v
+        ))
+        OR (u.location_mode = 'in_space' AND b.tile_id IS NULL
+            AND u.star_system_id = b.star_system_id
+            AND u.star_system_x = b.space_x AND u.star_system_y = b.space_y
+            AND u.star_system_z IS b.space_z)
+    )";
+
+/// Ends the battle with `outcome`: its report is written, with what each side brought, its
+/// units are free again and the battle row is deleted. False if it had already ended.
+pub async fn end_battle(
+    tx: &mut Transaction<'_, Sqlite>,
+    battle_id: i64,
+    outcome: &str,
+) -> Result<bool> {
+    let snapshot = |side: &str| {
+        format!(
+            "(SELECT json_group_array(json_object('unit_type', u.unit_type, 'sent', u.count, 'lost', 0))
+              FROM units u WHERE u.player_id = b.{side} AND {BATTLE_UNITS})"
+        )
+    };
+    let res = sqlx::query(&format!(
+        "INSERT INTO battle_reports (
+             battle_id, tile_id, star_system_id, space_x, space_y, space_z,
+             attacker_id, defender_id, outcome,
+             attacker_units_snapshot, defender_units_snapshot, started_at)
+         SELECT b.id, b.tile_id, b.star_system_id, b.space_x, b.space_y, b.space_z,
+                b.attacker_id, b.defender_id, ?, {}, {}, b.started_at
+         FROM battles b WHERE b.id = ?",
+        snapshot("attacker_id"),
+        snapshot("defender_id")
+    ))
+    .bind(outcome)
+    .bind(battle_id)
+    .execute(&mut **tx)
+    .await?;
+    if res.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(&format!(
+        "UPDATE units SET in_battle = 0
+         WHERE id IN (SELECT u.id FROM units u JOIN battles b ON b.id = ? WHERE {BATTLE_UNITS})"
+    ))
+    .bind(battle_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query("DELETE FROM battles WHERE id = ?")
+        .bind(battle_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(true)
+}
@@ -1,4 +1,5 @@
-use crate::models::BuildingRow;
+use crate::db::building::DueConstructionRow;
+use crate::db::building::BuildingRow;
 use anyhow::Result;
 use sqlx::{Sqlite, SqlitePool, Transaction};
 
@@ -32,3 +33,140 @@ pub async fn create_building(
 
     Ok(res.last_insert_rowid())
 }
+
+/// Standing buildings of every player on a planet.
+pub async fn fetch_planet_buildings(pool: &SqlitePool, planet_id: i64) -> Result<Vec<BuildingRow>> {
+    let buildings = sqlx::query_as::<_, BuildingRow>(
+        "SELECT b.* FROM buildings b
+         JOIN planet_tiles t ON t.id = b.tile_id
+         WHERE t.planet_id = ? AND b.destroyed_at IS NULL",
+    )
+    .bind(planet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(buildings)
+}
+
+/// Buildings of `world_id` whose construction finished by `now` (a stored timestamp), earliest
+/// first.
+pub async fn fetch_due_constructions(
+    pool: &SqlitePool,
+    world_id: i64,
+    now: &str,
+    limit: i64,
+) -> Result<Vec<DueConstructionRow>> {
+    let rows = sqlx::query_as::<_, DueConstructionRow>(
+        "SELECT b.id, b.player_id, b.construction_done_at FROM buildings b
+         JOIN players p ON p.id = b.player_id
+         WHERE p.world_id = ? AND b.construction_done_at <= ?
+         ORDER BY b.construction_done_at, b.id
+         LIMIT ?",
+    )
+    .bind(world_id)
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Earliest end of a construction in progress in `world_id`.
+pub async fn fetch_next_construction_done_at(
+    pool: &SqlitePool,
+    world_id: i64,
+) -> Result<Option<String>> {
+    let done_at: Option<String> = sqlx::query_scalar(
+        "SELECT MIN(b.construction_done_at) FROM buildings b
+         JOIN players p ON p.id = b.player_id
+         WHERE p.world_id = ?",
+    )
+    .bind(world_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(done_at)
+}
+
+/// Marks the building as built, once: false if that construction was already completed.
+pub async fn claim_construction(
+    tx: &mut Transaction<'_, Sqlite>,
+    building_id: i64,
+    done_at: &str,
+) -> Result<bool> {
+    let res = sqlx::query(
+        "UPDATE buildings
+         SET construction_done_at = NULL,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+         WHERE id = ? AND construction_done_at = ?",
+    )
+    .bind(building_id)
+    .bind(done_at)
+    .execute(&mut **tx)
+    .await?;
+    Ok(res.rows_affected() == 1)
+}
+
+pub async fn postpone_construction(
+    pool: &SqlitePool,
+    building_id: i64,
+    done_at: &str,
+    until: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE buildings SET construction_done_at = ? WHERE id = ? AND construction_done_at = ?",
+    )
+    .bind(until)
+    .bind(building_id)
+    .bind(done_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn set_flight_state(
+    tx: &mut Transaction<'_, Sqlite>,
+    building_id: i64,
+    flight_state: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE buildings
+         SET flight_state = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+         WHERE id = ?",
+    )
+    .bind(flight_state)
+    .bind(building_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Grounds a flying building on the stored tile (`planet_id`, `face`, `u`, `v`). Fails if the
+/// tile was never stored or already holds a building.
+pub async fn land_building(
+    tx: &mut Transaction<'_, Sqlite>,
+    building_id: i64,
+    planet_id: i64,
+    face: i32,
+    u: i32,
+    v: i32,
+) -> Result<()> {
+    let res = sqlx::query(
+        "UPDATE buildings
+         SET flight_state = 'grounded',
+             tile_id = (SELECT id FROM planet_tiles
+                        WHERE planet_id = ? AND face = ? AND u = ? AND v = ?),
+             updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+         WHERE id = ?",
+    )
+    .bind(planet_id)
+    .bind(face)
+    .bind(u)
+    .bind(v)
+    .bind(building_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if res.rows_affected() != 1 {
+        anyhow::bail!("building {building_id} not found");
+    }
+    Ok(())
+}
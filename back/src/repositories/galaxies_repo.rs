@@ -1,23 +1,64 @@
-use crate::models::GalaxyRow;
+use crate::db::galaxy::GalaxyPresenceRow;
 use anyhow::Result;
 use sqlx::SqlitePool;
 
-pub async fn insert_galaxy(
-    tx: &mut Transaction<'_, Sqlite>,
-    seed: i64,
-    coords: (i64, i64, i64),
-) -> Result<(GalaxyRow)> {
-    let (x, y, z) = coords;
+type Cell = (i32, i32, i32);
 
-    let id: i64 = sqlx::query_scalar(
-        "INSERT INTO galaxies (seed, x, y, z) VALUES (?, ?, ?, ?) ON CONFLICT DO UPDATE SET id=id RETURNING id"
+pub async fn fetch_galaxy_id(
+    pool: &SqlitePool,
+    world_id: i64,
+    position: Cell,
+) -> Result<Option<i64>> {
+    let id = sqlx::query_scalar(
+        "SELECT id FROM galaxies WHERE world_id = ? AND x = ? AND y = ? AND z = ?",
     )
-    .bind(seed)
-    .bind(x)
-    .bind(y)
-    .bind(z)
-    .fetch_one(&mut **tx)
+    .bind(world_id)
+    .bind(position.0)
+    .bind(position.1)
+    .bind(position.2)
+    .fetch_optional(pool)
     .await?;
-
     Ok(id)
 }
+
+/// Persisted galaxies inside the inclusive `min..=max` box of the world's cosmic grid.
+pub async fn fetch_galaxies_in_box(
+    pool: &SqlitePool,
+    world_id: i64,
+    player_id: i64,
+    min: Cell,
+    max: Cell,
+) -> Result<Vec<GalaxyPresenceRow>> {
+    let rows = sqlx::query_as::<_, GalaxyPresenceRow>(
+        r#"
+        SELECT g.id, g.x, g.y, g.z,
+               EXISTS (
+                   SELECT 1 FROM star_systems s
+                   JOIN planets p ON p.star_system_id = s.id
+                   JOIN planet_tiles t ON t.planet_id = p.id
+                   WHERE s.galaxy_id = g.id AND t.owner_player_id = ?1
+               ) OR EXISTS (
+                   SELECT 1 FROM units u
+                   LEFT JOIN planets p ON p.id = COALESCE(u.planet_id, u.orbit_planet_id)
+                   JOIN star_systems s ON s.id = COALESCE(u.star_system_id, p.star_system_id)
+                   WHERE s.galaxy_id = g.id AND u.player_id = ?1
+               ) AS has_presence
+        FROM galaxies g
+        WHERE g.world_id = ?2
+          AND g.x BETWEEN ?3 AND ?4
+          AND g.y BETWEEN ?5 AND ?6
+          AND g.z BETWEEN ?7 AND ?8
+        "#,
+    )
+    .bind(player_id)
+    .bind(world_id)
+    .bind(min.0)
+    .bind(max.0)
+    .bind(min.1)
+    .bind(max.1)
+    .bind(min.2)
+    .bind(max.2)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
@@ -0,0 +1,233 @@
+use crate::db::move_order::{MoveOrderRow, MoveRouteRow, NewUnitMoveOrder, TileWalkLeg};
+use anyhow::Result;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+// Columns of MoveOrderRow but its player_id, which each query joins in
+const MOVE_ORDER_COLUMNS: &str = "
+    o.id, o.unit_id, o.building_id, o.mover_type, o.move_type,
+    o.from_planet_id, o.to_planet_id, o.to_planet_face, o.to_planet_u, o.to_planet_v,
+    o.from_star_system_id, o.from_space_x, o.from_space_y, o.from_space_z,
+    o.to_star_system_id, o.to_space_x, o.to_space_y, o.to_space_z,
+    o.start_time, o.arrival_time, o.route_id, o.attempts";
+
+// Orders of one world: the mover's owner plays in it
+const WORLD_MOVE_ORDERS: &str = "
+    FROM move_orders o
+    LEFT JOIN units u ON u.id = o.unit_id
+    LEFT JOIN buildings b ON b.id = o.building_id
+    JOIN players p ON p.id = COALESCE(u.player_id, b.player_id)
+    WHERE p.world_id = ?";
+
+/// Orders of `world_id` arrived by `now_ms`, earliest first.
+pub async fn fetch_due_move_orders(
+    pool: &SqlitePool,
+    world_id: i64,
+    now_ms: i64,
+    limit: i64,
+) -> Result<Vec<MoveOrderRow>> {
+    let orders = sqlx::query_as::<_, MoveOrderRow>(&format!(
+        "SELECT {MOVE_ORDER_COLUMNS}, p.id AS player_id {WORLD_MOVE_ORDERS}
+           AND o.arrival_time <= ?
+         ORDER BY o.arrival_time, o.id
+         LIMIT ?"
+    ))
+    .bind(world_id)
+    .bind(now_ms)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(orders)
+}
+
+/// Earliest arrival among the pending orders of `world_id`.
+pub async fn fetch_next_arrival_time(pool: &SqlitePool, world_id: i64) -> Result<Option<i64>> {
+    let arrival: Option<i64> =
+        sqlx::query_scalar(&format!("SELECT MIN(o.arrival_time) {WORLD_MOVE_ORDERS}"))
+            .bind(world_id)
+            .fetch_one(pool)
+            .await?;
+    Ok(arrival)
+}
+
+/// Removes the order so that its arrival is applied once: false if it was already gone.
+pub async fn claim_move_order(tx: &mut Transaction<'_, Sqlite>, order_id: i64) -> Result<bool> {
+    let res = sqlx::query("DELETE FROM move_orders WHERE id = ?")
+        .bind(order_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(res.rows_affected() == 1)
+}
+
+/// Retries a failed order at `until_ms`, counting the failure.
+pub async fn postpone_move_order(pool: &SqlitePool, order_id: i64, until_ms: i64) -> Result<()> {
+    sqlx::query("UPDATE move_orders SET arrival_time = ?, attempts = attempts + 1 WHERE id = ?")
+        .bind(until_ms)
+        .bind(order_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// The space flight the unit is on, if any.
+pub async fn fetch_unit_space_travel(
+    pool: &SqlitePool,
+    unit_id: i64,
+) -> Result<Option<MoveOrderRow>> {
+    let order = sqlx::query_as::<_, MoveOrderRow>(&format!(
+        "SELECT {MOVE_ORDER_COLUMNS}, u.player_id
+         FROM move_orders o
+         JOIN units u ON u.id = o.unit_id
+         WHERE o.unit_id = ? AND o.move_type = 'space_travel'"
+    ))
+    .bind(unit_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(order)
+}
+
+/// Orders pending for the unit, route legs included.
+pub async fn count_unit_move_orders(tx: &mut Transaction<'_, Sqlite>, unit_id: i64) -> Result<i64> {
+    let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM move_orders WHERE unit_id = ?")
+        .bind(unit_id)
+        .fetch_one(&mut **tx)
+        .await?;
+    Ok(pending)
+}
+
+pub async fn insert_unit_move_order(
+    tx: &mut Transaction<'_, Sqlite>,
+    order: &NewUnitMoveOrder,
+) -> Result<i64> {
+    let [from_x, from_y, from_z] = order.from_space.map_or([None; 3], |p| p.map(Some));
+    let [to_x, to_y, to_z] = order.to_space.map_or([None; 3], |p| p.map(Some));
+    let (to_face, to_u, to_v) = order.to_tile.map_or((None, None, None), |(face, u, v)| {
+        (Some(face), Some(u), Some(v))
+    });
+
+    let res = sqlx::query(
+        "INSERT INTO move_orders (
+             unit_id, mover_type, move_type,
+             from_planet_id, from_star_system_id, from_space_x, from_space_y, from_space_z,
+             to_planet_id, to_planet_face, to_planet_u, to_planet_v,
+             to_star_system_id, to_space_x, to_space_y, to_space_z,
+             start_time, arrival_time
+         ) VALUES (?, 'unit', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(order.unit_id)
+    .bind(order.move_type)
+    .bind(order.from_planet_id)
+    .bind(order.from_star_system_id)
+    .bind(from_x)
+    .bind(from_y)
+    .bind(from_z)
+    .bind(order.to_planet_id)
+    .bind(to_face)
+    .bind(to_u)
+    .bind(to_v)
+    .bind(order.to_star_system_id)
+    .bind(to_x)
+    .bind(to_y)
+    .bind(to_z)
+    .bind(order.start_time)
+    .bind(order.arrival_time)
+    .execute(&mut **tx)
+    .await?;
+    Ok(res.last_insert_rowid())
+}
+
+pub async fn create_move_route(
+    tx: &mut Transaction<'_, Sqlite>,
+    unit_id: i64,
+    planet_id: i64,
+    origin: (i32, i32, i32),
+) -> Result<MoveRouteRow> {
+    let route = sqlx::query_as::<_, MoveRouteRow>(
+        "INSERT INTO move_routes (unit_id, planet_id, origin_face, origin_u, origin_v)
+         VALUES (?, ?, ?, ?, ?)
+         RETURNING id, unit_id, planet_id, origin_face, origin_u, origin_v",
+    )
+    .bind(unit_id)
+    .bind(planet_id)
+    .bind(origin.0)
+    .bind(origin.1)
+    .bind(origin.2)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(route)
+}
+
+pub async fn insert_tile_walk_leg(
+    tx: &mut Transaction<'_, Sqlite>,
+    route: &MoveRouteRow,
+    leg: &TileWalkLeg,
+) -> Result<i64> {
+    let res = sqlx::query(
+        "INSERT INTO move_orders (
+             unit_id, mover_type, move_type, route_id, leg_index,
+             from_planet_id, from_planet_face, from_planet_u, from_planet_v,
+             to_planet_id, to_planet_face, to_planet_u, to_planet_v,
+             start_time, arrival_time
+         ) VALUES (?, 'unit', 'tile_walk', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(route.unit_id)
+    .bind(route.id)
+    .bind(leg.leg_index)
+    .bind(route.planet_id)
+    .bind(leg.from.0)
+    .bind(leg.from.1)
+    .bind(leg.from.2)
+    .bind(route.planet_id)
+    .bind(leg.to.0)
+    .bind(leg.to.1)
+    .bind(leg.to.2)
+    .bind(leg.start_time)
+    .bind(leg.arrival_time)
+    .execute(&mut **tx)
+    .await?;
+    Ok(res.last_insert_rowid())
+}
+
+/// The route the unit is walking, if any.
+pub async fn fetch_unit_route(
+    tx: &mut Transaction<'_, Sqlite>,
+    unit_id: i64,
+) -> Result<Option<MoveRouteRow>> {
+    let route = sqlx::query_as::<_, MoveRouteRow>(
+        "SELECT id, unit_id, planet_id, origin_face, origin_u, origin_v
+         FROM move_routes WHERE unit_id = ?
+         ORDER BY id DESC LIMIT 1",
+    )
+    .bind(unit_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+    Ok(route)
+}
+
+/// Drops the route with its legs not walked yet.
+pub async fn delete_move_route(tx: &mut Transaction<'_, Sqlite>, route_id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM move_orders WHERE route_id = ?")
+        .bind(route_id)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query("DELETE FROM move_routes WHERE id = ?")
+        .bind(route_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Drops the route once its last leg was walked.
+pub async fn delete_move_route_if_walked(
+    tx: &mut Transaction<'_, Sqlite>,
+    route_id: i64,
+) -> Result<()> {
+    sqlx::query(
+        "DELETE FROM move_routes
+         WHERE id = ? AND NOT EXISTS (SELECT 1 FROM move_orders WHERE route_id = ?)",
+    )
+    .bind(route_id)
+    .bind(route_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
@@ -1,11 +1,70 @@
-use crate::models::PlayerRow;
+use crate::db::planet::{PlanetLocationRow, PlanetTileRow, TileInfluenceRow};
 use anyhow::Result;
 use sqlx::SqlitePool;
 
-pub async fn fetch_player_by_id(pool: &SqlitePool, player_id: i64) -> Result<PlayerRow> {
-    let player = sqlx::query_as::<_, PlayerRow>("SELECT * FROM players WHERE id = ?")
-        .bind(player_id)
-        .fetch_one(pool)
-        .await?;
-    Ok(player)
+/// A planet of `world_id`, with its system and galaxy grid positions.
+pub async fn fetch_planet_location(
+    pool: &SqlitePool,
+    world_id: i64,
+    planet_id: i64,
+) -> Result<Option<PlanetLocationRow>> {
+    let planet = sqlx::query_as::<_, PlanetLocationRow>(
+        r#"
+        SELECT p.id, p.star_system_id, p.seed, p.orbit_index, p.moon_index,
+               s.x AS system_x, s.y AS system_y, s.z AS system_z,
+               g.x AS galaxy_x, g.y AS galaxy_y, g.z AS galaxy_z
+        FROM planets p
+        JOIN star_systems s ON s.id = p.star_system_id
+        JOIN galaxies g ON g.id = s.galaxy_id
+        WHERE p.id = ? AND g.world_id = ?
+        "#,
+    )
+    .bind(planet_id)
+    .bind(world_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(planet)
+}
+
+pub async fn fetch_planet_tiles(pool: &SqlitePool, planet_id: i64) -> Result<Vec<PlanetTileRow>> {
+    let tiles = sqlx::query_as::<_, PlanetTileRow>(
+        "SELECT id, face, u, v, owner_player_id FROM planet_tiles WHERE planet_id = ?",
+    )
+    .bind(planet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(tiles)
+}
+
+pub async fn fetch_planet_influence(
+    pool: &SqlitePool,
+    planet_id: i64,
+) -> Result<Vec<TileInfluenceRow>> {
+    let influence = sqlx::query_as::<_, TileInfluenceRow>(
+        "SELECT i.tile_id, i.player_id, i.score
+         FROM tile_influence i
+         JOIN planet_tiles t ON t.id = i.tile_id
+         WHERE t.planet_id = ?",
+    )
+    .bind(planet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(influence)
+}
+
+/// Players owning tiles or standing buildings on the planet.
+pub async fn fetch_planet_holder_ids(pool: &SqlitePool, planet_id: i64) -> Result<Vec<i64>> {
+    let holders = sqlx::query_scalar(
+        "SELECT owner_player_id FROM planet_tiles
+         WHERE planet_id = ?1 AND owner_player_id IS NOT NULL
+         UNION
+         SELECT b.player_id
+         FROM buildings b
+         JOIN planet_tiles t ON t.id = b.tile_id
+         WHERE t.planet_id = ?1 AND b.destroyed_at IS NULL",
+    )
+    .bind(planet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(holders)
 }
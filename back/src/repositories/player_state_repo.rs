@@ -1,17 +1,55 @@
-use crate::game::proc_gen::StartingLocation;
-use crate::repositories::{buildings_repo, units_repo};
+use crate::db::planet::OccupiedPlanetRow;
+use crate::game::game_init::StartingLocation;
+use crate::game::resource::Resource;
+use crate::repositories::{buildings_repo, resources_repo, units_repo};
 use anyhow::Result;
-use sqlx::{Sqlite, Transaction};
+use sqlx::{Executor, Sqlite, Transaction};
+
+// Fuel a new player starts with, as no building produces deuterium yet: 400 AU of scout hops
+// within a system, or a jump of 8 cells
+const STARTING_DEUTERIUM: f64 = 200.0;
+
+/// Every persisted planet of the world, flagged when a player calls it home.
+pub async fn fetch_occupied_planets<'e, E>(
+    executor: E,
+    world_id: i64,
+) -> Result<Vec<OccupiedPlanetRow>>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let planets = sqlx::query_as::<_, OccupiedPlanetRow>(
+        r#"
+        SELECT g.x AS galaxy_x, g.y AS galaxy_y, g.z AS galaxy_z,
+               s.x AS system_x, s.y AS system_y, s.z AS system_z,
+               p.orbit_index, p.moon_index,
+               EXISTS (SELECT 1 FROM players pl WHERE pl.home_planet_id = p.id)
+               OR EXISTS (
+                   SELECT 1 FROM planet_tiles t
+                   WHERE t.planet_id = p.id AND t.owner_player_id IS NOT NULL
+               ) AS is_home
+        FROM planets p
+        JOIN star_systems s ON s.id = p.star_system_id
+        JOIN galaxies g ON g.id = s.galaxy_id
+        WHERE g.world_id = ?
+        "#,
+    )
+    .bind(world_id)
+    .fetch_all(executor)
+    .await?;
+    Ok(planets)
+}
 
 pub async fn insert_initial_player_state(
     tx: &mut Transaction<'_, Sqlite>,
+    world_id: i64,
     player_id: i64,
     spawn: &StartingLocation,
 ) -> Result<()> {
     // 1. Get or Create Galaxy
     let galaxy_id = match sqlx::query_scalar::<_, i64>(
-        "SELECT id FROM galaxies WHERE x = ? AND y = ? AND z = ?",
+        "SELECT id FROM galaxies WHERE world_id = ? AND x = ? AND y = ? AND z = ?",
     )
+    .bind(world_id)
     .bind(spawn.galaxy.position.0)
     .bind(spawn.galaxy.position.1)
     .bind(spawn.galaxy.position.2)
@@ -19,14 +57,17 @@ pub async fn insert_initial_player_state(
     .await?
     {
         Some(id) => id,
-        None => sqlx::query("INSERT INTO galaxies (seed, x, y, z) VALUES (?, ?, ?, ?)")
-            .bind(spawn.galaxy.seed as i64)
-            .bind(spawn.galaxy.position.0)
-            .bind(spawn.galaxy.position.1)
-            .bind(spawn.galaxy.position.2)
-            .execute(&mut **tx)
-            .await?
-            .last_insert_rowid(),
+        None => {
+            sqlx::query("INSERT INTO galaxies (world_id, seed, x, y, z) VALUES (?, ?, ?, ?, ?)")
+                .bind(world_id)
+                .bind(spawn.galaxy.seed as i64)
+                .bind(spawn.galaxy.position.0)
+                .bind(spawn.galaxy.position.1)
+                .bind(spawn.galaxy.position.2)
+                .execute(&mut **tx)
+                .await?
+                .last_insert_rowid()
+        }
     };
 
     // 2. Get or Create Star System
@@ -54,35 +95,33 @@ pub async fn insert_initial_player_state(
         .last_insert_rowid(),
     };
 
-    // 3. Get or Create Planet
-    let planet_id = match sqlx::query_scalar::<_, i64>(
-        "SELECT id FROM planets WHERE star_system_id = ? AND x = ? AND y = ?",
+    // 3. Create the home Planet: the allocator only hands out planets nobody has touched, so an
+    //    existing row means a concurrent sign-up won and the unique index aborts this one
+    let planet_id = sqlx::query(
+        "INSERT INTO planets (star_system_id, seed, x, y, subdivision, orbit_index, moon_index)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(star_system_id)
+    .bind(spawn.planet.seed as i64)
     .bind(spawn.planet.planet_pos.0)
     .bind(spawn.planet.planet_pos.1)
-    .fetch_optional(&mut **tx)
+    .bind(spawn.planet.subdivision as i32)
+    .bind(spawn.planet.orbit_index as i64)
+    .bind(spawn.planet.moon_index.map(|index| index as i64))
+    .execute(&mut **tx)
     .await?
-    {
-        Some(id) => id,
-        None => sqlx::query(
-            "INSERT INTO planets (star_system_id, seed, x, y, subdivision) VALUES (?, ?, ?, ?, ?)",
-        )
-        .bind(star_system_id)
-        .bind(spawn.planet.seed as i64)
-        .bind(spawn.planet.planet_pos.0)
-        .bind(spawn.planet.planet_pos.1)
-        .bind(spawn.planet.subdivision as i32)
+    .last_insert_rowid();
+
+    sqlx::query("UPDATE players SET home_planet_id = ? WHERE id = ?")
+        .bind(planet_id)
+        .bind(player_id)
         .execute(&mut **tx)
-        .await?
-        .last_insert_rowid(),
-    };
+        .await?;
 
     // 4. Claim Tile for Player
     let tile_id = sqlx::query(
-        "INSERT INTO planet_tiles (planet_id, face, u, v, tile_type, yield_quality, rare_deposit, owner_player_id)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-         ON CONFLICT(planet_id, face, u, v) DO UPDATE SET owner_player_id = excluded.owner_player_id",
+        "INSERT INTO planet_tiles (planet_id, face, u, v, tile_type, yield_quality, rare_deposit, deposit_richness, owner_player_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(planet_id)
     .bind(spawn.tile.face as i32)
@@ -90,7 +129,8 @@ pub async fn insert_initial_player_state(
     .bind(spawn.tile.v as i32)
     .bind(spawn.tile.tile_type.as_str())
     .bind(spawn.tile.yield_quality as f64)
-    .bind(spawn.tile.rare_deposit)
+    .bind(spawn.tile.rare_deposit.map(|deposit| deposit.resource.as_str()))
+    .bind(spawn.tile.rare_deposit.map(|deposit| deposit.richness.level()))
     .bind(player_id)
     .execute(&mut **tx)
     .await?
@@ -105,12 +145,14 @@ pub async fn insert_initial_player_state(
         player_id,
         "scout",
         planet_id,
-        spawn.tile.face,
-        spawn.tile.u,
-        spawn.tile.v,
+        (spawn.tile.face, spawn.tile.u, spawn.tile.v),
         100,
     )
     .await?;
 
+    // 7. Fuel for the scout's first flights
+    resources_repo::add_player_resource(tx, player_id, Resource::Deuterium, STARTING_DEUTERIUM)
+        .await?;
+
     Ok(())
 }
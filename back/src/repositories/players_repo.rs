@@ -1,11 +1,36 @@
-use crate::models::PlayerRow;
+use crate::db::player::PlayerRow;
 use anyhow::Result;
-use sqlx::SqlitePool;
+use sqlx::{Sqlite, SqlitePool, Transaction};
 
 pub async fn fetch_player_by_id(pool: &SqlitePool, player_id: i64) -> Result<PlayerRow> {
-    let player = sqlx::query_as::<_, PlayerRow>("SELECT * FROM players WHERE id = ?")
+    let player = sqlx::query_as::<_, PlayerRow>("SELECT username FROM players WHERE id = ?")
         .bind(player_id)
         .fetch_one(pool)
         .await?;
     Ok(player)
 }
+
+/// Username of a player of `world_id`.
+pub async fn fetch_username_in_world(
+    pool: &SqlitePool,
+    world_id: i64,
+    player_id: i64,
+) -> Result<Option<String>> {
+    let username = sqlx::query_scalar("SELECT username FROM players WHERE id = ? AND world_id = ?")
+        .bind(player_id)
+        .bind(world_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(username)
+}
+
+pub async fn fetch_home_planet_id(
+    tx: &mut Transaction<'_, Sqlite>,
+    player_id: i64,
+) -> Result<Option<i64>> {
+    let home: Option<i64> = sqlx::query_scalar("SELECT home_planet_id FROM players WHERE id = ?")
+        .bind(player_id)
+        .fetch_one(&mut **tx)
+        .await?;
+    Ok(home)
+}
@@ -0,0 +1,134 @@
+use crate::game::resource::Resource;
+use anyhow::Result;
+use sqlx::{Sqlite, Transaction};
+
+/// Takes `amount` of `resource` from the player's pool: false, and nothing taken, if the pool
+/// holds less.
+pub async fn spend_player_resource(
+    tx: &mut Transaction<'_, Sqlite>,
+    player_id: i64,
+    resource: Resource,
+    amount: f64,
+) -> Result<bool> {
+    let res = sqlx::query(
+        "UPDATE player_resources
+         SET amount = amount - ?1,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+         WHERE player_id = ?2 AND resource_type = ?3 AND amount >= ?1",
+    )
+    .bind(amount)
+    .bind(player_id)
+    .bind(resource.as_str())
+    .execute(&mut **tx)
+    .await?;
+    Ok(res.rows_affected() == 1)
+}
+
+/// Room left in the player's pool of `resource` below its cap.
+pub async fn fetch_free_storage(
+    tx: &mut Transaction<'_, Sqlite>,
+    player_id: i64,
+    resource: Resource,
+) -> Result<f64> {
+    create_player_resource(tx, player_id, resource).await?;
+    let room: f64 = sqlx::query_scalar(
+        "SELECT MAX(cap - amount, 0.0) FROM player_resources
+         WHERE player_id = ? AND resource_type = ?",
+    )
+    .bind(player_id)
+    .bind(resource.as_str())
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(room)
+}
+
+/// Adds `amount` of `resource` to the player's pool, up to its cap: the rest is lost.
+pub async fn add_player_resource(
+    tx: &mut Transaction<'_, Sqlite>,
+    player_id: i64,
+    resource: Resource,
+    amount: f64,
+) -> Result<()> {
+    create_player_resource(tx, player_id, resource).await?;
+    sqlx::query(
+        "UPDATE player_resources
+         SET amount = MIN(amount + ?, cap),
+             updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+         WHERE player_id = ? AND resource_type = ?",
+    )
+    .bind(amount)
+    .bind(player_id)
+    .bind(resource.as_str())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// An empty pool with the default cap, unless the player already has one.
+async fn create_player_resource(
+    tx: &mut Transaction<'_, Sqlite>,
+    player_id: i64,
+    resource: Resource,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO player_resources (player_id, resource_type) VALUES (?, ?)
+         ON CONFLICT(player_id, resource_type) DO NOTHING",
+    )
+    .bind(player_id)
+    .bind(resource.as_str())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_db;
+
+    async fn amount(tx: &mut Transaction<'_, Sqlite>, player_id: i64) -> f64 {
+        sqlx::query_scalar(
+            "SELECT amount FROM player_resources WHERE player_id = ? AND resource_type = 'iron'",
+        )
+        .bind(player_id)
+        .fetch_one(&mut **tx)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pools_stop_at_their_cap() {
+        let pool = test_db::memory_pool().await;
+        let world = test_db::create_world(&pool).await;
+        let player_id = test_db::create_player(&pool, &world, "hauler").await;
+        let mut tx = pool.begin().await.unwrap();
+
+        assert_eq!(
+            fetch_free_storage(&mut tx, player_id, Resource::Iron)
+                .await
+                .unwrap(),
+            1000.0
+        );
+        // A first haul beyond the cap is cut too
+        add_player_resource(&mut tx, player_id, Resource::Iron, 1500.0)
+            .await
+            .unwrap();
+        assert_eq!(amount(&mut tx, player_id).await, 1000.0);
+        assert_eq!(
+            fetch_free_storage(&mut tx, player_id, Resource::Iron)
+                .await
+                .unwrap(),
+            0.0
+        );
+
+        assert!(
+            spend_player_resource(&mut tx, player_id, Resource::Iron, 300.0)
+                .await
+                .unwrap()
+        );
+        add_player_resource(&mut tx, player_id, Resource::Iron, 500.0)
+            .await
+            .unwrap();
+        assert_eq!(amount(&mut tx, player_id).await, 1000.0);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::db::space_object::{NewAsteroidExtraction, SpaceObjectStateRow};
+use anyhow::Result;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// What players did to the generated asteroids and anomalies of a system. Objects without a
+/// row are untouched.
+pub async fn fetch_generated_object_states(
+    pool: &SqlitePool,
+    star_system_id: i64,
+) -> Result<Vec<SpaceObjectStateRow>> {
+    let rows = sqlx::query_as::<_, SpaceObjectStateRow>(
+        "SELECT object_type, object_index, owner_player_id, extracted, despawned_at
+         FROM space_objects
+         WHERE star_system_id = ? AND object_index IS NOT NULL",
+    )
+    .bind(star_system_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Books a haul mined from a generated asteroid; its miner becomes the owner. The first
+/// extraction creates the asteroid's row, and the last one despawns it. False, and nothing
+/// booked, if the asteroid changed since the haul was planned.
+pub async fn record_asteroid_extraction(
+    tx: &mut Transaction<'_, Sqlite>,
+    extraction: &NewAsteroidExtraction,
+) -> Result<bool> {
+    let res = sqlx::query(
+        "INSERT INTO space_objects
+           (star_system_id, object_type, object_index, owner_player_id, extracted, despawned_at)
+         VALUES (?1, 'asteroid', ?2, ?3, ?4,
+                 CASE WHEN ?5 THEN strftime('%Y-%m-%dT%H:%M:%fZ','now') END)
+         ON CONFLICT(star_system_id, object_type, object_index) DO UPDATE SET
+           owner_player_id = excluded.owner_player_id,
+           extracted = extracted + excluded.extracted,
+           despawned_at = excluded.despawned_at
+         WHERE extracted = ?6 AND despawned_at IS NULL",
+    )
+    .bind(extraction.star_system_id)
+    .bind(extraction.asteroid_index as i64)
+    .bind(extraction.player_id)
+    .bind(extraction.amount)
+    .bind(extraction.mined_out)
+    .bind(extraction.extracted_before)
+    .execute(&mut **tx)
+    .await?;
+    Ok(res.rows_affected() == 1)
+}
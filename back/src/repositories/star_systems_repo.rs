@@ -1,52 +1,101 @@
-use crate::models::StarSystemRow;
+use crate::db::star_system::{StarSystemCellRow, SystemPresenceRow};
 use anyhow::Result;
 use sqlx::SqlitePool;
 
-/// Persists the generated initial entities inside a single transaction safely
-pub async fn insert_star_system(
-    tx: &mut Transaction<'_, Sqlite>,
-    seed: u64,
-    coords: (i64, i64, i64),
-    galaxy_id: i64,
-) -> Result<()> {
-    let (x, y, z) = coords;
+type Cell = (i32, i32, i32);
 
-    let id: i64 = sqlx::query_scalar(
-        "INSERT INTO star_systems (galaxy_id, seed, x, y, z) VALUES (?, ?, ?, ?, ?) ON CONFLICT DO UPDATE SET id=id RETURNING id"
+/// Per-player footprint of every persisted star system inside the inclusive `min..=max` box of
+/// a galaxy's grid: owned tiles, standing buildings, and units in orbit or in deep space.
+pub async fn fetch_system_presence_in_box(
+    pool: &SqlitePool,
+    galaxy_id: i64,
+    min: Cell,
+    max: Cell,
+) -> Result<Vec<SystemPresenceRow>> {
+    let rows = sqlx::query_as::<_, SystemPresenceRow>(
+        r#"
+        WITH systems AS (
+            SELECT id, x, y, z FROM star_systems
+            WHERE galaxy_id = ?1
+              AND x BETWEEN ?2 AND ?3
+              AND y BETWEEN ?4 AND ?5
+              AND z BETWEEN ?6 AND ?7
+        ),
+        presence AS (
+            SELECT p.star_system_id AS system_id, t.owner_player_id AS player_id,
+                   1 AS owned_tiles, 0 AS buildings, 0 AS fleet_units
+            FROM planet_tiles t
+            JOIN planets p ON p.id = t.planet_id
+            WHERE t.owner_player_id IS NOT NULL
+              AND p.star_system_id IN (SELECT id FROM systems)
+            UNION ALL
+            SELECT p.star_system_id, b.player_id, 0, 1, 0
+            FROM buildings b
+            JOIN planet_tiles t ON t.id = b.tile_id
+            JOIN planets p ON p.id = t.planet_id
+            WHERE b.destroyed_at IS NULL
+              AND p.star_system_id IN (SELECT id FROM systems)
+            UNION ALL
+            SELECT COALESCE(u.star_system_id, p.star_system_id), u.player_id, 0, 0, u.count
+            FROM units u
+            LEFT JOIN planets p ON p.id = u.orbit_planet_id
+            WHERE u.location_mode IN ('in_orbit', 'in_space')
+              AND COALESCE(u.star_system_id, p.star_system_id) IN (SELECT id FROM systems)
+        )
+        SELECT s.id AS star_system_id, s.x, s.y, s.z, pr.player_id,
+               COALESCE(SUM(pr.owned_tiles), 0) AS owned_tiles,
+               COALESCE(SUM(pr.buildings), 0) AS buildings,
+               COALESCE(SUM(pr.fleet_units), 0) AS fleet_units
+        FROM systems s
+        LEFT JOIN presence pr ON pr.system_id = s.id
+        GROUP BY s.id, pr.player_id
+        "#,
     )
-    .bind(g_id)
-    .bind(target_galaxy_id)
-    .bind(target_sx)
-    .bind(target_sy)
-    .bind(target_sz)
-    .fetch_one(&mut **tx)
+    .bind(galaxy_id)
+    .bind(min.0)
+    .bind(max.0)
+    .bind(min.1)
+    .bind(max.1)
+    .bind(min.2)
+    .bind(max.2)
+    .fetch_all(pool)
     .await?;
+    Ok(rows)
+}
 
-    let planet_id: i64 = sqlx::query_scalar(
-        "INSERT INTO planets (star_system_id, seed, x, y, subdivision) VALUES (?, ?, ?, ?, 0) ON CONFLICT DO UPDATE SET id=id RETURNING id"
+pub async fn fetch_star_system_id(
+    pool: &SqlitePool,
+    galaxy_id: i64,
+    position: Cell,
+) -> Result<Option<i64>> {
+    let id = sqlx::query_scalar(
+        "SELECT id FROM star_systems WHERE galaxy_id = ? AND x = ? AND y = ? AND z = ?",
     )
-    .bind(sys_id)
-    .bind(planet_seed as i64)
-    .bind(target_orbit as f64)
-    .bind(target_orbit as f64)
-    .fetch_one(&mut **tx)
+    .bind(galaxy_id)
+    .bind(position.0)
+    .bind(position.1)
+    .bind(position.2)
+    .fetch_optional(pool)
     .await?;
+    Ok(id)
+}
 
-    sqlx::query(
-        r#"
-        INSERT INTO units (
-            unit_type, is_squad, count, hp, player_id, in_battle,
-            location_mode, planet_id, planet_face, planet_u, planet_v
-        ) VALUES ('colonist_scout', 0, 1, 100, ?, 0, 'planet_surface', ?, ?, ?, ?)
-        "#,
+/// Galaxy and grid cell of a star system of `world_id`.
+pub async fn fetch_star_system_cell(
+    pool: &SqlitePool,
+    world_id: i64,
+    star_system_id: i64,
+) -> Result<Option<StarSystemCellRow>> {
+    let system = sqlx::query_as::<_, StarSystemCellRow>(
+        "SELECT s.id, s.galaxy_id, g.x AS galaxy_x, g.y AS galaxy_y, g.z AS galaxy_z,
+                s.x, s.y, s.z
+         FROM star_systems s
+         JOIN galaxies g ON g.id = s.galaxy_id
+         WHERE s.id = ? AND g.world_id = ?",
     )
-    .bind(player_id)
-    .bind(planet_id)
-    .bind(safe_face as i64)
-    .bind(safe_u as i64)
-    .bind(safe_v as i64)
-    .execute(&mut **tx)
+    .bind(star_system_id)
+    .bind(world_id)
+    .fetch_optional(pool)
     .await?;
-
-    Ok(())
+    Ok(system)
 }
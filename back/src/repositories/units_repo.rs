@@ -1,6 +1,6 @@
 use crate::db::unit::UnitRow;
 use anyhow::Result;
-use sqlx::{Sqlite, SqlitePool, Transaction};
+use sqlx::{Executor, Sqlite, SqlitePool, Transaction};
 
 pub async fn fetch_player_units(pool: &SqlitePool, player_id: i64) -> Result<Vec<UnitRow>> {
     let units = sqlx::query_as::<_, UnitRow>("SELECT * FROM units WHERE player_id = ?")
@@ -25,9 +25,7 @@ pub async fn create_surface_unit(
     player_id: i64,
     unit_type: &str,
     planet_id: i64,
-    face: u8,
-    u: u32,
-    v: u32,
+    (face, u, v): (u8, u32, u32),
     hp: i32,
 ) -> Result<i64> {
     let res = sqlx::query(
@@ -78,3 +76,108 @@ pub async fn create_surface_unit(
 
 //     Ok(())
 // }
+
+/// Units of every player standing on a planet's surface.
+pub async fn fetch_surface_units(pool: &SqlitePool, planet_id: i64) -> Result<Vec<UnitRow>> {
+    let units = sqlx::query_as::<_, UnitRow>(
+        "SELECT * FROM units WHERE planet_id = ? AND location_mode = 'planet_surface'",
+    )
+    .bind(planet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(units)
+}
+
+/// Puts the unit down on tile (`face`, `u`, `v`) of `planet_id`, wherever it was.
+pub async fn place_unit_on_surface(
+    tx: &mut Transaction<'_, Sqlite>,
+    unit_id: i64,
+    planet_id: i64,
+    face: i32,
+    u: i32,
+    v: i32,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE units
+         SET location_mode = 'planet_surface',
+             planet_id = ?, planet_face = ?, planet_u = ?, planet_v = ?,
+             orbit_planet_id = NULL, orbit_layer = NULL,
+             star_system_id = NULL, star_system_x = NULL, star_system_y = NULL, star_system_z = NULL
+         WHERE id = ?",
+    )
+    .bind(planet_id)
+    .bind(face)
+    .bind(u)
+    .bind(v)
+    .bind(unit_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Puts the unit in the `orbit_layer` ('low' or 'high') orbit of `planet_id`.
+pub async fn place_unit_in_orbit(
+    tx: &mut Transaction<'_, Sqlite>,
+    unit_id: i64,
+    planet_id: i64,
+    orbit_layer: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE units
+         SET location_mode = 'in_orbit',
+             orbit_planet_id = ?, orbit_layer = ?,
+             planet_id = NULL, planet_face = NULL, planet_u = NULL, planet_v = NULL,
+             star_system_id = NULL, star_system_x = NULL, star_system_y = NULL, star_system_z = NULL
+         WHERE id = ?",
+    )
+    .bind(planet_id)
+    .bind(orbit_layer)
+    .bind(unit_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Leaves the unit at `position` (in AU) in deep space of `star_system_id`.
+pub async fn place_unit_in_space(
+    tx: &mut Transaction<'_, Sqlite>,
+    unit_id: i64,
+    star_system_id: i64,
+    position: [f64; 3],
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE units
+         SET location_mode = 'in_space',
+             star_system_id = ?, star_system_x = ?, star_system_y = ?, star_system_z = ?,
+             planet_id = NULL, planet_face = NULL, planet_u = NULL, planet_v = NULL,
+             orbit_planet_id = NULL, orbit_layer = NULL
+         WHERE id = ?",
+    )
+    .bind(star_system_id)
+    .bind(position[0])
+    .bind(position[1])
+    .bind(position[2])
+    .bind(unit_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn fetch_unit<'e, E>(executor: E, unit_id: i64) -> Result<Option<UnitRow>>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let unit = sqlx::query_as::<_, UnitRow>("SELECT * FROM units WHERE id = ?")
+        .bind(unit_id)
+        .fetch_optional(executor)
+        .await?;
+    Ok(unit)
+}
+
+pub async fn is_unit_in_battle(tx: &mut Transaction<'_, Sqlite>, unit_id: i64) -> Result<bool> {
+    let in_battle: Option<i32> = sqlx::query_scalar("SELECT in_battle FROM units WHERE id = ?")
+        .bind(unit_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+    Ok(in_battle.is_some_and(|in_battle| in_battle != 0))
+}
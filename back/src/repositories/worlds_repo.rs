@@ -0,0 +1,41 @@
+use crate::db::world::WorldRow;
+use anyhow::Result;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+pub async fn fetch_world_by_name(pool: &SqlitePool, name: &str) -> Result<Option<WorldRow>> {
+    let world = sqlx::query_as::<_, WorldRow>("SELECT * FROM worlds WHERE name = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+    Ok(world)
+}
+
+pub async fn create_world(
+    pool: &SqlitePool,
+    name: &str,
+    seed: u64,
+    generation_version: u32,
+    ruleset: &str,
+) -> Result<WorldRow> {
+    let world = sqlx::query_as::<_, WorldRow>(
+        "INSERT INTO worlds (name, seed, generation_version, ruleset) VALUES (?, ?, ?, ?)
+         RETURNING *",
+    )
+    .bind(name)
+    .bind(seed as i64)
+    .bind(generation_version as i64)
+    .bind(ruleset)
+    .fetch_one(pool)
+    .await?;
+    Ok(world)
+}
+
+/// Takes SQLite's write lock for the rest of `tx` by writing first: transactions that claim
+/// the same world's spawn area then run one after the other.
+pub async fn lock_world_for_spawn(tx: &mut Transaction<'_, Sqlite>, world_id: i64) -> Result<()> {
+    sqlx::query("UPDATE worlds SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now') WHERE id = ?")
+        .bind(world_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
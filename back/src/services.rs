@@ -1 +1,8 @@
+pub mod diplomacy;
+pub mod events;
+pub mod flight;
 pub mod map;
+pub mod movement;
+pub mod planets;
+pub mod travel;
+pub mod universe;
@@ -0,0 +1,49 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::dto::event::GameEventDto;
+use crate::error::ApiError;
+use crate::game::world::World;
+use crate::repositories::players_repo;
+use crate::services::events::EventHub;
+
+// Longest message, in characters
+const MAX_MESSAGE_CHARS: usize = 2_000;
+
+/// Delivers a message from `from_player_id` to another player of the world, live over the
+/// event stream. Messages are not stored beyond the stream's replay buffer.
+pub async fn send_message(
+    pool: &SqlitePool,
+    world: &World,
+    events: &EventHub,
+    from_player_id: i64,
+    to_player_id: i64,
+    text: &str,
+) -> Result<(), ApiError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ApiError::BadRequest("Message is empty"));
+    }
+    if text.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(ApiError::BadRequest("Message is too long"));
+    }
+    if to_player_id == from_player_id {
+        return Err(ApiError::BadRequest("Cannot write to yourself"));
+    }
+
+    players_repo::fetch_username_in_world(pool, world.id, to_player_id)
+        .await?
+        .ok_or(ApiError::NotFound("Player not found"))?;
+    let from_username = players_repo::fetch_username_in_world(pool, world.id, from_player_id)
+        .await?
+        .ok_or(ApiError::NotFound("Player not found"))?;
+
+    events.publish(&GameEventDto::DiplomacyMessage {
+        from_player_id,
+        from_username,
+        to_player_id,
+        text: text.to_string(),
+        sent_at: Utc::now().timestamp_millis(),
+    })?;
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast;
+
+use crate::dto::event::GameEventDto;
+use crate::repositories::planets_repo;
+
+// Events kept for clients that reconnect: about the last minutes of a busy world
+const REPLAY_CAPACITY: usize = 1024;
+// Events a slow listener may fall behind by before it must resync
+const CHANNEL_CAPACITY: usize = 256;
+
+/// An event as pushed to listeners: its id, who may see it, and its JSON.
+#[derive(Debug)]
+pub struct PublishedEvent {
+    pub id: u64,
+    recipients: Vec<i64>,
+    pub json: String,
+}
+
+impl PublishedEvent {
+    pub fn is_for(&self, player_id: i64) -> bool {
+        self.recipients.contains(&player_id)
+    }
+}
+
+/// What a new listener starts from: the events it missed since `Last-Event-ID`, then the
+/// live channel. `gap` is set when some of the missed events are no longer kept.
+pub struct Subscription {
+    pub gap: bool,
+    pub replay: Vec<Arc<PublishedEvent>>,
+    pub receiver: broadcast::Receiver<Arc<PublishedEvent>>,
+}
+
+/// Pushes game events to the players who may see them, numbering them so that a client which
+/// reconnects resumes after the last one it got.
+pub struct EventHub {
+    sender: broadcast::Sender<Arc<PublishedEvent>>,
+    history: Mutex<History>,
+}
+
+struct History {
+    // Ids start at the process start time in ms: an id from before a restart is always older
+    next_id: u64,
+    recent: VecDeque<Arc<PublishedEvent>>,
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            history: Mutex::new(History {
+                next_id: Utc::now().timestamp_millis() as u64,
+                recent: VecDeque::with_capacity(REPLAY_CAPACITY),
+            }),
+        }
+    }
+
+    pub fn publish(&self, event: &GameEventDto) -> Result<()> {
+        let json = serde_json::to_string(event)?;
+        let mut history = self.history.lock().expect("event history lock poisoned");
+
+        let published = Arc::new(PublishedEvent {
+            id: history.next_id,
+            recipients: event.recipients(),
+            json,
+        });
+        history.next_id += 1;
+        if history.recent.len() == REPLAY_CAPACITY {
+            history.recent.pop_front();
+        }
+        history.recent.push_back(published.clone());
+
+        // Sent under the lock, so that listeners see events in id order. Nobody listening is fine
+        let _ = self.sender.send(published);
+        Ok(())
+    }
+
+    /// Starts listening, with the kept events numbered after `last_event_id`. Without one,
+    /// only new events are delivered.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        let history = self.history.lock().expect("event history lock poisoned");
+        // Taken under the lock: an event is either replayed or received, never both or neither
+        let receiver = self.sender.subscribe();
+
+        let Some(last_event_id) = last_event_id else {
+            return Subscription {
+                gap: false,
+                replay: Vec::new(),
+                receiver,
+            };
+        };
+        let oldest_kept = history
+            .recent
+            .front()
+            .map_or(history.next_id, |event| event.id);
+        Subscription {
+            gap: last_event_id.saturating_add(1) < oldest_kept,
+            replay: history
+                .recent
+                .iter()
+                .filter(|event| event.id > last_event_id)
+                .cloned()
+                .collect(),
+            receiver,
+        }
+    }
+}
+
+/// Warns the players holding tiles or buildings on `planet_id` that a unit of `attacker_id`
+/// is heading there.
+pub async fn announce_incoming_unit(
+    pool: &SqlitePool,
+    events: &EventHub,
+    attacker_id: i64,
+    unit_id: i64,
+    planet_id: i64,
+    move_type: &str,
+    arrival_time: i64,
+) -> Result<()> {
+    let holders = planets_repo::fetch_planet_holder_ids(pool, planet_id).await?;
+    for player_id in holders.into_iter().filter(|&id| id != attacker_id) {
+        events.publish(&GameEventDto::IncomingAttack {
+            player_id,
+            attacker_id,
+            unit_id,
+            planet_id,
+            move_type: move_type.to_string(),
+            arrival_time,
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYER: i64 = 1;
+    const OTHER_PLAYER: i64 = 2;
+    const BYSTANDER: i64 = 3;
+
+    fn construction(player_id: i64) -> GameEventDto {
+        GameEventDto::ConstructionCompleted {
+            player_id,
+            building_id: 7,
+        }
+    }
+
+    /// Publishes `count` events and returns their ids.
+    fn publish_many(hub: &EventHub, count: usize) -> Vec<u64> {
+        let mut listener = hub.subscribe(None).receiver;
+        (0..count)
+            .map(|_| {
+                hub.publish(&construction(PLAYER)).unwrap();
+                listener.try_recv().unwrap().id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replay_starts_after_last_event_id() {
+        let hub = EventHub::new();
+        let ids = publish_many(&hub, 5);
+
+        let subscription = hub.subscribe(Some(ids[1]));
+        assert!(!subscription.gap);
+        let replayed: Vec<u64> = subscription.replay.iter().map(|event| event.id).collect();
+        assert_eq!(replayed, ids[2..]);
+
+        assert!(hub.subscribe(Some(ids[4])).replay.is_empty());
+        // A first connection only gets new events
+        let subscription = hub.subscribe(None);
+        assert!(!subscription.gap);
+        assert!(subscription.replay.is_empty());
+    }
+
+    #[test]
+    fn gap_once_missed_events_are_evicted() {
+        let hub = EventHub::new();
+        let ids = publish_many(&hub, REPLAY_CAPACITY + 2);
+
+        // ids[0] and ids[1] are gone: after ids[1] nothing was lost, after ids[0] ids[1] was
+        let subscription = hub.subscribe(Some(ids[1]));
+        assert!(!subscription.gap);
+        assert_eq!(subscription.replay.len(), REPLAY_CAPACITY);
+
+        let subscription = hub.subscribe(Some(ids[0]));
+        assert!(subscription.gap);
+        assert_eq!(subscription.replay.len(), REPLAY_CAPACITY);
+    }
+
+    #[test]
+    fn gap_after_a_restart() {
+        let before_restart = EventHub::new();
+        let last_event_id = *publish_many(&before_restart, 3).last().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let hub = EventHub::new();
+        let subscription = hub.subscribe(Some(last_event_id));
+        assert!(subscription.gap);
+        assert!(subscription.replay.is_empty());
+
+        // Events published since the restart are replayed, still flagging the lost ones
+        let ids = publish_many(&hub, 2);
+        let subscription = hub.subscribe(Some(last_event_id));
+        assert!(subscription.gap);
+        let replayed: Vec<u64> = subscription.replay.iter().map(|event| event.id).collect();
+        assert_eq!(replayed, ids);
+    }
+
+    #[test]
+    fn events_reach_only_their_recipients() {
+        let hub = EventHub::new();
+        let mut listener = hub.subscribe(None).receiver;
+
+        let events = [
+            (
+                GameEventDto::MoveArrived {
+                    player_id: PLAYER,
+                    order_id: 1,
+                    unit_id: Some(2),
+                    building_id: None,
+                    move_type: "tile_walk".to_string(),
+                    route_id: None,
+                },
+                vec![PLAYER],
+            ),
+            (
+                GameEventDto::MoveHalted {
+                    player_id: PLAYER,
+                    unit_id: 2,
+                    route_id: 3,
+                },
+                vec![PLAYER],
+            ),
+            (construction(PLAYER), vec![PLAYER]),
+            (
+                GameEventDto::BattleEnded {
+                    battle_id: 4,
+                    attacker_id: PLAYER,
+                    defender_id: OTHER_PLAYER,
+                    outcome: "draw".to_string(),
+                    ended_at: "2026-10-18T12:00:00.000Z".to_string(),
+                },
+                vec![PLAYER, OTHER_PLAYER],
+            ),
+            (
+                GameEventDto::IncomingAttack {
+                    player_id: PLAYER,
+                    attacker_id: OTHER_PLAYER,
+                    unit_id: 5,
+                    planet_id: 6,
+                    move_type: "land".to_string(),
+                    arrival_time: 0,
+                },
+                vec![PLAYER],
+            ),
+            (
+                GameEventDto::DiplomacyMessage {
+                    from_player_id: OTHER_PLAYER,
+                    from_username: "other".to_string(),
+                    to_player_id: PLAYER,
+                    text: "hello".to_string(),
+                    sent_at: 0,
+                },
+                vec![PLAYER, OTHER_PLAYER],
+            ),
+        ];
+
+        for (event, recipients) in events {
+            hub.publish(&event).unwrap();
+            let published = listener.try_recv().unwrap();
+            for player_id in [PLAYER, OTHER_PLAYER, BYSTANDER] {
+                assert_eq!(
+                    published.is_for(player_id),
+                    recipients.contains(&player_id),
+                    "{event:?} for player {player_id}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::db::move_order::NewUnitMoveOrder;
+use crate::db::unit::UnitRow;
+use crate::dto::flight::FlightOrderDto;
+use crate::error::ApiError;
+use crate::game::flight::{FlightState, Maneuver};
+use crate::game::movement::ground_move_cost;
+use crate::game::proc_gen::planet::Planet;
+use crate::game::proc_gen::star_system::StarSystem;
+use crate::game::unit_stats::UnitStats;
+use crate::game::world::World;
+use crate::repositories::{move_orders_repo, planets_repo, units_repo};
+use crate::services::{movement, planets};
+use anyhow::{Context, Result, anyhow};
+use chrono::{TimeDelta, Utc};
+use sqlx::SqlitePool;
+
+// A unit in space this close to a planet (AU) can enter its orbit: minutes of the planet's
+// own motion around its star at the usual distances
+const ORBIT_CAPTURE_RADIUS_AU: f64 = 0.05;
+
+/// Where a unit is sent: the next layer up or down, with the planet to orbit when arriving
+/// from space and the tile to land on when landing.
+#[derive(Debug, Clone, Copy)]
+pub struct FlightOrder {
+    pub to: FlightState,
+    pub planet_id: Option<i64>,
+    pub landing_tile: Option<(u8, u32, u32)>,
+}
+
+/// Starts a launch, orbit change or landing of a unit of `player_id`, timed from the unit's
+/// thrust, the planet's gravity and the world's fleet speed. The caller wakes the scheduler
+/// once this returns.
+pub async fn order_flight(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    unit_id: i64,
+    order: &FlightOrder,
+) -> Result<FlightOrderDto, ApiError> {
+    let unit = movement::fetch_owned_unit(pool, player_id, unit_id).await?;
+    let from = flight_state(&unit)?;
+    if from == order.to {
+        return Err(ApiError::BadRequest("Unit is already there"));
+    }
+    if from == FlightState::Embarked {
+        return Err(ApiError::BadRequest("Unit is embarked"));
+    }
+    let maneuver = Maneuver::between(from, order.to).ok_or(ApiError::BadRequest(
+        "Units change one layer at a time: surface, low orbit, high orbit, space",
+    ))?;
+    if unit.in_battle != 0 {
+        return Err(ApiError::Conflict("Unit is in battle"));
+    }
+
+    let stats = UnitStats::for_type(&unit.unit_type);
+    if !stats.orbit_capable {
+        return Err(ApiError::BadRequest("Unit cannot fly"));
+    }
+
+    let planet_id = match maneuver {
+        Maneuver::LaunchToOrbit => unit.planet_id,
+        Maneuver::EnterOrbit => Some(order.planet_id.ok_or(ApiError::BadRequest(
+            "planet_id is required to enter an orbit",
+        ))?),
+        _ => unit.orbit_planet_id,
+    }
+    .with_context(|| format!("unit {unit_id} is at no planet"))?;
+
+    let location = planets_repo::fetch_planet_location(pool, world.id, planet_id)
+        .await?
+        .ok_or(ApiError::NotFound("Planet not found"))?;
+    let root_seed = world.root_seed();
+    let (system, planet) = tokio::task::spawn_blocking(move || {
+        planets::regenerate_planet_in_system(root_seed, &location)
+    })
+    .await
+    .map_err(anyhow::Error::from)??;
+
+    let duration_s = maneuver
+        .duration_s(&stats, &planet.physics)
+        .ok_or(ApiError::BadRequest(
+            "Engines too weak to lift off against this gravity",
+        ))?;
+    let start = Utc::now();
+    let arrival = start
+        + TimeDelta::milliseconds(
+            (duration_s * 1000.0 / world.ruleset.fleet_speed()).round() as i64
+        );
+
+    let mut new_order = NewUnitMoveOrder {
+        unit_id,
+        move_type: maneuver.move_type(),
+        from_planet_id: Some(planet_id),
+        from_star_system_id: None,
+        from_space: None,
+        to_planet_id: Some(planet_id),
+        to_tile: None,
+        to_star_system_id: None,
+        to_space: None,
+        start_time: start.timestamp_millis(),
+        arrival_time: arrival.timestamp_millis(),
+    };
+
+    match maneuver {
+        Maneuver::Land => {
+            let (face, u, v) = order
+                .landing_tile
+                .ok_or(ApiError::BadRequest("face, u and v are required to land"))?;
+            let tile = planet
+                .query_tile(face, u, v)
+                .ok_or(ApiError::BadRequest("No such tile on this planet"))?;
+            if ground_move_cost(tile.tile_type).is_none() {
+                return Err(ApiError::BadRequest("Cannot land on this terrain"));
+            }
+            new_order.to_tile = Some((tile.face as i32, tile.u as i32, tile.v as i32));
+        }
+        Maneuver::OrbitToSpace => {
+            // The unit is released where the planet will be once it has left
+            new_order.to_planet_id = None;
+            new_order.to_star_system_id = Some(planet.star_system_id);
+            new_order.to_space = Some(planet_position_au(
+                &system,
+                &planet,
+                world.game_time_s(arrival),
+            )?);
+        }
+        Maneuver::EnterOrbit => {
+            let position = match (unit.star_system_x, unit.star_system_y) {
+                (Some(x), Some(y)) => [x, y, unit.star_system_z.unwrap_or(0.0)],
+                _ => return Err(anyhow!("unit {unit_id} is in space at no position").into()),
+            };
+            if unit.star_system_id != Some(planet.star_system_id) {
+                return Err(ApiError::BadRequest("Planet is in another star system"));
+            }
+            let planet_now = planet_position_au(&system, &planet, world.game_time_s(start))?;
+            if distance_au(position, planet_now) > ORBIT_CAPTURE_RADIUS_AU {
+                return Err(ApiError::BadRequest(
+                    "Too far from the planet to enter its orbit",
+                ));
+            }
+            new_order.from_planet_id = None;
+            new_order.from_star_system_id = unit.star_system_id;
+            new_order.from_space = Some(position);
+        }
+        Maneuver::LaunchToOrbit | Maneuver::RaiseOrbit | Maneuver::LowerOrbit => {}
+    }
+
+    let mut tx = pool.begin().await?;
+    // Writing first takes SQLite's write lock, so the checks below cannot race another order
+    let order_id = move_orders_repo::insert_unit_move_order(&mut tx, &new_order).await?;
+
+    let current = units_repo::fetch_unit(&mut *tx, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit not found"))?;
+    if place_of(&current) != place_of(&unit) {
+        return Err(ApiError::Conflict("Unit moved meanwhile"));
+    }
+    if move_orders_repo::count_unit_move_orders(&mut tx, unit_id).await? > 1 {
+        return Err(ApiError::Conflict("Unit is already moving"));
+    }
+    tx.commit().await?;
+
+    Ok(FlightOrderDto {
+        order_id,
+        unit_id,
+        maneuver: maneuver.move_type(),
+        from: from.as_str(),
+        to: order.to.as_str(),
+        planet_id,
+        start_time: new_order.start_time,
+        arrival_time: new_order.arrival_time,
+    })
+}
+
+fn flight_state(unit: &UnitRow) -> Result<FlightState> {
+    FlightState::from_location(&unit.location_mode, unit.orbit_layer.as_deref()).with_context(
+        || {
+            format!(
+                "unit {} has unknown location mode '{}'",
+                unit.id, unit.location_mode
+            )
+        },
+    )
+}
+
+// Everything a flight order depends on
+fn place_of(unit: &UnitRow) -> (&str, Option<&str>, Option<i64>, Option<i64>, Option<i64>) {
+    (
+        &unit.location_mode,
+        unit.orbit_layer.as_deref(),
+        unit.planet_id,
+        unit.orbit_planet_id,
+        unit.star_system_id,
+    )
+}
+
+/// Position of `planet` in its system at game time `time_s`, in AU. Moons share their
+/// parent's: at this scale the difference does not matter.
+fn planet_position_au(system: &StarSystem, planet: &Planet, time_s: f64) -> Result<[f64; 3]> {
+    let body = system
+        .bodies
+        .iter()
+        .find(|body| body.index == planet.orbit_index)
+        .context("planet has no body in its regenerated system")?;
+    Ok(system.body_position_au(body, time_s).map(f64::from))
+}
+
+fn distance_au(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (0..3)
+        .map(|axis| (a[axis] - b[axis]).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
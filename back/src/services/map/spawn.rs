@@ -1,11 +1,23 @@
+use crate::db::planet::OccupiedPlanetRow;
 use crate::dto::state::GameStateDto;
-use crate::game::game_init;
-use crate::game::proc_gen::seed::WORLD_SEED;
-use crate::repositories::{buildings_repo, player_state_repo, players_repo, units_repo};
-use anyhow::Result;
+use crate::game::game_init::{self, SpawnExclusions, StartingLocation};
+use crate::game::start_area::FairnessBand;
+use crate::game::world::World;
+use crate::repositories::{
+    buildings_repo, player_state_repo, players_repo, units_repo, worlds_repo,
+};
+use anyhow::{Result, bail};
 use sqlx::SqlitePool;
 
-pub async fn load_or_initialize_player(pool: &SqlitePool, player_id: i64) -> Result<GameStateDto> {
+// Searches that lose the race to a concurrent sign-up are retried against the updated universe
+const MAX_SPAWN_ATTEMPTS: u32 = 5;
+
+pub async fn load_or_initialize_player(
+    pool: &SqlitePool,
+    world: &World,
+    band: &FairnessBand,
+    player_id: i64,
+) -> Result<GameStateDto> {
     let player = players_repo::fetch_player_by_id(pool, player_id).await?;
 
     let mut units = units_repo::fetch_player_units(pool, player_id).await?;
@@ -13,13 +25,7 @@ pub async fn load_or_initialize_player(pool: &SqlitePool, player_id: i64) -> Res
 
     // If completely empty, trigger initialization using deterministic generator paths
     if units.is_empty() && buildings.is_empty() {
-        let spawn = game_init::find_starting_location(WORLD_SEED);
-
-        let mut tx = pool.begin().await?;
-
-        player_state_repo::insert_initial_player_state(&mut tx, player_id, &spawn).await?;
-
-        tx.commit().await?;
+        allocate_home_planet(pool, world, band, player_id).await?;
 
         // Re-fetch elements cleanly to populate DTO surface mapping
         units = units_repo::fetch_player_units(pool, player_id).await?;
@@ -33,3 +39,71 @@ pub async fn load_or_initialize_player(pool: &SqlitePool, player_id: i64) -> Res
         buildings: buildings.into_iter().map(Into::into).collect(),
     })
 }
+
+/// Reserves an untouched home planet for the player, away from every other home.
+///
+/// The search runs without holding any lock; its pick is then re-checked inside a transaction
+/// that holds SQLite's write lock, so two simultaneous sign-ups can never both claim it.
+async fn allocate_home_planet(
+    pool: &SqlitePool,
+    world: &World,
+    band: &FairnessBand,
+    player_id: i64,
+) -> Result<()> {
+    for _ in 0..MAX_SPAWN_ATTEMPTS {
+        let occupied = player_state_repo::fetch_occupied_planets(pool, world.id).await?;
+        let exclusions = spawn_exclusions(&occupied);
+
+        let (seed, generation_version, search_band) = (world.seed, world.generation_version, *band);
+        let spawn: StartingLocation = tokio::task::spawn_blocking(move || {
+            game_init::find_starting_location(seed, generation_version, &exclusions, &search_band)
+        })
+        .await??;
+
+        let mut tx = pool.begin().await?;
+        worlds_repo::lock_world_for_spawn(&mut tx, world.id).await?;
+
+        // Another request for the same player may have finished first
+        if players_repo::fetch_home_planet_id(&mut tx, player_id)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let occupied = player_state_repo::fetch_occupied_planets(&mut *tx, world.id).await?;
+        if !spawn_exclusions(&occupied).allows(&spawn, band) {
+            // Someone settled nearby while we searched: drop the transaction and look again
+            continue;
+        }
+
+        player_state_repo::insert_initial_player_state(&mut tx, world.id, player_id, &spawn)
+            .await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "player {} starts on planet {:?} of system {:?}, start score {:.1}",
+            player_id,
+            spawn.planet.orbit_index,
+            spawn.star_system.position,
+            spawn.survey.score()
+        );
+        return Ok(());
+    }
+
+    bail!("could not reserve a home planet after {MAX_SPAWN_ATTEMPTS} attempts")
+}
+
+fn spawn_exclusions(occupied: &[OccupiedPlanetRow]) -> SpawnExclusions {
+    let mut exclusions = SpawnExclusions::default();
+    for planet in occupied {
+        exclusions.add_planet(
+            (planet.galaxy_x, planet.galaxy_y, planet.galaxy_z),
+            (planet.system_x, planet.system_y, planet.system_z),
+            planet.orbit_index as u32,
+            planet.moon_index.map(|index| index as u32),
+            planet.is_home,
+        );
+    }
+    exclusions
+}
@@ -0,0 +1,223 @@
+use crate::db::move_order::TileWalkLeg;
+use crate::db::planet::PlanetLocationRow;
+use crate::db::unit::UnitRow;
+use crate::dto::move_route::{MoveLegDto, MoveRouteDto};
+use crate::error::ApiError;
+use crate::game::movement::{GROUND_SECONDS_PER_TILE, PathStep, find_ground_path};
+use crate::game::proc_gen::goldberg::GoldbergTopology;
+use crate::game::world::World;
+use crate::repositories::{move_orders_repo, planets_repo, units_repo};
+use crate::services::planets;
+use anyhow::{Context, anyhow};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+type TileCoords = (i32, i32, i32);
+
+/// Sends a unit of `player_id` walking to tile `destination` of the planet it stands on, one
+/// leg per tile crossed. The caller wakes the scheduler once this returns.
+pub async fn order_surface_move(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    unit_id: i64,
+    destination: (u8, u32, u32),
+) -> Result<MoveRouteDto, ApiError> {
+    let unit = fetch_owned_unit(pool, player_id, unit_id).await?;
+    let (planet_id, origin) = surface_position(&unit)?;
+    if unit.in_battle != 0 {
+        return Err(ApiError::Conflict("Unit is in battle"));
+    }
+
+    let (face, u, v) = destination;
+    let (topology, path) = plan_ground_path(pool, world, planet_id, origin, move |topology| {
+        topology
+            .tile_index(face, u, v)
+            .ok_or(ApiError::BadRequest("No such tile on this planet"))
+    })
+    .await?;
+    let path = path.ok_or(ApiError::BadRequest(
+        "Destination cannot be reached on foot",
+    ))?;
+    if path.is_empty() {
+        return Err(ApiError::BadRequest("Unit is already there"));
+    }
+
+    start_route(pool, world, unit_id, planet_id, origin, &topology, &path).await
+}
+
+/// Stops the walk of a unit of `player_id` and leads it back to where the walk started.
+/// Returns the way back, without legs if the unit is already there or cannot return.
+pub async fn cancel_surface_move(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    unit_id: i64,
+) -> Result<MoveRouteDto, ApiError> {
+    fetch_owned_unit(pool, player_id, unit_id).await?;
+
+    let mut tx = pool.begin().await?;
+    let route = move_orders_repo::fetch_unit_route(&mut tx, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit is not walking"))?;
+    move_orders_repo::delete_move_route(&mut tx, route.id).await?;
+
+    // Read once the deletion holds the write lock: no leg can be walked in between
+    let unit = units_repo::fetch_unit(&mut *tx, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit not found"))?;
+    tx.commit().await?;
+
+    let (planet_id, position) = surface_position(&unit)?;
+    let origin = (route.origin_face, route.origin_u, route.origin_v);
+    let stay = MoveRouteDto {
+        route_id: None,
+        unit_id,
+        planet_id,
+        legs: Vec::new(),
+    };
+    if planet_id != route.planet_id || position == origin {
+        return Ok(stay);
+    }
+
+    let (topology, path) = plan_ground_path(pool, world, planet_id, position, move |topology| {
+        Ok(tile_index(topology, origin).context("route origin is off the planet's tiles")?)
+    })
+    .await?;
+    match path {
+        Some(path) if !path.is_empty() => {
+            start_route(pool, world, unit_id, planet_id, position, &topology, &path).await
+        }
+        _ => Ok(stay),
+    }
+}
+
+/// The unit, if it exists and belongs to `player_id`.
+pub async fn fetch_owned_unit(
+    pool: &SqlitePool,
+    player_id: i64,
+    unit_id: i64,
+) -> Result<UnitRow, ApiError> {
+    let unit = units_repo::fetch_unit(pool, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit not found"))?;
+    if unit.player_id != player_id {
+        return Err(ApiError::Forbidden("Unit belongs to another player"));
+    }
+    Ok(unit)
+}
+
+fn surface_position(unit: &UnitRow) -> Result<(i64, TileCoords), ApiError> {
+    match (
+        unit.location_mode.as_str(),
+        unit.planet_id,
+        unit.planet_face,
+        unit.planet_u,
+        unit.planet_v,
+    ) {
+        ("planet_surface", Some(planet_id), Some(face), Some(u), Some(v)) => {
+            Ok((planet_id, (face, u, v)))
+        }
+        _ => Err(ApiError::BadRequest("Unit is not on a planet surface")),
+    }
+}
+
+/// Regenerates the planet and searches the ground path from `from` to the tile `destination`
+/// picks, off the async runtime. The path is `None` if the destination cannot be reached.
+async fn plan_ground_path(
+    pool: &SqlitePool,
+    world: &World,
+    planet_id: i64,
+    from: TileCoords,
+    destination: impl FnOnce(&GoldbergTopology) -> Result<u32, ApiError> + Send + 'static,
+) -> Result<(Arc<GoldbergTopology>, Option<Vec<PathStep>>), ApiError> {
+    let location: PlanetLocationRow =
+        planets_repo::fetch_planet_location(pool, world.id, planet_id)
+            .await?
+            .ok_or_else(|| anyhow!("planet {planet_id} is not in this world"))?;
+
+    let root_seed = world.root_seed();
+    tokio::task::spawn_blocking(move || {
+        let planet = planets::regenerate_planet(root_seed, &location)?;
+        let topology = planet.topology();
+        let from = tile_index(&topology, from).context("unit stands off the planet's tiles")?;
+        let to = destination(&topology)?;
+
+        let path = find_ground_path(&planet, from, to);
+        Ok((topology, path))
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+}
+
+/// Stores `path` as the unit's route, timed from now at the world's fleet speed.
+async fn start_route(
+    pool: &SqlitePool,
+    world: &World,
+    unit_id: i64,
+    planet_id: i64,
+    origin: TileCoords,
+    topology: &GoldbergTopology,
+    path: &[PathStep],
+) -> Result<MoveRouteDto, ApiError> {
+    let mut tx = pool.begin().await?;
+    // Writing first takes SQLite's write lock, so the checks below cannot race another order
+    let route = move_orders_repo::create_move_route(&mut tx, unit_id, planet_id, origin).await?;
+
+    // The path was searched outside the transaction: the unit must still be where it started
+    let unit = units_repo::fetch_unit(&mut *tx, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit not found"))?;
+    if surface_position(&unit)? != (planet_id, origin) {
+        return Err(ApiError::Conflict("Unit moved meanwhile"));
+    }
+    if move_orders_repo::count_unit_move_orders(&mut tx, unit_id).await? > 0 {
+        return Err(ApiError::Conflict("Unit is already moving"));
+    }
+
+    let mut legs = Vec::with_capacity(path.len());
+    let mut from = origin;
+    let mut time = Utc::now().timestamp_millis();
+    let ms_per_cost = GROUND_SECONDS_PER_TILE * 1000.0 / world.ruleset.fleet_speed();
+
+    for (leg_index, step) in path.iter().enumerate() {
+        let (face, u, v) = topology.tile_coords(step.tile);
+        let leg = TileWalkLeg {
+            leg_index: leg_index as i32,
+            from,
+            to: (face as i32, u as i32, v as i32),
+            start_time: time,
+            arrival_time: time + (step.cost * ms_per_cost).round() as i64,
+        };
+        let order_id = move_orders_repo::insert_tile_walk_leg(&mut tx, &route, &leg).await?;
+
+        legs.push(MoveLegDto {
+            order_id,
+            index: step.tile,
+            face,
+            u,
+            v,
+            start_time: leg.start_time,
+            arrival_time: leg.arrival_time,
+        });
+        from = leg.to;
+        time = leg.arrival_time;
+    }
+
+    tx.commit().await?;
+    Ok(MoveRouteDto {
+        route_id: Some(route.id),
+        unit_id,
+        planet_id,
+        legs,
+    })
+}
+
+fn tile_index(topology: &GoldbergTopology, (face, u, v): TileCoords) -> Option<u32> {
+    topology.tile_index(
+        u8::try_from(face).ok()?,
+        u32::try_from(u).ok()?,
+        u32::try_from(v).ok()?,
+    )
+}
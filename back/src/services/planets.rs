@@ -1,17 +1,197 @@
-// // services/planet.rs
-// pub async fn get_planet(pool: &SqlitePool, player_id: i64, planet_id: i64)
-//     -> anyhow::Result<PlanetResponse>
-// {
-//     let planet = db::planet::find(pool, planet_id).await?;
-//     let resources = db::resources::find(pool, planet_id).await?;
-//     let computed = production::compute(&resources);  // pure game logic
-
-//     Ok(PlanetResponse::from_parts(planet, computed)) // db → dto
-// }
-//
-//
-//
-//
+use crate::db::planet::PlanetLocationRow;
+use crate::dto::planet::{
+    PlanetPhysicsDto, PlanetSurfaceDto, SurfaceBuildingDto, SurfaceOverlayDto, SurfaceUnitDto,
+    TileColumnsDto, TileInfluenceDto, TileStateDto, TopologyDto,
+};
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::goldberg::GoldbergTopology;
+use crate::game::proc_gen::planet::Planet;
+use crate::game::proc_gen::seed::RootSeed;
+use crate::game::proc_gen::star_system::StarSystem;
+use crate::game::world::World;
+use crate::repositories::{buildings_repo, planets_repo, units_repo};
+use anyhow::{Result, anyhow, bail};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+// Other players' units are seen this many tiles away from one's own units, buildings and land
+const SURFACE_VISION_RINGS: u32 = 2;
+
+/// Every tile of a planet, regenerated from its seed and overlaid with the persisted state the
+/// player is allowed to see. Returns None if the planet does not exist in this world.
+pub async fn load_planet_surface(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    planet_id: i64,
+) -> Result<Option<PlanetSurfaceDto>> {
+    let Some(location) = planets_repo::fetch_planet_location(pool, world.id, planet_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let root_seed = world.root_seed();
+    let (planet, tiles) = tokio::task::spawn_blocking(move || {
+        let planet = regenerate_planet(root_seed, &location)?;
+        let tiles: Vec<_> = (0..planet.topology().tile_count())
+            .map(|index| planet.query_tile_at(index))
+            .collect();
+        anyhow::Ok((planet, tiles))
+    })
+    .await??;
+
+    let topology = planet.topology();
+    let overlay = load_surface_overlay(pool, &topology, player_id, planet_id).await?;
+
+    Ok(Some(PlanetSurfaceDto {
+        id: planet_id,
+        star_system_id: planet.star_system_id,
+        orbit_index: planet.orbit_index,
+        moon_index: planet.moon_index,
+        class: planet.class.as_str(),
+        physics: PlanetPhysicsDto::from(&planet),
+        topology: TopologyDto::from(topology.as_ref()),
+        tiles: TileColumnsDto::from(tiles.as_slice()),
+        overlay,
+    }))
+}
+
+/// Rebuilds a stored planet from its address, refusing one its stored seed no longer matches.
+pub fn regenerate_planet(root_seed: RootSeed, location: &PlanetLocationRow) -> Result<Planet> {
+    Ok(regenerate_planet_in_system(root_seed, location)?.1)
+}
+
+/// `regenerate_planet`, along with the star system the planet orbits in.
+pub fn regenerate_planet_in_system(
+    root_seed: RootSeed,
+    location: &PlanetLocationRow,
+) -> Result<(StarSystem, Planet)> {
+    let galaxy = Galaxy::new(
+        root_seed,
+        (location.galaxy_x, location.galaxy_y, location.galaxy_z),
+    );
+    let system = StarSystem::new(
+        &galaxy,
+        (location.system_x, location.system_y, location.system_z),
+    );
+
+    let mut planet = Planet::from_address(
+        location.star_system_id,
+        &system,
+        location.orbit_index as u32,
+        location.moon_index.map(|index| index as u32),
+    )
+    .ok_or_else(|| {
+        anyhow!(
+            "planet {} has no generated body at its address",
+            location.id
+        )
+    })?;
+
+    // The stored seed is only a checksum: a mismatch means generation rules changed under it
+    if planet.seed != location.seed as u64 {
+        bail!(
+            "planet {} no longer matches its generated seed",
+            location.id
+        );
+    }
+
+    planet.id = Some(location.id);
+    Ok((system, planet))
+}
+
+async fn load_surface_overlay(
+    pool: &SqlitePool,
+    topology: &GoldbergTopology,
+    player_id: i64,
+    planet_id: i64,
+) -> Result<SurfaceOverlayDto> {
+    let mut overlay = SurfaceOverlayDto::default();
+    let mut index_by_tile_id = HashMap::new();
+
+    for row in planets_repo::fetch_planet_tiles(pool, planet_id).await? {
+        let Some(index) = tile_index(topology, row.face, row.u, row.v) else {
+            continue;
+        };
+        index_by_tile_id.insert(row.id, index);
+        overlay.tiles.push(TileStateDto {
+            index,
+            tile_id: row.id,
+            owner_player_id: row.owner_player_id,
+        });
+    }
+
+    for row in buildings_repo::fetch_planet_buildings(pool, planet_id).await? {
+        if let Some(&index) = index_by_tile_id.get(&row.tile_id) {
+            overlay.buildings.push(SurfaceBuildingDto {
+                index,
+                player_id: row.player_id,
+                building: row.into(),
+            });
+        }
+    }
+
+    for row in planets_repo::fetch_planet_influence(pool, planet_id).await? {
+        if let Some(&index) = index_by_tile_id.get(&row.tile_id) {
+            overlay.influence.push(TileInfluenceDto {
+                index,
+                player_id: row.player_id,
+                score: row.score,
+            });
+        }
+    }
+
+    let units: Vec<_> = units_repo::fetch_surface_units(pool, planet_id)
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            let index = tile_index(topology, row.planet_face?, row.planet_u?, row.planet_v?)?;
+            Some((index, row))
+        })
+        .collect();
+
+    // Fog of war: the player sees around their own units, buildings and territory
+    let vision_sources = units
+        .iter()
+        .filter(|(_, unit)| unit.player_id == player_id)
+        .map(|&(index, _)| index)
+        .chain(
+            overlay
+                .buildings
+                .iter()
+                .filter(|building| building.player_id == player_id)
+                .map(|building| building.index),
+        )
+        .chain(
+            overlay
+                .tiles
+                .iter()
+                .filter(|tile| tile.owner_player_id == Some(player_id))
+                .map(|tile| tile.index),
+        );
+    let visible = topology.tiles_within(vision_sources.collect::<Vec<_>>(), SURFACE_VISION_RINGS);
+
+    overlay.units = units
+        .into_iter()
+        .filter(|(index, unit)| unit.player_id == player_id || visible.contains(index))
+        .map(|(index, unit)| SurfaceUnitDto {
+            index,
+            player_id: unit.player_id,
+            unit: unit.into(),
+        })
+        .collect();
+
+    Ok(overlay)
+}
+
+fn tile_index(topology: &GoldbergTopology, face: i32, u: i32, v: i32) -> Option<u32> {
+    topology.tile_index(
+        u8::try_from(face).ok()?,
+        u32::try_from(u).ok()?,
+        u32::try_from(v).ok()?,
+    )
+}
+
 // here is another exemple for Tick Production
 //
 // // services/resource_tick.rs
@@ -0,0 +1,426 @@
+use crate::db::move_order::NewUnitMoveOrder;
+use crate::db::star_system::StarSystemCellRow;
+use crate::db::unit::UnitRow;
+use crate::dto::travel::{SpaceTravelDto, UnitPositionDto};
+use crate::error::ApiError;
+use crate::game::proc_gen::orbit;
+use crate::game::resource::Resource;
+use crate::game::travel::{self, SpacePosition, SystemPoint, Trajectory};
+use crate::game::unit_stats::UnitStats;
+use crate::game::world::World;
+use crate::repositories::{
+    move_orders_repo, planets_repo, resources_repo, star_systems_repo, units_repo,
+};
+use crate::services::{movement, planets};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, TimeDelta, Utc};
+use sqlx::SqlitePool;
+
+/// Where a unit in space is sent: a planet of its system, met where its orbit will have
+/// carried it, or a point of a star system. Another system than the unit's is reached with a
+/// jump, arriving at `position_au` or at the system's edge facing the way the unit came.
+#[derive(Debug, Clone, Copy)]
+pub struct TravelOrder {
+    pub planet_id: Option<i64>,
+    pub star_system_id: Option<i64>,
+    pub position_au: Option<[f64; 3]>,
+}
+
+/// Starts a flight of a unit of `player_id` through space, paying its deuterium up front.
+/// The caller wakes the scheduler once this returns.
+pub async fn order_space_travel(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    unit_id: i64,
+    order: &TravelOrder,
+) -> Result<SpaceTravelDto, ApiError> {
+    let unit = movement::fetch_owned_unit(pool, player_id, unit_id).await?;
+    let from_point = space_point(&unit)?;
+    if unit.in_battle != 0 {
+        return Err(ApiError::Conflict("Unit is in battle"));
+    }
+
+    let stats = UnitStats::for_type(&unit.unit_type);
+    let speed_au_per_s = travel::hop_speed_au_per_s(&stats)
+        .ok_or(ApiError::BadRequest("Unit cannot fly in space"))?
+        * world.ruleset.fleet_speed();
+
+    let from_system = fetch_system_cell(pool, world, from_point.0).await?;
+    let from = SystemPoint {
+        star_system_id: from_system.id,
+        grid: cell_of(&from_system),
+        position_au: from_point.1,
+    };
+    let start = Utc::now();
+
+    let (to, duration_s, deuterium) = match (order.planet_id, order.star_system_id) {
+        (Some(planet_id), _) => {
+            let (to, duration_s) =
+                plan_planet_intercept(pool, world, &from, planet_id, speed_au_per_s, start).await?;
+            let distance_au = travel::distance(from.position_au, to.position_au);
+            (
+                to,
+                duration_s,
+                travel::hop_fuel(&stats, unit.count, distance_au),
+            )
+        }
+        (None, Some(star_system_id)) if star_system_id != from.star_system_id => {
+            let to_system =
+                star_systems_repo::fetch_star_system_cell(pool, world.id, star_system_id)
+                    .await?
+                    .ok_or(ApiError::NotFound("Star system not found"))?;
+            if to_system.galaxy_id != from_system.galaxy_id {
+                return Err(ApiError::BadRequest(
+                    "Travel between galaxies needs a stargate",
+                ));
+            }
+            let grid = cell_of(&to_system);
+            let position_au = match order.position_au {
+                Some(position_au) => checked_system_position(position_au)?,
+                None => travel::jump_arrival_au(from.grid, grid),
+            };
+
+            let distance_cells = travel::grid_distance(from.grid, grid);
+            let duration_s = travel::jump_duration_s(&stats, distance_cells)
+                .ok_or(ApiError::BadRequest("Unit has no interstellar drive"))?
+                / world.ruleset.fleet_speed();
+            let to = SystemPoint {
+                star_system_id,
+                grid,
+                position_au,
+            };
+            (
+                to,
+                duration_s,
+                travel::jump_fuel(&stats, unit.count, distance_cells),
+            )
+        }
+        (None, _) => {
+            let position_au = order.position_au.ok_or(ApiError::BadRequest(
+                "position is required to fly within a system",
+            ))?;
+            let to = SystemPoint {
+                position_au: checked_system_position(position_au)?,
+                ..from
+            };
+            let distance_au = travel::distance(from.position_au, to.position_au);
+            (
+                to,
+                distance_au / speed_au_per_s,
+                travel::hop_fuel(&stats, unit.count, distance_au),
+            )
+        }
+    };
+    if to == from {
+        return Err(ApiError::BadRequest("Unit is already there"));
+    }
+
+    let arrival = start + TimeDelta::milliseconds((duration_s * 1000.0).round() as i64);
+    let new_order = NewUnitMoveOrder {
+        unit_id,
+        move_type: "space_travel",
+        from_planet_id: None,
+        from_star_system_id: Some(from.star_system_id),
+        from_space: Some(from.position_au),
+        to_planet_id: None,
+        to_tile: None,
+        to_star_system_id: Some(to.star_system_id),
+        to_space: Some(to.position_au),
+        start_time: start.timestamp_millis(),
+        arrival_time: arrival.timestamp_millis(),
+    };
+
+    let mut tx = pool.begin().await?;
+    // Writing first takes SQLite's write lock, so the checks below cannot race another order
+    let order_id = move_orders_repo::insert_unit_move_order(&mut tx, &new_order).await?;
+
+    let current = units_repo::fetch_unit(&mut *tx, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit not found"))?;
+    if space_point(&current)? != (from.star_system_id, from.position_au) {
+        return Err(ApiError::Conflict("Unit moved meanwhile"));
+    }
+    if move_orders_repo::count_unit_move_orders(&mut tx, unit_id).await? > 1 {
+        return Err(ApiError::Conflict("Unit is already moving"));
+    }
+    if deuterium > 0.0
+        && !resources_repo::spend_player_resource(
+            &mut tx,
+            player_id,
+            Resource::Deuterium,
+            deuterium,
+        )
+        .await?
+    {
+        return Err(ApiError::BadRequest("Not enough deuterium"));
+    }
+    tx.commit().await?;
+
+    Ok(SpaceTravelDto {
+        order_id,
+        unit_id,
+        jump: from.star_system_id != to.star_system_id,
+        from_star_system_id: from.star_system_id,
+        from: from.position_au,
+        to_star_system_id: to.star_system_id,
+        to: to.position_au,
+        planet_id: order.planet_id,
+        deuterium,
+        start_time: new_order.start_time,
+        arrival_time: new_order.arrival_time,
+    })
+}
+
+/// Where a unit of `player_id` in space is at `at`, between the ends of its flight if it is
+/// flying. For scans and interceptions.
+pub async fn unit_space_position(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    unit_id: i64,
+    at: DateTime<Utc>,
+) -> Result<UnitPositionDto, ApiError> {
+    let unit = movement::fetch_owned_unit(pool, player_id, unit_id).await?;
+    let (star_system_id, position_au) = space_point(&unit)?;
+
+    let Some(order) = move_orders_repo::fetch_unit_space_travel(pool, unit_id).await? else {
+        return Ok(UnitPositionDto::new(
+            unit_id,
+            at.timestamp_millis(),
+            false,
+            SpacePosition::InSystem {
+                star_system_id,
+                position_au,
+            },
+        ));
+    };
+
+    let (from_id, from_au) = order_endpoint(
+        order.id,
+        order.from_star_system_id,
+        [order.from_space_x, order.from_space_y, order.from_space_z],
+    )?;
+    let (to_id, to_au) = order_endpoint(
+        order.id,
+        order.to_star_system_id,
+        [order.to_space_x, order.to_space_y, order.to_space_z],
+    )?;
+    let from_system = fetch_system_cell(pool, world, from_id).await?;
+    let to_system = fetch_system_cell(pool, world, to_id).await?;
+
+    let trajectory = Trajectory {
+        from: SystemPoint {
+            star_system_id: from_id,
+            grid: cell_of(&from_system),
+            position_au: from_au,
+        },
+        to: SystemPoint {
+            star_system_id: to_id,
+            grid: cell_of(&to_system),
+            position_au: to_au,
+        },
+        start_time: order.start_time,
+        arrival_time: order.arrival_time,
+    };
+    let at_ms = at.timestamp_millis();
+    let moving = (order.start_time..order.arrival_time).contains(&at_ms);
+    Ok(UnitPositionDto::new(
+        unit_id,
+        at_ms,
+        moving,
+        trajectory.position_at(at_ms),
+    ))
+}
+
+/// Meeting point with `planet_id` for a unit leaving `from` at `start`, and the flight time in
+/// seconds. The planet must be in the unit's system.
+async fn plan_planet_intercept(
+    pool: &SqlitePool,
+    world: &World,
+    from: &SystemPoint,
+    planet_id: i64,
+    speed_au_per_s: f64,
+    start: DateTime<Utc>,
+) -> Result<(SystemPoint, f64), ApiError> {
+    let location = planets_repo::fetch_planet_location(pool, world.id, planet_id)
+        .await?
+        .ok_or(ApiError::NotFound("Planet not found"))?;
+    if location.star_system_id != from.star_system_id {
+        return Err(ApiError::BadRequest(
+            "Planet is in another star system: jump there first",
+        ));
+    }
+
+    let root_seed = world.root_seed();
+    let (system, planet) = tokio::task::spawn_blocking(move || {
+        planets::regenerate_planet_in_system(root_seed, &location)
+    })
+    .await
+    .map_err(anyhow::Error::from)??;
+    // Moons travel with their parent: at this scale the difference does not matter
+    let body = system
+        .bodies
+        .iter()
+        .find(|body| body.index == planet.orbit_index)
+        .context("planet has no body in its regenerated system")?;
+
+    let departure_s = world.game_time_s(start);
+    let intercept = orbit::intercept(
+        &body.orbit,
+        system.host_position_au(body.host),
+        from.position_au.map(|c| c as f32),
+        departure_s,
+        speed_au_per_s,
+    )
+    .context("no intercept at a positive speed")?;
+
+    let to = SystemPoint {
+        position_au: intercept.position_au.map(f64::from),
+        ..*from
+    };
+    Ok((to, intercept.arrival_s - departure_s))
+}
+
+/// Star system and position of a unit in space.
+pub fn space_point(unit: &UnitRow) -> Result<(i64, [f64; 3]), ApiError> {
+    match (
+        unit.location_mode.as_str(),
+        unit.star_system_id,
+        unit.star_system_x,
+        unit.star_system_y,
+    ) {
+        ("in_space", Some(star_system_id), Some(x), Some(y)) => {
+            Ok((star_system_id, [x, y, unit.star_system_z.unwrap_or(0.0)]))
+        }
+        _ => Err(ApiError::BadRequest("Unit is not in space")),
+    }
+}
+
+// One end of a space_travel order
+fn order_endpoint(
+    order_id: i64,
+    star_system_id: Option<i64>,
+    [x, y, z]: [Option<f64>; 3],
+) -> Result<(i64, [f64; 3])> {
+    match (star_system_id, x, y) {
+        (Some(star_system_id), Some(x), Some(y)) => Ok((star_system_id, [x, y, z.unwrap_or(0.0)])),
+        _ => Err(anyhow!("move order {order_id} has no space endpoints")),
+    }
+}
+
+fn checked_system_position(position_au: [f64; 3]) -> Result<[f64; 3], ApiError> {
+    if position_au.iter().any(|c| !c.is_finite())
+        || travel::distance(position_au, [0.0; 3]) > travel::MAX_SYSTEM_RADIUS_AU
+    {
+        return Err(ApiError::BadRequest("Position is outside the star system"));
+    }
+    Ok(position_au)
+}
+
+async fn fetch_system_cell(
+    pool: &SqlitePool,
+    world: &World,
+    star_system_id: i64,
+) -> Result<StarSystemCellRow> {
+    star_systems_repo::fetch_star_system_cell(pool, world.id, star_system_id)
+        .await?
+        .with_context(|| format!("star system {star_system_id} is not in this world"))
+}
+
+fn cell_of(system: &StarSystemCellRow) -> (i32, i32, i32) {
+    (system.x, system.y, system.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_db;
+    use crate::game::flight::FlightState;
+    use crate::game::start_area::FairnessBand;
+    use crate::services::flight::{self, FlightOrder};
+    use crate::services::map::spawn;
+    use crate::worker::arrivals;
+
+    async fn deuterium(pool: &SqlitePool, player_id: i64) -> f64 {
+        sqlx::query_scalar(
+            "SELECT amount FROM player_resources WHERE player_id = ? AND resource_type = 'deuterium'",
+        )
+        .bind(player_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Applies every pending order as if its arrival time had come.
+    async fn arrive_all(pool: &SqlitePool, world: &World) {
+        for order in move_orders_repo::fetch_due_move_orders(pool, world.id, i64::MAX, 64)
+            .await
+            .unwrap()
+        {
+            arrivals::apply_arrival(pool, &order).await.unwrap();
+        }
+    }
+
+    async fn fly(pool: &SqlitePool, world: &World, player_id: i64, unit_id: i64, to: FlightState) {
+        let order = FlightOrder {
+            to,
+            planet_id: None,
+            landing_tile: None,
+        };
+        flight::order_flight(pool, world, player_id, unit_id, &order)
+            .await
+            .unwrap_or_else(|error| panic!("flight to {to:?}: {error:?}"));
+        arrive_all(pool, world).await;
+    }
+
+    #[tokio::test]
+    async fn spawned_scout_reaches_space_and_hops() {
+        let pool = test_db::memory_pool().await;
+        let world = test_db::create_world(&pool).await;
+        let player_id = test_db::create_player(&pool, &world, "pilot").await;
+        spawn::load_or_initialize_player(&pool, &world, &FairnessBand::default(), player_id)
+            .await
+            .unwrap();
+
+        let scout = units_repo::fetch_player_units(&pool, player_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|unit| unit.unit_type == "scout")
+            .unwrap();
+
+        fly(&pool, &world, player_id, scout.id, FlightState::LowOrbit).await;
+        fly(&pool, &world, player_id, scout.id, FlightState::HighOrbit).await;
+        fly(&pool, &world, player_id, scout.id, FlightState::Space).await;
+
+        let in_space = units_repo::fetch_unit(&pool, scout.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let (_, from_au) = space_point(&in_space).unwrap();
+        let fuel_before = deuterium(&pool, player_id).await;
+
+        let order = TravelOrder {
+            planet_id: None,
+            star_system_id: None,
+            position_au: Some([from_au[0] + 1.0, from_au[1], from_au[2]]),
+        };
+        let hop = order_space_travel(&pool, &world, player_id, scout.id, &order)
+            .await
+            .unwrap();
+        assert!(!hop.jump);
+        assert!(hop.deuterium > 0.0);
+        assert_eq!(
+            deuterium(&pool, player_id).await,
+            fuel_before - hop.deuterium
+        );
+
+        arrive_all(&pool, &world).await;
+        let arrived = units_repo::fetch_unit(&pool, scout.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(space_point(&arrived).unwrap().1, hop.to);
+    }
+}
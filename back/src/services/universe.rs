@@ -0,0 +1,5 @@
+pub mod cache;
+pub mod chunks;
+pub mod nearby;
+pub mod space_objects;
+pub mod systems;
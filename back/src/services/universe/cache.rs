@@ -0,0 +1,89 @@
+use crate::dto::star_system::StarSystemSummaryDto;
+use crate::dto::universe::GalaxySummaryDto;
+use crate::game::proc_gen::chunk::ChunkCoord;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, PoisonError};
+
+// Enough for a few clients panning around; a system chunk is at most a few hundred KB
+const GALAXY_CHUNK_CAPACITY: usize = 256;
+const STAR_SYSTEM_CHUNK_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GalaxyChunkKey {
+    pub world_id: i64,
+    pub generation_version: u32,
+    pub chunk: ChunkCoord,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StarSystemChunkKey {
+    pub world_id: i64,
+    pub generation_version: u32,
+    pub galaxy: (i32, i32, i32),
+    pub chunk: ChunkCoord,
+}
+
+/// Generated (never persisted) chunk contents, shared by every request that browses them.
+pub struct UniverseCache {
+    pub galaxies: ChunkCache<GalaxyChunkKey, Vec<GalaxySummaryDto>>,
+    pub star_systems: ChunkCache<StarSystemChunkKey, Vec<StarSystemSummaryDto>>,
+}
+
+impl UniverseCache {
+    pub fn new() -> Self {
+        Self {
+            galaxies: ChunkCache::new(GALAXY_CHUNK_CAPACITY),
+            star_systems: ChunkCache::new(STAR_SYSTEM_CHUNK_CAPACITY),
+        }
+    }
+}
+
+/// Bounded map that forgets the oldest chunk first once full.
+pub struct ChunkCache<K, V> {
+    capacity: usize,
+    inner: Mutex<CacheEntries<K, V>>,
+}
+
+struct CacheEntries<K, V> {
+    values: HashMap<K, Arc<V>>,
+    insertion_order: VecDeque<K>,
+}
+
+impl<K: Copy + Eq + Hash, V> ChunkCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(CacheEntries {
+                values: HashMap::new(),
+                insertion_order: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<Arc<V>> {
+        // Entries are only ever inserted whole, so a poisoned lock still guards valid data
+        let entries = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.values.get(key).cloned()
+    }
+
+    /// Stores `value` unless another request generated the chunk first, and returns the
+    /// cached copy either way.
+    pub fn insert(&self, key: K, value: V) -> Arc<V> {
+        let mut entries = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(existing) = entries.values.get(&key) {
+            return existing.clone();
+        }
+
+        if entries.values.len() >= self.capacity
+            && let Some(oldest) = entries.insertion_order.pop_front()
+        {
+            entries.values.remove(&oldest);
+        }
+
+        let value = Arc::new(value);
+        entries.values.insert(key, value.clone());
+        entries.insertion_order.push_back(key);
+        value
+    }
+}
@@ -0,0 +1,190 @@
+use crate::db::star_system::SystemPresenceRow;
+use crate::dto::star_system::StarSystemSummaryDto;
+use crate::dto::universe::{
+    FleetPresenceDto, GalaxyChunkDto, GalaxyEntryDto, GalaxySummaryDto, StarSystemChunkDto,
+    StarSystemEntryDto, SystemOwnerDto,
+};
+use crate::game::proc_gen::chunk::{
+    ChunkCoord, GALAXY_CHUNK_SIZE, STAR_SYSTEM_CHUNK_SIZE, galaxies_in_chunk, star_systems_in_chunk,
+};
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::universe::should_spawn_galaxy;
+use crate::game::world::World;
+use crate::repositories::{galaxies_repo, star_systems_repo};
+use crate::services::universe::cache::{GalaxyChunkKey, StarSystemChunkKey, UniverseCache};
+use anyhow::Result;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+/// Galaxies of a cosmic chunk, merged with what is persisted about them.
+/// Returns None when the chunk lies outside the coordinate grid.
+pub async fn load_galaxy_chunk(
+    pool: &SqlitePool,
+    cache: &UniverseCache,
+    world: &World,
+    player_id: i64,
+    chunk: ChunkCoord,
+) -> Result<Option<GalaxyChunkDto>> {
+    let Some((min, max)) = chunk.bounds(GALAXY_CHUNK_SIZE) else {
+        return Ok(None);
+    };
+
+    let key = GalaxyChunkKey {
+        world_id: world.id,
+        generation_version: world.generation_version,
+        chunk,
+    };
+    let generated = match cache.galaxies.get(&key) {
+        Some(hit) => hit,
+        None => {
+            let root_seed = world.root_seed();
+            let galaxies = tokio::task::spawn_blocking(move || {
+                galaxies_in_chunk(root_seed, chunk)
+                    .iter()
+                    .map(GalaxySummaryDto::from)
+                    .collect()
+            })
+            .await?;
+            cache.galaxies.insert(key, galaxies)
+        }
+    };
+
+    let mut persisted: HashMap<_, _> =
+        galaxies_repo::fetch_galaxies_in_box(pool, world.id, player_id, min, max)
+            .await?
+            .into_iter()
+            .map(|row| ((row.x, row.y, row.z), row))
+            .collect();
+
+    let galaxies = generated
+        .iter()
+        .map(|galaxy| {
+            let row = persisted.remove(&(galaxy.x, galaxy.y, galaxy.z));
+            GalaxyEntryDto {
+                galaxy: galaxy.clone(),
+                id: row.as_ref().map(|row| row.id),
+                has_presence: row.is_some_and(|row| row.has_presence),
+            }
+        })
+        .collect();
+
+    Ok(Some(GalaxyChunkDto {
+        world_id: world.id,
+        generation_version: world.generation_version,
+        chunk: [chunk.x, chunk.y, chunk.z],
+        chunk_size: GALAXY_CHUNK_SIZE,
+        galaxies,
+    }))
+}
+
+/// Star systems of a chunk of one galaxy's grid, merged with the players' footprint there.
+/// Returns None when no galaxy exists at `galaxy_pos` or the chunk lies outside the grid.
+pub async fn load_star_system_chunk(
+    pool: &SqlitePool,
+    cache: &UniverseCache,
+    world: &World,
+    player_id: i64,
+    galaxy_pos: (i32, i32, i32),
+    chunk: ChunkCoord,
+) -> Result<Option<StarSystemChunkDto>> {
+    let root_seed = world.root_seed();
+    let Some((min, max)) = chunk.bounds(STAR_SYSTEM_CHUNK_SIZE) else {
+        return Ok(None);
+    };
+    if !should_spawn_galaxy(root_seed, galaxy_pos) {
+        return Ok(None);
+    }
+
+    let key = StarSystemChunkKey {
+        world_id: world.id,
+        generation_version: world.generation_version,
+        galaxy: galaxy_pos,
+        chunk,
+    };
+    let generated = match cache.star_systems.get(&key) {
+        Some(hit) => hit,
+        None => {
+            let galaxy = Galaxy::new(root_seed, galaxy_pos);
+            let systems = tokio::task::spawn_blocking(move || {
+                star_systems_in_chunk(&galaxy, chunk)
+                    .iter()
+                    .map(StarSystemSummaryDto::from)
+                    .collect()
+            })
+            .await?;
+            cache.star_systems.insert(key, systems)
+        }
+    };
+
+    let presence = match galaxies_repo::fetch_galaxy_id(pool, world.id, galaxy_pos).await? {
+        Some(galaxy_id) => {
+            star_systems_repo::fetch_system_presence_in_box(pool, galaxy_id, min, max).await?
+        }
+        None => Vec::new(),
+    };
+    let mut presence_by_cell: HashMap<_, Vec<_>> = HashMap::new();
+    for row in presence {
+        presence_by_cell
+            .entry((row.x, row.y, row.z))
+            .or_default()
+            .push(row);
+    }
+
+    let systems = generated
+        .iter()
+        .map(|system| {
+            let rows = presence_by_cell
+                .remove(&(system.x, system.y, system.z))
+                .unwrap_or_default();
+            star_system_entry(system.clone(), &rows, player_id)
+        })
+        .collect();
+
+    Ok(Some(StarSystemChunkDto {
+        world_id: world.id,
+        generation_version: world.generation_version,
+        galaxy: [galaxy_pos.0, galaxy_pos.1, galaxy_pos.2],
+        chunk: [chunk.x, chunk.y, chunk.z],
+        chunk_size: STAR_SYSTEM_CHUNK_SIZE,
+        systems,
+    }))
+}
+
+fn star_system_entry(
+    system: StarSystemSummaryDto,
+    rows: &[SystemPresenceRow],
+    player_id: i64,
+) -> StarSystemEntryDto {
+    // Settlements are public knowledge, fleets are only seen by players with a foothold there
+    let has_presence = rows.iter().any(|row| row.player_id == Some(player_id));
+
+    let owners = rows
+        .iter()
+        .filter(|row| row.owned_tiles > 0 || row.buildings > 0)
+        .filter_map(|row| {
+            Some(SystemOwnerDto {
+                player_id: row.player_id?,
+                owned_tiles: row.owned_tiles,
+                buildings: row.buildings,
+            })
+        })
+        .collect();
+
+    let fleets = rows
+        .iter()
+        .filter(|row| has_presence && row.fleet_units > 0)
+        .filter_map(|row| {
+            Some(FleetPresenceDto {
+                player_id: row.player_id?,
+                unit_count: row.fleet_units,
+            })
+        })
+        .collect();
+
+    StarSystemEntryDto {
+        system,
+        id: rows.first().map(|row| row.star_system_id),
+        owners,
+        fleets,
+    }
+}
@@ -0,0 +1,49 @@
+use crate::dto::star_system::StarSystemSummaryDto;
+use crate::dto::universe::{NearbyStarSystemDto, NearbyStarSystemsDto};
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::nearby::nearest_star_systems;
+use crate::game::proc_gen::star_system::StarSystem;
+use crate::game::proc_gen::universe::should_spawn_galaxy;
+use crate::game::world::World;
+use anyhow::Result;
+
+// Bounds of a nearby systems query, keeping it cheap enough to serve per request
+pub const MAX_NEARBY_RADIUS: f32 = 128.0;
+pub const DEFAULT_NEARBY_LIMIT: usize = 20;
+pub const MAX_NEARBY_LIMIT: usize = 100;
+
+/// The `limit` star systems nearest to `center` within `radius` cells, in the galaxy at
+/// `galaxy_pos`. Returns None when no galaxy exists there.
+pub async fn load_nearby_star_systems(
+    world: &World,
+    galaxy_pos: (i32, i32, i32),
+    center: (i32, i32, i32),
+    radius: f32,
+    limit: usize,
+) -> Result<Option<NearbyStarSystemsDto>> {
+    let root_seed = world.root_seed();
+    if !should_spawn_galaxy(root_seed, galaxy_pos) {
+        return Ok(None);
+    }
+
+    let galaxy = Galaxy::new(root_seed, galaxy_pos);
+    let systems = tokio::task::spawn_blocking(move || {
+        nearest_star_systems(&galaxy, center, radius, limit)
+            .into_iter()
+            .map(|nearby| NearbyStarSystemDto {
+                system: StarSystemSummaryDto::from(&StarSystem::new(&galaxy, nearby.position)),
+                distance: nearby.distance,
+            })
+            .collect()
+    })
+    .await?;
+
+    Ok(Some(NearbyStarSystemsDto {
+        world_id: world.id,
+        generation_version: world.generation_version,
+        galaxy: [galaxy_pos.0, galaxy_pos.1, galaxy_pos.2],
+        center: [center.0, center.1, center.2],
+        radius,
+        systems,
+    }))
+}
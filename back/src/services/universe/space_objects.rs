@@ -0,0 +1,199 @@
+use crate::db::space_object::{NewAsteroidExtraction, SpaceObjectStateRow};
+use crate::dto::space_object::{
+    AnomalyDto, AsteroidDto, AsteroidExtractionDto, SystemSpaceObjectsDto,
+};
+use crate::error::ApiError;
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::space_object::SpaceObjects;
+use crate::game::proc_gen::star_system::StarSystem;
+use crate::game::proc_gen::universe::should_spawn_galaxy;
+use crate::game::travel;
+use crate::game::unit_stats::UnitStats;
+use crate::game::world::World;
+use crate::repositories::{
+    galaxies_repo, move_orders_repo, resources_repo, space_objects_repo, star_systems_repo,
+    units_repo,
+};
+use crate::services::{movement, travel as space_travel};
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+// A ship mines an asteroid from at most this far: fly to its position first
+const EXTRACTION_RANGE_AU: f64 = 0.05;
+
+/// Asteroids and anomalies of the system at `system_pos` in the galaxy at `galaxy_pos`.
+/// Returns None when either does not exist.
+pub async fn load_system_space_objects(
+    pool: &SqlitePool,
+    world: &World,
+    galaxy_pos: (i32, i32, i32),
+    system_pos: (i32, i32, i32),
+) -> Result<Option<SystemSpaceObjectsDto>> {
+    let root_seed = world.root_seed();
+    if !should_spawn_galaxy(root_seed, galaxy_pos) {
+        return Ok(None);
+    }
+    let galaxy = Galaxy::new(root_seed, galaxy_pos);
+    if !galaxy.should_spawn_star_system(system_pos) {
+        return Ok(None);
+    }
+
+    let objects = tokio::task::spawn_blocking(move || {
+        let system = StarSystem::new(&galaxy, system_pos);
+        SpaceObjects::generate(&system)
+    })
+    .await?;
+
+    let star_system_id = match galaxies_repo::fetch_galaxy_id(pool, world.id, galaxy_pos).await? {
+        Some(galaxy_id) => {
+            star_systems_repo::fetch_star_system_id(pool, galaxy_id, system_pos).await?
+        }
+        None => None,
+    };
+    let states: HashMap<(String, i64), SpaceObjectStateRow> = match star_system_id {
+        Some(id) => space_objects_repo::fetch_generated_object_states(pool, id)
+            .await?
+            .into_iter()
+            .map(|row| ((row.object_type.clone(), row.object_index), row))
+            .collect(),
+        None => HashMap::new(),
+    };
+    let state_of =
+        |object_type: &str, index: u32| states.get(&(object_type.to_string(), index as i64));
+
+    // Mined-out asteroids and resolved anomalies are despawned for good
+    let asteroids = objects
+        .asteroids
+        .iter()
+        .filter_map(|asteroid| match state_of("asteroid", asteroid.index) {
+            None => Some(AsteroidDto::new(asteroid, 0.0, None)),
+            Some(state) if state.despawned_at.is_none() => Some(AsteroidDto::new(
+                asteroid,
+                state.extracted,
+                state.owner_player_id,
+            )),
+            Some(_) => None,
+        })
+        .collect();
+
+    let anomalies = objects
+        .anomalies
+        .iter()
+        .filter(|anomaly| {
+            state_of("anomaly", anomaly.index).is_none_or(|state| state.despawned_at.is_none())
+        })
+        .map(AnomalyDto::from)
+        .collect();
+
+    Ok(Some(SystemSpaceObjectsDto {
+        galaxy: [galaxy_pos.0, galaxy_pos.1, galaxy_pos.2],
+        system: [system_pos.0, system_pos.1, system_pos.2],
+        star_system_id,
+        asteroids,
+        anomalies,
+    }))
+}
+
+/// Mines asteroid `asteroid_index` of the system a unit of `player_id` is in, as much as the
+/// unit can haul, into the player's pool. The player becomes the asteroid's owner, and a
+/// mined-out asteroid despawns for good.
+pub async fn extract_asteroid(
+    pool: &SqlitePool,
+    world: &World,
+    player_id: i64,
+    unit_id: i64,
+    asteroid_index: u32,
+) -> Result<AsteroidExtractionDto, ApiError> {
+    let unit = movement::fetch_owned_unit(pool, player_id, unit_id).await?;
+    let (star_system_id, position_au) = space_travel::space_point(&unit)?;
+    if unit.in_battle != 0 {
+        return Err(ApiError::Conflict("Unit is in battle"));
+    }
+    let capacity = UnitStats::for_type(&unit.unit_type).cargo_capacity * unit.count as f64;
+    if capacity <= 0.0 {
+        return Err(ApiError::BadRequest("Unit cannot mine"));
+    }
+
+    let cell = star_systems_repo::fetch_star_system_cell(pool, world.id, star_system_id)
+        .await?
+        .with_context(|| format!("star system {star_system_id} is not in this world"))?;
+    let root_seed = world.root_seed();
+    let (system, objects) = tokio::task::spawn_blocking(move || {
+        let galaxy = Galaxy::new(root_seed, (cell.galaxy_x, cell.galaxy_y, cell.galaxy_z));
+        let system = StarSystem::new(&galaxy, (cell.x, cell.y, cell.z));
+        let objects = SpaceObjects::generate(&system);
+        (system, objects)
+    })
+    .await
+    .map_err(anyhow::Error::from)?;
+    let asteroid = objects
+        .asteroids
+        .into_iter()
+        .find(|asteroid| asteroid.index == asteroid_index)
+        .ok_or(ApiError::NotFound("Asteroid not found"))?;
+
+    let host_au = system.host_position_au(asteroid.host);
+    let asteroid_au = asteroid.position_au();
+    let asteroid_au = [0, 1, 2].map(|axis| f64::from(host_au[axis] + asteroid_au[axis]));
+    if travel::distance(position_au, asteroid_au) > EXTRACTION_RANGE_AU {
+        return Err(ApiError::BadRequest("Asteroid is out of reach"));
+    }
+
+    let state = space_objects_repo::fetch_generated_object_states(pool, star_system_id)
+        .await?
+        .into_iter()
+        .find(|row| row.object_type == "asteroid" && row.object_index == asteroid_index as i64);
+    if state
+        .as_ref()
+        .is_some_and(|state| state.despawned_at.is_some())
+    {
+        return Err(ApiError::Conflict("Asteroid is mined out"));
+    }
+    let extracted_before = state.map_or(0.0, |state| state.extracted);
+    let remaining = asteroid.remaining_yield(extracted_before);
+    if remaining <= 0.0 {
+        return Err(ApiError::Conflict("Asteroid is mined out"));
+    }
+
+    let mut tx = pool.begin().await?;
+    // Writing first takes SQLite's write lock, so the checks below cannot race another order.
+    // The haul is cut to the room left, so that the asteroid only loses what is stored
+    let room = resources_repo::fetch_free_storage(&mut tx, player_id, asteroid.composition).await?;
+    let amount = remaining.min(capacity).min(room);
+    if amount <= 0.0 {
+        return Err(ApiError::BadRequest("Storage is full"));
+    }
+
+    let extraction = NewAsteroidExtraction {
+        star_system_id,
+        asteroid_index,
+        player_id,
+        extracted_before,
+        amount,
+        mined_out: amount >= remaining,
+    };
+    if !space_objects_repo::record_asteroid_extraction(&mut tx, &extraction).await? {
+        return Err(ApiError::Conflict("Asteroid was mined meanwhile"));
+    }
+
+    let current = units_repo::fetch_unit(&mut *tx, unit_id)
+        .await?
+        .ok_or(ApiError::NotFound("Unit not found"))?;
+    if space_travel::space_point(&current)? != (star_system_id, position_au) {
+        return Err(ApiError::Conflict("Unit moved meanwhile"));
+    }
+    if move_orders_repo::count_unit_move_orders(&mut tx, unit_id).await? > 0 {
+        return Err(ApiError::Conflict("Unit is moving"));
+    }
+    resources_repo::add_player_resource(&mut tx, player_id, asteroid.composition, amount).await?;
+    tx.commit().await?;
+
+    Ok(AsteroidExtractionDto {
+        unit_id,
+        star_system_id,
+        resource: asteroid.composition.as_str(),
+        amount,
+        asteroid: AsteroidDto::new(&asteroid, extracted_before + amount, Some(player_id)),
+    })
+}
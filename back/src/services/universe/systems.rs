@@ -0,0 +1,43 @@
+use crate::dto::star_system::StarSystemDto;
+use crate::game::proc_gen::galaxy::Galaxy;
+use crate::game::proc_gen::star_system::StarSystem;
+use crate::game::proc_gen::universe::should_spawn_galaxy;
+use crate::game::world::World;
+use crate::repositories::{galaxies_repo, star_systems_repo};
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// The system at `system_pos` in the galaxy at `galaxy_pos`, its bodies placed on their orbits
+/// as of now. Returns None when either does not exist.
+pub async fn load_star_system(
+    pool: &SqlitePool,
+    world: &World,
+    galaxy_pos: (i32, i32, i32),
+    system_pos: (i32, i32, i32),
+) -> Result<Option<StarSystemDto>> {
+    let root_seed = world.root_seed();
+    if !should_spawn_galaxy(root_seed, galaxy_pos) {
+        return Ok(None);
+    }
+    let galaxy = Galaxy::new(root_seed, galaxy_pos);
+    if !galaxy.should_spawn_star_system(system_pos) {
+        return Ok(None);
+    }
+
+    let system = tokio::task::spawn_blocking(move || StarSystem::new(&galaxy, system_pos)).await?;
+
+    let star_system_id = match galaxies_repo::fetch_galaxy_id(pool, world.id, galaxy_pos).await? {
+        Some(galaxy_id) => {
+            star_systems_repo::fetch_star_system_id(pool, galaxy_id, system_pos).await?
+        }
+        None => None,
+    };
+
+    Ok(Some(StarSystemDto::new(
+        galaxy_pos,
+        &system,
+        star_system_id,
+        world.game_time_s(Utc::now()),
+    )))
+}
@@ -0,0 +1,4 @@
+pub mod arrivals;
+pub mod battles;
+pub mod construction;
+pub mod runner;
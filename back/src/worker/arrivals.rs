@@ -1,8 +1,129 @@
-// pub async fn process(
-//     order: MoveOrder,
-//     state: Arc<AppState>,
-// ) -> anyhow::Result<()> {
-//     db::queries::apply_movement(&state.db, &order).await?;
-//     domain::encounter::check_and_emit(&state.db, &state.notify, &order).await?;
-//     Ok(())
-// }
\ No newline at end of file
+use crate::db::move_order::MoveOrderRow;
+use crate::dto::event::GameEventDto;
+use crate::repositories::{buildings_repo, move_orders_repo, units_repo};
+use anyhow::{Context, Result, bail};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// Moves the mover of `order` to its destination and deletes the order, in one transaction.
+/// Returns None if the order was already applied.
+pub async fn apply_arrival(
+    pool: &SqlitePool,
+    order: &MoveOrderRow,
+) -> Result<Option<GameEventDto>> {
+    let mut tx = pool.begin().await?;
+    if !move_orders_repo::claim_move_order(&mut tx, order.id).await? {
+        return Ok(None);
+    }
+
+    // A unit caught in a battle on the way stops where it stands, with the rest of its route
+    if let (Some(route_id), Some(unit_id)) = (order.route_id, order.unit_id)
+        && units_repo::is_unit_in_battle(&mut tx, unit_id).await?
+    {
+        move_orders_repo::delete_move_route(&mut tx, route_id).await?;
+        tx.commit().await?;
+        return Ok(Some(GameEventDto::MoveHalted {
+            player_id: order.player_id,
+            unit_id,
+            route_id,
+        }));
+    }
+
+    match (order.mover_type.as_str(), order.unit_id, order.building_id) {
+        ("unit", Some(unit_id), _) => move_unit(&mut tx, unit_id, order).await?,
+        ("building", _, Some(building_id)) => move_building(&mut tx, building_id, order).await?,
+        (mover_type, _, _) => bail!("move order {} has no {mover_type} to move", order.id),
+    }
+
+    if let Some(route_id) = order.route_id {
+        move_orders_repo::delete_move_route_if_walked(&mut tx, route_id).await?;
+    }
+
+    tx.commit().await?;
+    Ok(Some(GameEventDto::MoveArrived {
+        player_id: order.player_id,
+        order_id: order.id,
+        unit_id: order.unit_id,
+        building_id: order.building_id,
+        move_type: order.move_type.clone(),
+        route_id: order.route_id,
+    }))
+}
+
+/// Drops an order that keeps failing, with the rest of its route: the mover stays where it is.
+pub async fn abandon_arrival(pool: &SqlitePool, order: &MoveOrderRow) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    move_orders_repo::claim_move_order(&mut tx, order.id).await?;
+    if let Some(route_id) = order.route_id {
+        move_orders_repo::delete_move_route(&mut tx, route_id).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn move_unit(
+    tx: &mut Transaction<'_, Sqlite>,
+    unit_id: i64,
+    order: &MoveOrderRow,
+) -> Result<()> {
+    match order.move_type.as_str() {
+        // Looting needs combat rules that do not exist yet: a raid only walks the unit there
+        "tile_walk" | "land" | "loot_and_retreat" => {
+            let (planet_id, face, u, v) = surface_destination(order)?;
+            units_repo::place_unit_on_surface(tx, unit_id, planet_id, face, u, v).await
+        }
+        "launch_to_orbit" | "lower_orbit" => {
+            let planet_id = orbited_planet(order)?;
+            units_repo::place_unit_in_orbit(tx, unit_id, planet_id, "low").await
+        }
+        "raise_orbit" | "enter_orbit" => {
+            let planet_id = orbited_planet(order)?;
+            units_repo::place_unit_in_orbit(tx, unit_id, planet_id, "high").await
+        }
+        "orbit_to_space" | "space_travel" => {
+            let star_system_id = order
+                .to_star_system_id
+                .or(order.from_star_system_id)
+                .with_context(|| format!("move order {} has no star system", order.id))?;
+            let position = match (order.to_space_x, order.to_space_y) {
+                (Some(x), Some(y)) => [x, y, order.to_space_z.unwrap_or(0.0)],
+                _ => bail!("move order {} has no space destination", order.id),
+            };
+            units_repo::place_unit_in_space(tx, unit_id, star_system_id, position).await
+        }
+        other => bail!("move type '{other}' does not apply to units"),
+    }
+}
+
+async fn move_building(
+    tx: &mut Transaction<'_, Sqlite>,
+    building_id: i64,
+    order: &MoveOrderRow,
+) -> Result<()> {
+    match order.move_type.as_str() {
+        "building_liftoff" => buildings_repo::set_flight_state(tx, building_id, "flying").await,
+        "building_land" => {
+            let (planet_id, face, u, v) = surface_destination(order)?;
+            buildings_repo::land_building(tx, building_id, planet_id, face, u, v).await
+        }
+        other => bail!("move type '{other}' does not apply to buildings"),
+    }
+}
+
+fn orbited_planet(order: &MoveOrderRow) -> Result<i64> {
+    order
+        .to_planet_id
+        .or(order.from_planet_id)
+        .with_context(|| format!("move order {} has no planet to orbit", order.id))
+}
+
+fn surface_destination(order: &MoveOrderRow) -> Result<(i64, i32, i32, i32)> {
+    match (
+        order.to_planet_id,
+        order.to_planet_face,
+        order.to_planet_u,
+        order.to_planet_v,
+    ) {
+        (Some(planet_id), Some(face), Some(u), Some(v)) => Ok((planet_id, face, u, v)),
+        _ => bail!("move order {} has no surface destination", order.id),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::db::battle::BattleTickRow;
+use crate::dto::event::GameEventDto;
+use crate::repositories::battles_repo;
+use anyhow::Result;
+use sqlx::SqlitePool;
+
+// Rounds of a battle are this many seconds apart
+pub const BATTLE_TICK_SECONDS: i64 = 60;
+// How a battle ends while there is no combat to decide it
+const UNFOUGHT_OUTCOME: &str = "draw";
+
+/// SQLite date modifier between two rounds.
+pub fn tick_interval() -> String {
+    format!("+{BATTLE_TICK_SECONDS} seconds")
+}
+
+/// Plays the due round of `battle`. Returns None if it was already played.
+///
+/// Combat itself (damage, losses, retreats) is not modelled yet, so the first round ends the
+/// battle as a draw and frees both sides' units; a battle never outlives its first round. Its
+/// resolution will run in this transaction.
+pub async fn play_battle_tick(
+    pool: &SqlitePool,
+    battle: &BattleTickRow,
+) -> Result<Option<GameEventDto>> {
+    let mut tx = pool.begin().await?;
+    if !battles_repo::claim_battle_tick(&mut tx, battle).await? {
+        return Ok(None);
+    }
+    if !battles_repo::end_battle(&mut tx, battle.id, UNFOUGHT_OUTCOME).await? {
+        return Ok(None);
+    }
+
+    tx.commit().await?;
+    Ok(Some(GameEventDto::BattleEnded {
+        battle_id: battle.id,
+        attacker_id: battle.attacker_id,
+        defender_id: battle.defender_id,
+        outcome: UNFOUGHT_OUTCOME.to_string(),
+        ended_at: battle.tick_at.clone(),
+    }))
+}
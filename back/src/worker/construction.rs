@@ -0,0 +1,24 @@
+use crate::db::building::DueConstructionRow;
+use crate::dto::event::GameEventDto;
+use crate::repositories::buildings_repo;
+use anyhow::Result;
+use sqlx::SqlitePool;
+
+/// Finishes the construction of `building`. Returns None if it was already finished.
+pub async fn complete_construction(
+    pool: &SqlitePool,
+    building: &DueConstructionRow,
+) -> Result<Option<GameEventDto>> {
+    let mut tx = pool.begin().await?;
+    if !buildings_repo::claim_construction(&mut tx, building.id, &building.construction_done_at)
+        .await?
+    {
+        return Ok(None);
+    }
+
+    tx.commit().await?;
+    Ok(Some(GameEventDto::ConstructionCompleted {
+        player_id: building.player_id,
+        building_id: building.id,
+    }))
+}
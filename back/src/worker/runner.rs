@@ -1,12 +1,533 @@
-// pub async fn run(state: Arc<AppState>) {
-//     loop {
-//         let orders = db::queries::due_orders(&state.db).await;
-//         for order in orders {
-//             let state = state.clone();
-//             tokio::spawn(async move {
-//                 let _ = arrivals::process(order, state).await;
-//             });
-//         }
-//         tokio::time::sleep(Duration::from_secs(1)).await;
-//     }
-// }
\ No newline at end of file
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use tokio::sync::Notify;
+
+use crate::app::AppState;
+use crate::db::battle::BattleTickRow;
+use crate::db::building::DueConstructionRow;
+use crate::db::move_order::MoveOrderRow;
+use crate::dto::event::GameEventDto;
+use crate::repositories::{battles_repo, buildings_repo, move_orders_repo};
+use crate::worker::{arrivals, battles, construction};
+
+// Due rows fetched per source and pass
+const BATCH_SIZE: i64 = 64;
+// An event that fails is retried this much later rather than blocking the ones behind it
+const RETRY_DELAY: TimeDelta = TimeDelta::seconds(30);
+// A move order failing this many times will not succeed on retry: it is dropped
+const MAX_ARRIVAL_ATTEMPTS: i64 = 10;
+// Rows written by another process sharing the database cannot wake us: look at least this often
+const MAX_IDLE: Duration = Duration::from_secs(300);
+
+/// Wakes the worker when something is scheduled earlier than what it sleeps until.
+#[derive(Default)]
+pub struct Scheduler {
+    wake: Notify,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            wake: Notify::new(),
+        }
+    }
+
+    /// Makes the worker re-read the next due time. Call after committing a new move order,
+    /// construction or battle.
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+enum DueEvent {
+    Arrival(Box<MoveOrderRow>),
+    Construction(DueConstructionRow),
+    BattleTick(BattleTickRow),
+}
+
+/// Resolves due events of the served world, forever.
+///
+/// State lives in the database only: every event is claimed and applied in one transaction, so
+/// a restart resumes with whatever is still due and an event applied before it is not applied
+/// again. Between passes the worker sleeps until the next due time, or until woken.
+pub async fn run(state: Arc<AppState>) {
+    tracing::info!("worker started");
+
+    loop {
+        let next_due = match process_due_events(&state).await {
+            Ok(()) => next_due_at(&state).await,
+            Err(e) => Err(e),
+        };
+        let wake_at = next_due.unwrap_or_else(|e| {
+            tracing::error!("worker failed: {:?}", e);
+            Some(Utc::now() + RETRY_DELAY)
+        });
+
+        let idle = wake_at.map_or(MAX_IDLE, |at| {
+            (at - Utc::now())
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+                .min(MAX_IDLE)
+        });
+        tokio::select! {
+            _ = tokio::time::sleep(idle) => {}
+            _ = state.scheduler.wake.notified() => {}
+        }
+    }
+}
+
+/// Applies everything due by now, earliest first, until nothing is left.
+async fn process_due_events(state: &AppState) -> Result<()> {
+    loop {
+        let now = Utc::now();
+        let events = fetch_due_events(state, now).await?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        for (_, event) in events {
+            match apply(state, &event).await {
+                Ok(Some(notification)) => {
+                    state.events.publish(&notification)?;
+                }
+                // Already applied, by an earlier pass or another process
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("{}: {:#}", describe(&event), e);
+                    postpone(state, &event, now + RETRY_DELAY).await?;
+                }
+            }
+        }
+    }
+}
+
+async fn fetch_due_events(
+    state: &AppState,
+    now: DateTime<Utc>,
+) -> Result<Vec<(DateTime<Utc>, DueEvent)>> {
+    let world_id = state.world.id;
+    let now_sql = sql_timestamp(now);
+    let mut events = Vec::new();
+
+    let orders = move_orders_repo::fetch_due_move_orders(
+        &state.db,
+        world_id,
+        now.timestamp_millis(),
+        BATCH_SIZE,
+    )
+    .await?;
+    for order in orders {
+        let due_at = millis_timestamp(order.arrival_time);
+        push_due(
+            state,
+            &mut events,
+            due_at,
+            DueEvent::Arrival(Box::new(order)),
+            now,
+        )
+        .await?;
+    }
+
+    let buildings =
+        buildings_repo::fetch_due_constructions(&state.db, world_id, &now_sql, BATCH_SIZE).await?;
+    for building in buildings {
+        let due_at = parse_sql_timestamp(&building.construction_done_at);
+        push_due(
+            state,
+            &mut events,
+            due_at,
+            DueEvent::Construction(building),
+            now,
+        )
+        .await?;
+    }
+
+    let ticks = battles_repo::fetch_due_battle_ticks(
+        &state.db,
+        world_id,
+        &battles::tick_interval(),
+        &now_sql,
+        BATCH_SIZE,
+    )
+    .await?;
+    for battle in ticks {
+        let due_at = parse_sql_timestamp(&battle.tick_at);
+        push_due(
+            state,
+            &mut events,
+            due_at,
+            DueEvent::BattleTick(battle),
+            now,
+        )
+        .await?;
+    }
+
+    events.sort_by_key(|(due_at, _)| *due_at);
+    Ok(events)
+}
+
+/// Queues `event`, or postpones it if its due time does not parse so that one bad row does not
+/// hold back the others.
+async fn push_due(
+    state: &AppState,
+    events: &mut Vec<(DateTime<Utc>, DueEvent)>,
+    due_at: Result<DateTime<Utc>>,
+    event: DueEvent,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    match due_at {
+        Ok(due_at) => events.push((due_at, event)),
+        Err(e) => {
+            tracing::warn!("{}: {:#}", describe(&event), e);
+            postpone(state, &event, now + RETRY_DELAY).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn next_due_at(state: &AppState) -> Result<Option<DateTime<Utc>>> {
+    let world_id = state.world.id;
+
+    let arrival = move_orders_repo::fetch_next_arrival_time(&state.db, world_id)
+        .await?
+        .map(millis_timestamp)
+        .transpose()?;
+    let construction = buildings_repo::fetch_next_construction_done_at(&state.db, world_id)
+        .await?
+        .and_then(|at| schedulable(&at));
+    let battle_tick =
+        battles_repo::fetch_next_battle_tick_at(&state.db, world_id, &battles::tick_interval())
+            .await?
+            .and_then(|at| schedulable(&at));
+
+    Ok([arrival, construction, battle_tick]
+        .into_iter()
+        .flatten()
+        .min())
+}
+
+// A time that does not parse cannot be slept until: the worker falls back to MAX_IDLE for it
+fn schedulable(at: &str) -> Option<DateTime<Utc>> {
+    parse_sql_timestamp(at)
+        .inspect_err(|e| tracing::warn!("next due event not scheduled: {:#}", e))
+        .ok()
+}
+
+async fn apply(state: &AppState, event: &DueEvent) -> Result<Option<GameEventDto>> {
+    match event {
+        DueEvent::Arrival(order) => arrivals::apply_arrival(&state.db, order).await,
+        DueEvent::Construction(building) => {
+            construction::complete_construction(&state.db, building).await
+        }
+        DueEvent::BattleTick(battle) => battles::play_battle_tick(&state.db, battle).await,
+    }
+}
+
+async fn postpone(state: &AppState, event: &DueEvent, until: DateTime<Utc>) -> Result<()> {
+    match event {
+        DueEvent::Arrival(order) if order.attempts + 1 >= MAX_ARRIVAL_ATTEMPTS => {
+            tracing::error!(
+                "move order {} dropped after {} failed attempts",
+                order.id,
+                order.attempts + 1
+            );
+            arrivals::abandon_arrival(&state.db, order).await
+        }
+        DueEvent::Arrival(order) => {
+            move_orders_repo::postpone_move_order(&state.db, order.id, until.timestamp_millis())
+                .await
+        }
+        DueEvent::Construction(building) => {
+            buildings_repo::postpone_construction(
+                &state.db,
+                building.id,
+                &building.construction_done_at,
+                &sql_timestamp(until),
+            )
+            .await
+        }
+        DueEvent::BattleTick(battle) => {
+            battles_repo::postpone_battle_tick(&state.db, battle, &sql_timestamp(until)).await
+        }
+    }
+}
+
+fn describe(event: &DueEvent) -> String {
+    match event {
+        DueEvent::Arrival(order) => format!("move order {} failed", order.id),
+        DueEvent::Construction(building) => {
+            format!("construction of building {} failed", building.id)
+        }
+        DueEvent::BattleTick(battle) => format!("round of battle {} failed", battle.id),
+    }
+}
+
+// Timestamps are stored like SQLite's strftime('%Y-%m-%dT%H:%M:%fZ'), which sorts as text
+fn sql_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn parse_sql_timestamp(at: &str) -> Result<DateTime<Utc>> {
+    let at = DateTime::parse_from_rfc3339(at).with_context(|| format!("bad timestamp '{at}'"))?;
+    Ok(at.with_timezone(&Utc))
+}
+
+fn millis_timestamp(ms: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(ms).with_context(|| format!("bad timestamp {ms} ms"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::move_order::NewUnitMoveOrder;
+    use crate::db::test_db;
+    use crate::db::unit::UnitRow;
+    use crate::game::start_area::FairnessBand;
+    use crate::repositories::units_repo;
+    use crate::services::events::EventHub;
+    use crate::services::map::spawn;
+    use crate::services::universe::cache::UniverseCache;
+
+    async fn test_state() -> AppState {
+        let db = test_db::memory_pool().await;
+        let world = test_db::create_world(&db).await;
+        AppState {
+            db,
+            events: EventHub::new(),
+            world,
+            universe_cache: UniverseCache::new(),
+            spawn_band: FairnessBand::default(),
+            scheduler: Scheduler::new(),
+        }
+    }
+
+    /// The scout of a new player, standing on its home planet.
+    async fn spawned_scout(state: &AppState) -> UnitRow {
+        let player_id = test_db::create_player(&state.db, &state.world, "worker").await;
+        spawn::load_or_initialize_player(&state.db, &state.world, &state.spawn_band, player_id)
+            .await
+            .unwrap();
+        units_repo::fetch_player_units(&state.db, player_id)
+            .await
+            .unwrap()
+            .remove(0)
+    }
+
+    async fn insert_order(state: &AppState, unit: &UnitRow, move_type: &'static str) -> i64 {
+        let mut tx = state.db.begin().await.unwrap();
+        let order_id = move_orders_repo::insert_unit_move_order(
+            &mut tx,
+            &NewUnitMoveOrder {
+                unit_id: unit.id,
+                move_type,
+                from_planet_id: unit.planet_id,
+                from_star_system_id: None,
+                from_space: None,
+                to_planet_id: unit.planet_id,
+                to_tile: None,
+                to_star_system_id: None,
+                to_space: None,
+                start_time: 0,
+                arrival_time: 0,
+            },
+        )
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+        order_id
+    }
+
+    async fn pending_order(state: &AppState, order_id: i64) -> Option<MoveOrderRow> {
+        move_orders_repo::fetch_due_move_orders(&state.db, state.world.id, i64::MAX, BATCH_SIZE)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|order| order.id == order_id)
+    }
+
+    #[tokio::test]
+    async fn arrival_is_applied_once() {
+        let state = test_state().await;
+        let scout = spawned_scout(&state).await;
+        let order_id = insert_order(&state, &scout, "launch_to_orbit").await;
+        let order = pending_order(&state, order_id).await.unwrap();
+
+        assert!(
+            arrivals::apply_arrival(&state.db, &order)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            arrivals::apply_arrival(&state.db, &order)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let unit = units_repo::fetch_unit(&state.db, scout.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unit.location_mode, "in_orbit");
+        assert_eq!(unit.orbit_layer.as_deref(), Some("low"));
+    }
+
+    #[tokio::test]
+    async fn failing_order_is_retried_then_dropped() {
+        let state = test_state().await;
+        let scout = spawned_scout(&state).await;
+        // A walk without a destination tile can never be applied
+        let order_id = insert_order(&state, &scout, "tile_walk").await;
+
+        let before = Utc::now();
+        process_due_events(&state).await.unwrap();
+        let after = Utc::now();
+
+        let order = pending_order(&state, order_id).await.unwrap();
+        assert_eq!(order.attempts, 1);
+        let retry_delay_ms = RETRY_DELAY.num_milliseconds();
+        assert!(
+            (before.timestamp_millis() + retry_delay_ms
+                ..=after.timestamp_millis() + retry_delay_ms)
+                .contains(&order.arrival_time)
+        );
+
+        // The last attempt fails too
+        sqlx::query("UPDATE move_orders SET arrival_time = 0, attempts = ? WHERE id = ?")
+            .bind(MAX_ARRIVAL_ATTEMPTS - 1)
+            .bind(order_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        process_due_events(&state).await.unwrap();
+
+        assert!(pending_order(&state, order_id).await.is_none());
+        let unit = units_repo::fetch_unit(&state.db, scout.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unit.location_mode, "planet_surface");
+    }
+
+    #[tokio::test]
+    async fn bad_construction_time_does_not_block_the_next() {
+        let state = test_state().await;
+        let scout = spawned_scout(&state).await;
+
+        // The home's hub, finishing at a time that sorts first but does not parse, and a second
+        // building next to it finishing at a good time
+        let hub_id: i64 = sqlx::query_scalar("SELECT id FROM buildings WHERE player_id = ?")
+            .bind(scout.player_id)
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+        let tile_id = sqlx::query(
+            "INSERT INTO planet_tiles (planet_id, face, u, v, tile_type)
+             SELECT planet_id, face, u + 1, v, 'plains' FROM planet_tiles WHERE owner_player_id = ?",
+        )
+        .bind(scout.player_id)
+        .execute(&state.db)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let mut tx = state.db.begin().await.unwrap();
+        let depot_id =
+            buildings_repo::create_building(&mut tx, scout.player_id, "depot", tile_id, 100, 100)
+                .await
+                .unwrap();
+        tx.commit().await.unwrap();
+        for (building_id, done_at) in [
+            (hub_id, "1999-99-99T00:00:00.000Z"),
+            (depot_id, "2000-01-01T00:00:00.000Z"),
+        ] {
+            sqlx::query("UPDATE buildings SET construction_done_at = ? WHERE id = ?")
+                .bind(done_at)
+                .bind(building_id)
+                .execute(&state.db)
+                .await
+                .unwrap();
+        }
+
+        process_due_events(&state).await.unwrap();
+
+        let done_at = |building_id: i64| {
+            sqlx::query_scalar::<_, Option<String>>(
+                "SELECT construction_done_at FROM buildings WHERE id = ?",
+            )
+            .bind(building_id)
+            .fetch_one(&state.db)
+        };
+        assert_eq!(done_at(depot_id).await.unwrap(), None);
+        // Postponed to a time the worker can sleep until
+        let retry_at = done_at(hub_id).await.unwrap().unwrap();
+        assert!(parse_sql_timestamp(&retry_at).unwrap() > Utc::now());
+        assert!(next_due_at(&state).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn battle_ends_after_its_first_round() {
+        let state = test_state().await;
+        let scout = spawned_scout(&state).await;
+        let defender_id = test_db::create_player(&state.db, &state.world, "defender").await;
+
+        let tile_id: i64 =
+            sqlx::query_scalar("SELECT id FROM planet_tiles WHERE owner_player_id = ?")
+                .bind(scout.player_id)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        sqlx::query("UPDATE units SET in_battle = 1 WHERE id = ?")
+            .bind(scout.id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let battle_id = sqlx::query(
+            "INSERT INTO battles (tile_id, attacker_id, defender_id, started_at)
+             VALUES (?, ?, ?, '2000-01-01T00:00:00.000Z')",
+        )
+        .bind(tile_id)
+        .bind(scout.player_id)
+        .bind(defender_id)
+        .execute(&state.db)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        process_due_events(&state).await.unwrap();
+
+        let battles: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM battles")
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+        assert_eq!(battles, 0);
+        let (outcome, attacker_units): (String, String) = sqlx::query_as(
+            "SELECT outcome, attacker_units_snapshot FROM battle_reports WHERE battle_id = ?",
+        )
+        .bind(battle_id)
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(outcome, "draw");
+        assert!(attacker_units.contains("scout"), "{attacker_units}");
+
+        let unit = units_repo::fetch_unit(&state.db, scout.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unit.in_battle, 0);
+        assert_eq!(
+            battles_repo::fetch_next_battle_tick_at(
+                &state.db,
+                state.world.id,
+                &battles::tick_interval()
+            )
+            .await
+            .unwrap(),
+            None
+        );
+    }
+}